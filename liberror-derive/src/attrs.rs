@@ -0,0 +1,87 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Attribute, LitStr, Path, Token, punctuated::Punctuated};
+
+/// `#[liberror(..)]` options on the deriving type.
+#[derive(Default)]
+pub(crate) struct ContainerAttrs {
+    /// The `TagCase` expression when `tag` is present.
+    pub tag: Option<TokenStream>,
+    pub help: Option<LitStr>,
+}
+
+/// `#[liberror(..)]` options on an enum variant.
+#[derive(Default)]
+pub(crate) struct VariantAttrs {
+    pub wrap: Vec<Path>,
+    pub help: Option<LitStr>,
+}
+
+pub(crate) fn container(attrs: &[Attribute]) -> syn::Result<ContainerAttrs> {
+    let mut parsed = ContainerAttrs::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("liberror")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("tag") {
+                let mut case = quote!(::liberror::tag::TagCase::Snake);
+                if meta.input.peek(syn::token::Paren) {
+                    meta.parse_nested_meta(|meta| {
+                        if !meta.path.is_ident("case") {
+                            return Err(meta.error("unsupported tag option, expected `case`"));
+                        }
+                        case = tag_case(meta.value()?.parse()?)?;
+                        Ok(())
+                    })?;
+                }
+                parsed.tag = Some(case);
+                Ok(())
+            } else if meta.path.is_ident("help") {
+                parsed.help = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("unsupported liberror attribute"))
+            }
+        })?;
+    }
+
+    Ok(parsed)
+}
+
+pub(crate) fn variant(attrs: &[Attribute]) -> syn::Result<VariantAttrs> {
+    let mut parsed = VariantAttrs::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("liberror")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("wrap") {
+                let content;
+                syn::parenthesized!(content in meta.input);
+                let paths = Punctuated::<Path, Token![,]>::parse_terminated(&content)?;
+                parsed.wrap.extend(paths);
+                Ok(())
+            } else if meta.path.is_ident("help") {
+                parsed.help = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("unsupported liberror attribute"))
+            }
+        })?;
+    }
+
+    Ok(parsed)
+}
+
+fn tag_case(value: LitStr) -> syn::Result<TokenStream> {
+    let variant = match value.value().as_str() {
+        "snake" => "Snake",
+        "screaming_snake" => "ScreamingSnake",
+        "kebab" => "Kebab",
+        "camel" => "Camel",
+        "preserve" => "Preserve",
+        _ => {
+            return Err(syn::Error::new_spanned(
+                value,
+                "expected one of \"snake\", \"screaming_snake\", \"kebab\", \"camel\", \"preserve\"",
+            ));
+        }
+    };
+    let variant = format_ident!("{variant}");
+    Ok(quote!(::liberror::tag::TagCase::#variant))
+}
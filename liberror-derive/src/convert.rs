@@ -0,0 +1,62 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput};
+
+use crate::attrs;
+
+/// Implements `IntoAnyError` when the type has a tag or help text to apply.
+pub(crate) fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let container = attrs::container(&input.attrs)?;
+
+    let mut help_arms = Vec::new();
+    let mut variant_count = 0;
+    if let Data::Enum(data) = &input.data {
+        variant_count = data.variants.len();
+        for variant in &data.variants {
+            if let Some(help) = attrs::variant(&variant.attrs)?.help {
+                let variant_name = &variant.ident;
+                help_arms.push(
+                    quote!(Self::#variant_name { .. } => ::core::option::Option::Some(#help)),
+                );
+            }
+        }
+    }
+
+    if container.tag.is_none() && container.help.is_none() && help_arms.is_empty() {
+        return Ok(TokenStream::new());
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let fallback = match &container.help {
+        Some(help) => quote!(::core::option::Option::Some(#help)),
+        None => quote!(::core::option::Option::None),
+    };
+    let help = if help_arms.is_empty() {
+        fallback
+    } else if help_arms.len() == variant_count {
+        quote!(match &self { #(#help_arms,)* })
+    } else {
+        quote!(match &self { #(#help_arms,)* _ => #fallback, })
+    };
+
+    let convert = if container.tag.is_some() {
+        quote!(::liberror::AnyError::from_tagged(self))
+    } else {
+        quote!(::liberror::AnyError::from(self))
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::liberror::IntoAnyError for #name #ty_generics #where_clause {
+            fn into_any_error(self) -> ::liberror::AnyError {
+                let help: ::core::option::Option<&'static str> = #help;
+                let error = #convert;
+                match help {
+                    ::core::option::Option::Some(help) => error.with_help(help),
+                    ::core::option::Option::None => error,
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,31 @@
+use proc_macro::TokenStream;
+use syn::{DeriveInput, parse_macro_input};
+
+mod attrs;
+mod convert;
+mod tag;
+mod wrap;
+
+/// Generates liberror glue for an error type.
+///
+/// - A variant annotated `#[liberror(wrap(SourceA, SourceB))]` gains `From<SourceA>` and
+///   `From<SourceB>`, converting the source through `AnyError::from`.
+/// - `#[liberror(tag)]` on the type implements `TypeTag` with a dotted tag built from the
+///   module path and identifier (per variant for enums); `#[liberror(tag(case = "kebab"))]`
+///   picks the casing.
+/// - `#[liberror(help = "...")]` on the type or a variant sets the help text applied by the
+///   generated `IntoAnyError` impl, which is emitted whenever a tag or help text is present.
+#[proc_macro_derive(LibError, attributes(liberror))]
+pub fn derive_lib_error(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let mut tokens = wrap::expand(input)?;
+    tokens.extend(tag::expand(input)?);
+    tokens.extend(convert::expand(input)?);
+    Ok(tokens)
+}
@@ -0,0 +1,48 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput};
+
+use crate::attrs;
+
+pub(crate) fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let Some(case) = attrs::container(&input.attrs)?.tag else {
+        return Ok(TokenStream::new());
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let (ident, known_idents) = match &input.data {
+        Data::Enum(data) => {
+            let idents = data
+                .variants
+                .iter()
+                .map(|variant| format!("{name}::{}", variant.ident))
+                .collect::<Vec<_>>();
+            let arms = data.variants.iter().zip(&idents).map(|(variant, ident)| {
+                let variant_name = &variant.ident;
+                quote!(Self::#variant_name { .. } => #ident)
+            });
+            (quote!(match self { #(#arms,)* }), idents)
+        }
+        _ => {
+            let ident = name.to_string();
+            (quote!(#ident), vec![ident])
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::liberror::tag::TypeTag for #name #ty_generics #where_clause {
+            fn type_tag(&self) -> ::std::string::String {
+                let ident: &str = #ident;
+                ::liberror::tag::format_tag(::core::module_path!(), ident, #case)
+            }
+
+            fn known_tags() -> ::std::vec::Vec<::std::string::String> {
+                ::std::vec![
+                    #(::liberror::tag::format_tag(::core::module_path!(), #known_idents, #case),)*
+                ]
+            }
+        }
+    })
+}
@@ -0,0 +1,51 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields};
+
+use crate::attrs;
+
+pub(crate) fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let Data::Enum(data) = &input.data else {
+        return Ok(TokenStream::new());
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut impls = Vec::new();
+    for variant in &data.variants {
+        let sources = attrs::variant(&variant.attrs)?.wrap;
+        if sources.is_empty() {
+            continue;
+        }
+
+        let variant_name = &variant.ident;
+        let construct = match &variant.fields {
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                quote!(Self::#variant_name(::liberror::AnyError::from(value)))
+            }
+            Fields::Named(fields) if fields.named.len() == 1 => {
+                let field = &fields.named[0].ident;
+                quote!(Self::#variant_name { #field: ::liberror::AnyError::from(value) })
+            }
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    "#[liberror(wrap(..))] variants must have exactly one AnyError field",
+                ));
+            }
+        };
+
+        for source in sources {
+            impls.push(quote! {
+                impl #impl_generics ::core::convert::From<#source> for #name #ty_generics #where_clause {
+                    fn from(value: #source) -> Self {
+                        #construct
+                    }
+                }
+            });
+        }
+    }
+
+    Ok(quote!(#(#impls)*))
+}
@@ -0,0 +1,42 @@
+use liberror::IntoAnyError;
+use liberror_derive::LibError;
+use thiserror::Error;
+
+#[derive(Debug, Error, LibError)]
+#[liberror(help = "contact support")]
+enum StorageError {
+    #[error("disk full")]
+    #[liberror(help = "free up disk space")]
+    DiskFull,
+    #[error("permission denied on {0}")]
+    PermissionDenied(String),
+}
+
+#[derive(Debug, Error, LibError)]
+#[error("token expired")]
+#[liberror(tag, help = "log in again")]
+struct TokenExpired;
+
+#[test]
+fn test_variant_help() {
+    let error = StorageError::DiskFull.into_any_error();
+
+    assert_eq!(error.message(), "disk full");
+    assert_eq!(error.help(), Some("free up disk space"));
+}
+
+#[test]
+fn test_container_help_fallback() {
+    let error = StorageError::PermissionDenied("/var".to_string()).into_any_error();
+
+    assert_eq!(error.help(), Some("contact support"));
+}
+
+#[test]
+fn test_help_with_tag() {
+    let error = TokenExpired.into_any_error();
+
+    assert_eq!(error.r#type, "help.token_expired");
+    assert_eq!(error.help(), Some("log in again"));
+    assert!(format!("{error:#}").ends_with("help: log in again"));
+}
@@ -0,0 +1,73 @@
+use liberror::{AnyError, catalog::Catalog, tag::TypeTag};
+use liberror_derive::LibError;
+use thiserror::Error;
+
+mod services {
+    pub mod user {
+        use super::super::*;
+
+        #[derive(Debug, Error, LibError)]
+        #[liberror(tag)]
+        pub enum UserServiceError {
+            #[error("database unavailable")]
+            DatabaseError,
+            #[error("user {0} not found")]
+            NotFound(String),
+            #[error("token expired at {at}")]
+            TokenExpired { at: u64 },
+        }
+
+        #[derive(Debug, Error, LibError)]
+        #[error("rate limited")]
+        #[liberror(tag(case = "kebab"))]
+        pub struct RateLimitedError;
+    }
+}
+
+use services::user::{RateLimitedError, UserServiceError};
+
+#[test]
+fn test_enum_variant_tags() {
+    assert_eq!(
+        UserServiceError::DatabaseError.type_tag(),
+        "tag.services.user.user_service_error.database_error"
+    );
+    assert_eq!(
+        UserServiceError::NotFound("alice".to_string()).type_tag(),
+        "tag.services.user.user_service_error.not_found"
+    );
+    assert_eq!(
+        UserServiceError::TokenExpired { at: 0 }.type_tag(),
+        "tag.services.user.user_service_error.token_expired"
+    );
+}
+
+#[test]
+fn test_struct_tag_with_case() {
+    assert_eq!(
+        RateLimitedError.type_tag(),
+        "tag.services.user.rate-limited-error"
+    );
+}
+
+#[test]
+fn test_from_tagged() {
+    let error = AnyError::from_tagged(UserServiceError::NotFound("alice".to_string()));
+
+    assert_eq!(
+        error.r#type,
+        "tag.services.user.user_service_error.not_found"
+    );
+    assert_eq!(error.message(), "user alice not found");
+}
+
+#[test]
+fn test_known_tags_populate_catalog() {
+    let mut catalog = Catalog::new();
+    catalog.register_type::<UserServiceError>();
+    catalog.register_type::<RateLimitedError>();
+
+    assert_eq!(catalog.len(), 4);
+    assert!(catalog.is_known(&UserServiceError::DatabaseError.type_tag()));
+    assert!(catalog.is_known("tag.services.user.rate-limited-error"));
+}
@@ -0,0 +1,64 @@
+use liberror::AnyError;
+use liberror_derive::LibError;
+use thiserror::Error;
+
+mod external {
+    #[derive(Debug, thiserror::Error)]
+    #[error("connection refused")]
+    pub struct ConnectionError;
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("row not found")]
+    pub struct RowNotFound;
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("invalid token")]
+    pub struct TokenError;
+}
+
+#[derive(Debug, Error, LibError)]
+enum ServiceError {
+    #[error("Database error: {0}")]
+    #[liberror(wrap(external::ConnectionError, external::RowNotFound))]
+    Database(AnyError),
+
+    #[error("Authentication error: {error}")]
+    #[liberror(wrap(external::TokenError))]
+    Authentication { error: AnyError },
+
+    #[error("User not found: {0}")]
+    NotFound(String),
+}
+
+fn query() -> Result<(), ServiceError> {
+    Err(external::RowNotFound)?;
+    Ok(())
+}
+
+#[test]
+fn test_wraps_each_listed_source() {
+    let error = ServiceError::from(external::ConnectionError);
+    assert!(
+        matches!(&error, ServiceError::Database(inner) if inner.message() == "connection refused")
+    );
+
+    let error = query().unwrap_err();
+    assert!(matches!(&error, ServiceError::Database(inner) if inner.message() == "row not found"));
+}
+
+#[test]
+fn test_wraps_named_field_variant() {
+    let error = ServiceError::from(external::TokenError);
+
+    assert!(matches!(
+        &error,
+        ServiceError::Authentication { error } if error.message() == "invalid token"
+    ));
+}
+
+#[test]
+fn test_unannotated_variants_are_untouched() {
+    let error = ServiceError::NotFound("alice".to_string());
+
+    assert_eq!(error.to_string(), "User not found: alice");
+}
@@ -0,0 +1,229 @@
+//! Several independent failures of one operation, such as every invalid field of a form or
+//! every failed job of a batch.
+
+use std::{error::Error, fmt};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    AnyError,
+    causes::{aggregate_message, partition_results},
+};
+
+/// Independent errors reported together. Serializes as an array of errors and converts into
+/// the [`AGGREGATE_TYPE`](crate::causes::AGGREGATE_TYPE) `AnyError` that
+/// [`AnyError::from_results`] fails with, each error a cause.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AggregateError {
+    errors: Vec<AnyError>,
+    /// How many operations ran, successful ones included.
+    #[serde(skip)]
+    total: usize,
+}
+
+impl AggregateError {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[track_caller]
+    pub fn push(&mut self, error: impl Into<AnyError>) {
+        self.errors.push(error.into());
+        self.total += 1;
+    }
+
+    pub fn errors(&self) -> &[AnyError] {
+        &self.errors
+    }
+
+    pub fn into_errors(self) -> Vec<AnyError> {
+        self.errors
+    }
+
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Errors pushed without a count of the operations that ran are the only ones counted.
+    fn total(&self) -> usize {
+        self.total.max(self.errors.len())
+    }
+}
+
+/// Collects every success, or every failure if there was at least one, e.g. the results of
+/// a batch or a `join_all`.
+#[track_caller]
+pub fn collect_errors<T, E: Into<AnyError>>(
+    results: impl IntoIterator<Item = Result<T, E>>,
+) -> Result<Vec<T>, AggregateError> {
+    let (values, errors) = partition_results(results);
+    if errors.is_empty() {
+        return Ok(values);
+    }
+
+    let total = values.len() + errors.len();
+    Err(AggregateError { errors, total })
+}
+
+/// [`collect_errors`] as a method on iterators of results.
+pub trait ResultIteratorExt<T, E>: Iterator<Item = Result<T, E>> {
+    #[track_caller]
+    fn try_collect_all(self) -> Result<Vec<T>, AggregateError>;
+}
+
+impl<T, E: Into<AnyError>, I: Iterator<Item = Result<T, E>>> ResultIteratorExt<T, E> for I {
+    fn try_collect_all(self) -> Result<Vec<T>, AggregateError> {
+        collect_errors(self)
+    }
+}
+
+impl fmt::Display for AggregateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&aggregate_message(self.errors.len(), self.total()))?;
+        for (index, error) in self.errors.iter().enumerate() {
+            let separator = if index == 0 { ": " } else { "; " };
+            write!(f, "{separator}{error}")?;
+        }
+        Ok(())
+    }
+}
+
+impl<E: Error> From<Vec<E>> for AggregateError {
+    #[track_caller]
+    fn from(errors: Vec<E>) -> Self {
+        let mut aggregate = AggregateError::new();
+        for error in errors {
+            aggregate.push(error);
+        }
+        aggregate
+    }
+}
+
+impl FromIterator<AnyError> for AggregateError {
+    fn from_iter<I: IntoIterator<Item = AnyError>>(errors: I) -> Self {
+        let errors = errors.into_iter().collect::<Vec<_>>();
+        Self {
+            total: errors.len(),
+            errors,
+        }
+    }
+}
+
+impl IntoIterator for AggregateError {
+    type Item = AnyError;
+    type IntoIter = std::vec::IntoIter<AnyError>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.errors.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a AggregateError {
+    type Item = &'a AnyError;
+    type IntoIter = std::slice::Iter<'a, AnyError>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.errors.iter()
+    }
+}
+
+impl From<AggregateError> for AnyError {
+    #[track_caller]
+    fn from(aggregate: AggregateError) -> Self {
+        let total = aggregate.total();
+        AnyError::aggregate(aggregate.errors, total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::ParseIntError;
+
+    use super::*;
+    use crate::causes::AGGREGATE_TYPE;
+
+    fn parse_errors() -> Vec<ParseIntError> {
+        ["x", "", "1.5"]
+            .into_iter()
+            .filter_map(|input| input.parse::<u8>().err())
+            .collect()
+    }
+
+    #[test]
+    fn test_from_vec_and_display() {
+        let aggregate = AggregateError::from(parse_errors());
+
+        assert_eq!(aggregate.len(), 3);
+        assert_eq!(
+            aggregate.to_string(),
+            "3 of 3 operations failed: ParseIntError: invalid digit found in string; \
+             ParseIntError: cannot parse integer from empty string; \
+             ParseIntError: invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn test_serializes_as_array() {
+        let aggregate = AggregateError::from(parse_errors());
+        let json = serde_json::to_value(&aggregate).unwrap();
+
+        assert_eq!(json.as_array().map(Vec::len), Some(3));
+        assert_eq!(
+            json[1]["context"]["message"],
+            "cannot parse integer from empty string"
+        );
+
+        let decoded: AggregateError = serde_json::from_value(json).unwrap();
+        assert_eq!(
+            decoded.errors()[1].message(),
+            aggregate.errors()[1].message()
+        );
+    }
+
+    #[test]
+    fn test_collect_errors() {
+        let values = collect_errors(["1", "2"].map(str::parse::<u8>)).unwrap();
+        assert_eq!(values, [1, 2]);
+
+        let aggregate = ["1", "x", "3", ""]
+            .into_iter()
+            .map(str::parse::<u8>)
+            .try_collect_all()
+            .unwrap_err();
+        assert_eq!(aggregate.len(), 2);
+        assert_eq!(
+            aggregate.to_string().split(':').next(),
+            Some("2 of 4 operations failed")
+        );
+        assert_eq!(
+            aggregate.errors()[0].message(),
+            "invalid digit found in string"
+        );
+        assert_eq!(
+            aggregate.errors()[0]
+                .location()
+                .map(|location| location.file.as_str()),
+            Some(file!())
+        );
+
+        let results = || ["1", "x", "3", ""].map(str::parse::<u8>);
+        let from_results = AnyError::from_results(results()).unwrap_err();
+        let collected = AnyError::from(collect_errors(results()).unwrap_err());
+        assert_eq!(collected, from_results);
+        assert_eq!(collected.message(), "2 of 4 operations failed");
+    }
+
+    #[test]
+    fn test_into_any_error() {
+        let error = AnyError::from(AggregateError::from(parse_errors()));
+
+        assert_eq!(error.r#type, AGGREGATE_TYPE);
+        assert_eq!(error.message(), "3 of 3 operations failed");
+        assert_eq!(error.causes().count(), 3);
+    }
+}
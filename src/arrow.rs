@@ -0,0 +1,185 @@
+//! Arrow export of error collections, for archiving them as Parquet or analyzing them with
+//! DataFusion or pandas.
+//!
+//! Each error becomes a row of [`schema`]:
+//!
+//! | column        | type                       |                                     |
+//! |---------------|----------------------------|-------------------------------------|
+//! | `fingerprint` | `UInt64`                   | [`AnyError::fingerprint`]           |
+//! | `type`        | `Utf8`                     | outermost `$type`                   |
+//! | `message`     | `Utf8`                     | the chain's messages joined by `: ` |
+//! | `depth`       | `UInt32`                   | number of layers in the chain       |
+//! | `timestamp`   | `Timestamp(ms, UTC)`, null | when it was reported or created     |
+//! | `code`        | `Utf8`, null               | the [`CODE_EXTENSION`] of the error |
+
+use std::{
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use arrow_array::{
+    ArrayRef, RecordBatch, StringArray, TimestampMillisecondArray, UInt32Array, UInt64Array,
+};
+use arrow_schema::{ArrowError, DataType, Field, Schema, TimeUnit};
+
+use crate::{AnyError, recent::RecentError};
+
+/// Read into the `code` column. Strings and numbers are exported as text.
+pub use crate::CODE_EXTENSION;
+
+pub fn schema() -> Schema {
+    Schema::new(vec![
+        Field::new("fingerprint", DataType::UInt64, false),
+        Field::new("type", DataType::Utf8, false),
+        Field::new("message", DataType::Utf8, false),
+        Field::new("depth", DataType::UInt32, false),
+        Field::new(
+            "timestamp",
+            DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+            true,
+        ),
+        Field::new("code", DataType::Utf8, true),
+    ])
+}
+
+/// One row per error, timestamped with [`AnyError::timestamp`] under the `time` feature and
+/// null otherwise.
+pub fn record_batch<'a>(
+    errors: impl IntoIterator<Item = &'a AnyError>,
+) -> Result<RecordBatch, ArrowError> {
+    build(errors.into_iter().map(|error| (created_at(error), error)))
+}
+
+#[cfg(feature = "time")]
+fn created_at(error: &AnyError) -> Option<SystemTime> {
+    error.timestamp().map(SystemTime::from)
+}
+
+#[cfg(not(feature = "time"))]
+fn created_at(_: &AnyError) -> Option<SystemTime> {
+    None
+}
+
+/// One row per [`RecentErrors`](crate::recent::RecentErrors) entry, timestamped with when it
+/// was reported.
+pub fn recent_record_batch<'a>(
+    entries: impl IntoIterator<Item = &'a RecentError>,
+) -> Result<RecordBatch, ArrowError> {
+    build(
+        entries
+            .into_iter()
+            .map(|entry| (Some(entry.reported_at), &entry.error)),
+    )
+}
+
+fn build<'a>(
+    rows: impl Iterator<Item = (Option<SystemTime>, &'a AnyError)>,
+) -> Result<RecordBatch, ArrowError> {
+    let mut fingerprints = Vec::new();
+    let mut types = Vec::new();
+    let mut messages = Vec::new();
+    let mut depths = Vec::new();
+    let mut timestamps = Vec::new();
+    let mut codes = Vec::new();
+    for (timestamp, error) in rows {
+        let chain = error.chain_messages();
+        fingerprints.push(error.fingerprint());
+        types.push(error.r#type.clone());
+        messages.push(chain.join(": "));
+        depths.push(chain.len() as u32);
+        timestamps.push(timestamp.map(|timestamp| {
+            timestamp
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as i64
+        }));
+        codes.push(error.extension(CODE_EXTENSION).map(|code| match code {
+            serde_json::Value::String(code) => code.clone(),
+            code => code.to_string(),
+        }));
+    }
+
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(UInt64Array::from(fingerprints)),
+        Arc::new(StringArray::from(types)),
+        Arc::new(StringArray::from(messages)),
+        Arc::new(UInt32Array::from(depths)),
+        Arc::new(TimestampMillisecondArray::from(timestamps).with_timezone("UTC")),
+        Arc::new(StringArray::from(codes)),
+    ];
+    RecordBatch::try_new(Arc::new(schema()), columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::Array;
+
+    use super::*;
+    use crate::{recent::RecentErrors, sink::ErrorSink};
+
+    fn errors() -> Vec<AnyError> {
+        let mut first = AnyError::msg("connection refused").with_type("io.Error");
+        first.wrap_with("loading user");
+        let second = AnyError::msg("user not found").with_extension(CODE_EXTENSION, "USR001");
+        vec![first, second]
+    }
+
+    #[test]
+    fn test_record_batch() {
+        let errors = errors();
+        let batch = record_batch(&errors).unwrap();
+
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(batch.schema().as_ref(), &schema());
+
+        let column = |name| batch.column_by_name(name).unwrap().clone();
+        let messages = column("message");
+        let messages = messages.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(messages.value(0), "loading user: connection refused");
+
+        let depths = column("depth");
+        let depths = depths.as_any().downcast_ref::<UInt32Array>().unwrap();
+        assert_eq!(depths.values().as_ref(), [2, 1]);
+
+        let codes = column("code");
+        let codes = codes.as_any().downcast_ref::<StringArray>().unwrap();
+        assert!(codes.is_null(0));
+        assert_eq!(codes.value(1), "USR001");
+
+        let fingerprints = column("fingerprint");
+        let fingerprints = fingerprints.as_any().downcast_ref::<UInt64Array>().unwrap();
+        assert_eq!(fingerprints.value(0), errors[0].fingerprint());
+
+        #[cfg(not(feature = "time"))]
+        assert_eq!(column("timestamp").null_count(), 2);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_record_batch_timestamps() {
+        let created = time::OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+        let timed = AnyError::msg("timed").with_timestamp(created);
+        let mut untimed = AnyError::msg("untimed");
+        untimed.context.details.timestamp = None;
+
+        let batch = record_batch([&timed, &untimed]).unwrap();
+        let timestamps = batch.column_by_name("timestamp").unwrap();
+        let timestamps = timestamps
+            .as_any()
+            .downcast_ref::<TimestampMillisecondArray>()
+            .unwrap();
+        assert_eq!(timestamps.value(0), 1_700_000_000_000);
+        assert!(timestamps.is_null(1));
+    }
+
+    #[test]
+    fn test_recent_record_batch() {
+        let recent = RecentErrors::new(4);
+        for error in errors() {
+            recent.report(&error);
+        }
+
+        let batch = recent_record_batch(&recent.snapshot()).unwrap();
+        assert_eq!(batch.column_by_name("timestamp").unwrap().null_count(), 0);
+    }
+}
@@ -0,0 +1,88 @@
+use std::{
+    any::Any,
+    fmt::Debug,
+    panic::{RefUnwindSafe, UnwindSafe},
+    sync::Arc,
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::type_name::standardized_type_name;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Attachment {
+    #[serde(rename = "$type")]
+    pub r#type: String,
+    pub value: Value,
+    #[serde(skip)]
+    typed: Option<Arc<dyn Any + Send + Sync>>,
+}
+
+impl Attachment {
+    pub fn new<T: Serialize + Send + Sync + 'static>(value: T) -> Self {
+        Self {
+            r#type: standardized_type_name::<T>(),
+            value: serde_json::to_value(&value).unwrap_or(Value::Null),
+            typed: Some(Arc::new(value)),
+        }
+    }
+
+    /// The original value, if this attachment was created in this process. Attachments
+    /// that went through deserialization only carry their serialized `value`.
+    pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+        self.typed.as_deref()?.downcast_ref()
+    }
+
+    /// Replaces the serialized value, dropping the original so it can't be read back.
+    #[cfg(feature = "redact")]
+    pub(crate) fn replace_value(&mut self, value: Value) {
+        self.value = value;
+        self.typed = None;
+    }
+}
+
+// The typed value is only ever read through `downcast_ref`, see the impls on `AnyErrorContext`.
+impl UnwindSafe for Attachment {}
+impl RefUnwindSafe for Attachment {}
+
+impl Debug for Attachment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Attachment")
+            .field("type", &self.r#type)
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize)]
+    struct RequestInfo {
+        id: u32,
+    }
+
+    #[test]
+    fn test_new_records_type_and_value() {
+        let attachment = Attachment::new(RequestInfo { id: 7 });
+
+        assert!(attachment.r#type.ends_with("RequestInfo"));
+        assert_eq!(attachment.value, serde_json::json!({ "id": 7 }));
+        assert_eq!(
+            attachment.downcast_ref::<RequestInfo>(),
+            Some(&RequestInfo { id: 7 })
+        );
+        assert!(attachment.downcast_ref::<String>().is_none());
+    }
+
+    #[test]
+    fn test_deserialized_attachment_is_untyped() {
+        let json = serde_json::to_string(&Attachment::new(RequestInfo { id: 7 })).unwrap();
+        let attachment: Attachment = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(attachment.value, serde_json::json!({ "id": 7 }));
+        assert!(attachment.downcast_ref::<RequestInfo>().is_none());
+    }
+}
@@ -0,0 +1,558 @@
+use std::{
+    backtrace::Backtrace as StdBacktrace,
+    fmt::{Debug, Display},
+    sync::{Arc, OnceLock},
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::config;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, valuable::Valuable)]
+#[serde(rename_all = "camelCase")]
+pub struct BacktraceFrame {
+    pub function: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<u32>,
+}
+
+/// A backtrace serialized as an array of frames. Backtraces captured in this process are
+/// only symbolicated, and filtered with the configured [`FrameFilter`], the first time their
+/// frames are needed.
+#[derive(Clone)]
+pub struct Backtrace {
+    inner: Arc<LazyFrames>,
+}
+
+struct LazyFrames {
+    captured: Option<StdBacktrace>,
+    filtered: bool,
+    frames: OnceLock<Vec<BacktraceFrame>>,
+}
+
+impl Backtrace {
+    pub fn new(frames: Vec<BacktraceFrame>) -> Self {
+        Self {
+            inner: Arc::new(LazyFrames {
+                captured: None,
+                filtered: false,
+                frames: OnceLock::from(frames),
+            }),
+        }
+    }
+
+    fn unresolved(captured: StdBacktrace, filtered: bool) -> Self {
+        Self {
+            inner: Arc::new(LazyFrames {
+                captured: Some(captured),
+                filtered,
+                frames: OnceLock::new(),
+            }),
+        }
+    }
+
+    /// Captures the current stack without resolving symbols, following the configured
+    /// [`BacktracePolicy`] or, when none is configured, the environment.
+    pub fn capture() -> Option<Self> {
+        let policy = config::current()
+            .backtrace
+            .unwrap_or_else(BacktracePolicy::from_env);
+        Self::capture_with(policy)
+    }
+
+    /// Always `None` without the `backtrace` feature.
+    pub fn capture_with(policy: BacktracePolicy) -> Option<Self> {
+        if !cfg!(feature = "backtrace") {
+            return None;
+        }
+        match policy {
+            BacktracePolicy::Disabled => None,
+            BacktracePolicy::Captured => {
+                Some(Self::unresolved(StdBacktrace::force_capture(), true))
+            }
+            BacktracePolicy::Full => Some(Self::unresolved(StdBacktrace::force_capture(), false)),
+        }
+    }
+
+    pub fn frames(&self) -> &[BacktraceFrame] {
+        self.inner
+            .frames
+            .get_or_init(|| match &self.inner.captured {
+                Some(captured) if self.inner.filtered => Self::parse(&captured.to_string())
+                    .filter(&config::current().frame_filter)
+                    .frames()
+                    .to_vec(),
+                Some(captured) => Self::parse(&captured.to_string()).frames().to_vec(),
+                None => Vec::new(),
+            })
+    }
+
+    pub fn is_resolved(&self) -> bool {
+        self.inner.frames.get().is_some()
+    }
+
+    /// Parses the `Display` output of `std::backtrace::Backtrace`, in either its short or
+    /// alternate (`{:#}`) form. Lines that are not frame headers or locations are skipped.
+    pub fn parse(text: &str) -> Self {
+        let mut frames: Vec<BacktraceFrame> = Vec::new();
+
+        for line in text.lines().map(str::trim) {
+            if let Some(location) = line.strip_prefix("at ") {
+                if let Some(frame) = frames.last_mut() {
+                    let (file, line) = parse_location(location);
+                    frame.file = Some(file.to_string());
+                    frame.line = line;
+                }
+                continue;
+            }
+
+            let Some((index, function)) = line.split_once(": ") else {
+                continue;
+            };
+            if index.is_empty() || !index.bytes().all(|b| b.is_ascii_digit()) {
+                continue;
+            }
+
+            frames.push(BacktraceFrame {
+                function: strip_address(function.trim()).to_string(),
+                file: None,
+                line: None,
+            });
+        }
+
+        Self::new(frames)
+    }
+
+    /// Returns the frames kept by `filter`, stopping after the first stop frame.
+    pub fn filter(&self, filter: &FrameFilter) -> Self {
+        let mut frames = Vec::new();
+        for frame in self.frames() {
+            if filter.keeps(frame) {
+                frames.push(frame.clone());
+            }
+            if filter.stops_at(frame) {
+                break;
+            }
+        }
+
+        Self::new(frames)
+    }
+}
+
+/// Decides which frames survive [`Backtrace::filter`]. The default skips frames from std,
+/// the async runtime and this crate, and stops at `main`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameFilter {
+    skip_crates: Vec<String>,
+    stop_at: Vec<String>,
+}
+
+impl FrameFilter {
+    /// A filter that keeps every frame.
+    pub fn new() -> Self {
+        Self {
+            skip_crates: Vec::new(),
+            stop_at: Vec::new(),
+        }
+    }
+
+    pub fn skip_crate(mut self, name: impl Into<String>) -> Self {
+        self.skip_crates.push(name.into());
+        self
+    }
+
+    /// Stops after a frame whose function name (without its path) is `function`.
+    pub fn stop_at(mut self, function: impl Into<String>) -> Self {
+        self.stop_at.push(function.into());
+        self
+    }
+
+    pub fn keeps(&self, frame: &BacktraceFrame) -> bool {
+        let crate_name = frame_crate(&frame.function);
+        !self.skip_crates.iter().any(|skipped| skipped == crate_name)
+    }
+
+    pub fn stops_at(&self, frame: &BacktraceFrame) -> bool {
+        let function = frame_function(&frame.function);
+        self.stop_at.iter().any(|stop| stop == function)
+    }
+}
+
+impl Default for FrameFilter {
+    fn default() -> Self {
+        [
+            "std", "core", "alloc", "tokio", "tower", "liberror", "__rustc",
+        ]
+        .into_iter()
+        .fold(Self::new(), Self::skip_crate)
+        .stop_at("main")
+    }
+}
+
+/// The crate segment of a frame's function path, ignoring leading `<`, `&` and `dyn ` and
+/// the `[hash]` disambiguator used by the alternate backtrace format.
+fn frame_crate(function: &str) -> &str {
+    let path = function.trim_start_matches(['<', '&']);
+    let path = path.strip_prefix("dyn ").unwrap_or(path);
+    let root = path.split("::").next().unwrap_or(path);
+    root.split('[').next().unwrap_or(root)
+}
+
+/// The last segment of a frame's function path, without a trailing `::h<hash>`.
+fn frame_function(function: &str) -> &str {
+    let mut segments = function.rsplit("::");
+    match segments.next() {
+        Some(last) if is_symbol_hash(last) => segments.next().unwrap_or(last),
+        Some(last) => last,
+        None => function,
+    }
+}
+
+fn is_symbol_hash(segment: &str) -> bool {
+    segment.len() == 17
+        && segment.starts_with('h')
+        && segment[1..].bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+impl From<&StdBacktrace> for Backtrace {
+    fn from(value: &StdBacktrace) -> Self {
+        Self::parse(&value.to_string())
+    }
+}
+
+impl From<StdBacktrace> for Backtrace {
+    fn from(value: StdBacktrace) -> Self {
+        Self::unresolved(value, true)
+    }
+}
+
+/// Whether errors capture a backtrace when they are converted, following the conventions of
+/// `RUST_LIB_BACKTRACE`/`RUST_BACKTRACE`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BacktracePolicy {
+    Disabled,
+    /// Capture, keeping only the frames allowed by the configured [`FrameFilter`].
+    Captured,
+    /// Capture every frame.
+    Full,
+}
+
+impl BacktracePolicy {
+    /// Reads `RUST_LIB_BACKTRACE`, falling back to `RUST_BACKTRACE`. The environment is only
+    /// read once per process.
+    pub fn from_env() -> Self {
+        static FROM_ENV: OnceLock<BacktracePolicy> = OnceLock::new();
+        *FROM_ENV.get_or_init(|| {
+            Self::from_vars(
+                std::env::var("RUST_LIB_BACKTRACE").ok().as_deref(),
+                std::env::var("RUST_BACKTRACE").ok().as_deref(),
+            )
+        })
+    }
+
+    fn from_vars(lib_backtrace: Option<&str>, backtrace: Option<&str>) -> Self {
+        match lib_backtrace.or(backtrace) {
+            None | Some("") | Some("0") => Self::Disabled,
+            Some("full") => Self::Full,
+            Some(_) => Self::Captured,
+        }
+    }
+}
+
+impl Default for Backtrace {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+impl PartialEq for Backtrace {
+    fn eq(&self, other: &Self) -> bool {
+        self.frames() == other.frames()
+    }
+}
+
+impl Eq for Backtrace {}
+
+impl Debug for Backtrace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.frames()).finish()
+    }
+}
+
+impl Serialize for Backtrace {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.frames().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Backtrace {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<BacktraceFrame>::deserialize(deserializer).map(Self::new)
+    }
+}
+
+impl valuable::Valuable for Backtrace {
+    fn as_value(&self) -> valuable::Value<'_> {
+        valuable::Value::Listable(self)
+    }
+
+    fn visit(&self, visit: &mut dyn valuable::Visit) {
+        self.frames()
+            .iter()
+            .for_each(|frame| visit.visit_value(frame.as_value()));
+    }
+}
+
+impl valuable::Listable for Backtrace {
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.frames().len();
+        (len, Some(len))
+    }
+}
+
+impl Display for Backtrace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, frame) in self.frames().iter().enumerate() {
+            writeln!(f, "{index:>4}: {}", frame.function)?;
+            match (&frame.file, frame.line) {
+                (Some(file), Some(line)) => writeln!(f, "             at {file}:{line}")?,
+                (Some(file), None) => writeln!(f, "             at {file}")?,
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Drops the `0x55d4c8a1b2c3 - ` prefix of alternate-format frames.
+fn strip_address(function: &str) -> &str {
+    match function.split_once(" - ") {
+        Some((address, rest)) if address.starts_with("0x") => rest,
+        _ => function,
+    }
+}
+
+/// Splits `path/to/file.rs:12:5` into the path and line, ignoring the column.
+fn parse_location(location: &str) -> (&str, Option<u32>) {
+    let mut parts = location.rsplitn(3, ':');
+    let (Some(last), Some(middle)) = (parts.next(), parts.next()) else {
+        return (location, None);
+    };
+
+    match (parts.next(), middle.parse::<u32>(), last.parse::<u32>()) {
+        (Some(file), Ok(line), Ok(_column)) => (file, Some(line)),
+        (_, _, Ok(line)) => (&location[..location.len() - last.len() - 1], Some(line)),
+        _ => (location, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHORT: &str = "   0: app::handler
+             at ./src/handler.rs:12:5
+   1: core::ops::function::FnOnce::call_once
+             at /rustc/abc/library/core/src/ops/function.rs:250:5
+   2: main
+   3: <unknown>
+";
+
+    const ALTERNATE: &str = "   4:     0x558e9a7e940e - app::handler::h5f0dfe24af8ce816
+                               at /tmp/app/src/handler.rs:12:5
+";
+
+    #[test]
+    fn test_parse_short_format() {
+        let backtrace = Backtrace::parse(SHORT);
+
+        assert_eq!(backtrace.frames().len(), 4);
+        assert_eq!(
+            backtrace.frames()[0],
+            BacktraceFrame {
+                function: "app::handler".to_string(),
+                file: Some("./src/handler.rs".to_string()),
+                line: Some(12),
+            }
+        );
+        assert_eq!(backtrace.frames()[2].function, "main");
+        assert!(backtrace.frames()[2].file.is_none());
+        assert_eq!(backtrace.frames()[3].function, "<unknown>");
+    }
+
+    #[test]
+    fn test_parse_alternate_format() {
+        let backtrace = Backtrace::parse(ALTERNATE);
+
+        assert_eq!(backtrace.frames().len(), 1);
+        assert_eq!(
+            backtrace.frames()[0].function,
+            "app::handler::h5f0dfe24af8ce816"
+        );
+        assert_eq!(
+            backtrace.frames()[0].file.as_deref(),
+            Some("/tmp/app/src/handler.rs")
+        );
+        assert_eq!(backtrace.frames()[0].line, Some(12));
+    }
+
+    #[test]
+    fn test_parse_location_without_column() {
+        assert_eq!(parse_location("src/lib.rs:7"), ("src/lib.rs", Some(7)));
+        assert_eq!(parse_location("src/lib.rs"), ("src/lib.rs", None));
+        assert_eq!(
+            parse_location("C:\\app\\src\\lib.rs:7:1"),
+            ("C:\\app\\src\\lib.rs", Some(7))
+        );
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let backtrace = Backtrace::parse(SHORT);
+
+        assert_eq!(Backtrace::parse(&backtrace.to_string()), backtrace);
+    }
+
+    #[test]
+    fn test_serializes_as_frame_array() {
+        let json = serde_json::to_value(Backtrace::parse(SHORT)).unwrap();
+
+        assert_eq!(json[0]["function"], "app::handler");
+        assert_eq!(json[0]["file"], "./src/handler.rs");
+        assert_eq!(json[0]["line"], 12);
+        assert!(json[2].get("file").is_none());
+    }
+
+    const RUNTIME: &str = "   0: liberror::capture
+   1: app::db::query
+             at ./src/db.rs:40:9
+   2: <tower::util::BoxService<T, U, E> as tower_service::Service<T>>::call
+   3: tokio::runtime::task::harness::poll
+   4: app::main::h56c9f3d526a0790b
+             at ./src/main.rs:3:21
+   5: core::ops::function::FnOnce::call_once
+   6: std[e28293b1aa0f68bd]::rt::lang_start_internal
+   7: main
+";
+
+    #[test]
+    fn test_default_filter() {
+        let filtered = Backtrace::parse(RUNTIME).filter(&FrameFilter::default());
+        let functions = filtered
+            .frames()
+            .iter()
+            .map(|frame| frame.function.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            functions,
+            vec!["app::db::query", "app::main::h56c9f3d526a0790b"]
+        );
+    }
+
+    #[test]
+    fn test_empty_filter_keeps_everything() {
+        let backtrace = Backtrace::parse(RUNTIME);
+
+        assert_eq!(backtrace.filter(&FrameFilter::new()), backtrace);
+    }
+
+    #[test]
+    fn test_custom_filter() {
+        let filter = FrameFilter::new().skip_crate("app").stop_at("poll");
+        let filtered = Backtrace::parse(RUNTIME).filter(&filter);
+
+        assert_eq!(filtered.frames().len(), 3);
+        assert_eq!(filtered.frames()[0].function, "liberror::capture");
+    }
+
+    #[test]
+    fn test_frame_path_helpers() {
+        assert_eq!(frame_crate("<&dyn core::ops::Fn<()>>::call"), "core");
+        assert_eq!(frame_crate("std[e28293b1aa0f68bd]::rt::lang_start"), "std");
+        assert_eq!(frame_function("app::main::h56c9f3d526a0790b"), "main");
+        assert_eq!(frame_function("main"), "main");
+    }
+
+    #[test]
+    fn test_owned_std_backtrace_resolves_lazily() {
+        let backtrace = Backtrace::from(StdBacktrace::force_capture());
+
+        assert!(!backtrace.is_resolved());
+        let json = serde_json::to_value(&backtrace).unwrap();
+        assert!(backtrace.is_resolved());
+        assert!(json.is_array());
+    }
+
+    #[test]
+    fn test_clones_share_resolution() {
+        let backtrace = Backtrace::from(StdBacktrace::force_capture());
+        let clone = backtrace.clone();
+
+        let _ = backtrace.frames();
+        assert!(clone.is_resolved());
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let json = serde_json::to_string(&Backtrace::parse(SHORT)).unwrap();
+        let backtrace: Backtrace = serde_json::from_str(&json).unwrap();
+
+        assert!(backtrace.is_resolved());
+        assert_eq!(backtrace, Backtrace::parse(SHORT));
+    }
+
+    #[test]
+    fn test_policy_from_vars() {
+        assert_eq!(
+            BacktracePolicy::from_vars(None, None),
+            BacktracePolicy::Disabled
+        );
+        assert_eq!(
+            BacktracePolicy::from_vars(None, Some("1")),
+            BacktracePolicy::Captured
+        );
+        assert_eq!(
+            BacktracePolicy::from_vars(None, Some("full")),
+            BacktracePolicy::Full
+        );
+        assert_eq!(
+            BacktracePolicy::from_vars(Some("0"), Some("full")),
+            BacktracePolicy::Disabled
+        );
+        assert_eq!(
+            BacktracePolicy::from_vars(Some("1"), Some("0")),
+            BacktracePolicy::Captured
+        );
+    }
+
+    #[cfg(feature = "backtrace")]
+    #[test]
+    fn test_capture_with_policy() {
+        assert!(Backtrace::capture_with(BacktracePolicy::Disabled).is_none());
+
+        let full = Backtrace::capture_with(BacktracePolicy::Full).expect("captured");
+        assert!(!full.is_resolved());
+        assert!(
+            full.frames()
+                .iter()
+                .any(|frame| frame.function.starts_with("std"))
+        );
+    }
+
+    #[cfg(not(feature = "backtrace"))]
+    #[test]
+    fn test_capture_disabled_without_feature() {
+        assert!(Backtrace::capture_with(BacktracePolicy::Full).is_none());
+    }
+
+    #[test]
+    fn test_from_std_backtrace() {
+        let backtrace = Backtrace::from(&std::backtrace::Backtrace::force_capture());
+
+        assert!(!backtrace.frames().is_empty());
+    }
+}
@@ -0,0 +1,217 @@
+//! A background reporter for high-volume services: reported errors are queued and handed to a
+//! [`BatchSink`] in batches, once enough have accumulated or the flush interval passes.
+//!
+//! The queue is bounded; errors reported while it is full are dropped and counted rather than
+//! blocking the caller.
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+use tokio::{sync::mpsc, task::JoinHandle, time::Instant};
+
+use crate::{AnyError, sink::ErrorSink};
+
+/// Receives batches from a [`BatchingReporter`], e.g. by posting them to an HTTP endpoint.
+pub trait BatchSink: Send + Sync + 'static {
+    fn flush(&self, batch: Vec<AnyError>) -> impl Future<Output = ()> + Send;
+}
+
+/// Any [`ErrorSink`], such as a [`FileSink`](crate::sink::FileSink), takes a batch one error
+/// at a time.
+impl<S: ErrorSink + 'static> BatchSink for S {
+    fn flush(&self, batch: Vec<AnyError>) -> impl Future<Output = ()> + Send {
+        for error in &batch {
+            self.report(error);
+        }
+        std::future::ready(())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct BatchConfig {
+    /// Flush as soon as this many errors are queued.
+    pub max_batch: usize,
+    /// Flush whatever is queued at least this often.
+    pub interval: Duration,
+    /// Errors held before new ones are dropped.
+    pub capacity: usize,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        Self {
+            max_batch: 100,
+            interval: Duration::from_secs(5),
+            capacity: 10_000,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    flushed: AtomicU64,
+    dropped: AtomicU64,
+}
+
+/// Counts since the reporter was spawned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchStats {
+    pub flushed: u64,
+    pub dropped: u64,
+}
+
+/// The queueing end of the background task started by [`BatchingReporter::spawn`]. Register
+/// it as a [sink](crate::sink::register) or report to it directly. The task flushes what is
+/// left and exits once every clone has been dropped.
+#[derive(Debug, Clone)]
+pub struct BatchingReporter {
+    sender: mpsc::Sender<AnyError>,
+    counters: Arc<Counters>,
+}
+
+impl BatchingReporter {
+    /// Starts the flushing task on the current tokio runtime. The task returns the final
+    /// stats once it exits.
+    pub fn spawn<S: BatchSink>(sink: S, config: BatchConfig) -> (Self, JoinHandle<BatchStats>) {
+        let (sender, receiver) = mpsc::channel(config.capacity.max(1));
+        let counters = Arc::<Counters>::default();
+        let task = tokio::spawn(run(sink, config, receiver, counters.clone()));
+        (Self { sender, counters }, task)
+    }
+
+    pub fn stats(&self) -> BatchStats {
+        self.counters.stats()
+    }
+}
+
+impl Counters {
+    fn stats(&self) -> BatchStats {
+        BatchStats {
+            flushed: self.flushed.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl ErrorSink for BatchingReporter {
+    fn report(&self, error: &AnyError) {
+        if self.sender.try_send(error.clone()).is_err() {
+            self.counters.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+async fn run<S: BatchSink>(
+    sink: S,
+    config: BatchConfig,
+    mut receiver: mpsc::Receiver<AnyError>,
+    counters: Arc<Counters>,
+) -> BatchStats {
+    let max_batch = config.max_batch.max(1);
+    let mut batch = Vec::with_capacity(max_batch);
+    let mut deadline = Instant::now() + config.interval;
+    loop {
+        let closed = match tokio::time::timeout_at(deadline, receiver.recv()).await {
+            Ok(Some(error)) => {
+                batch.push(error);
+                if batch.len() < max_batch {
+                    continue;
+                }
+                false
+            }
+            Ok(None) => true,
+            Err(_) => {
+                deadline = Instant::now() + config.interval;
+                false
+            }
+        };
+
+        if !batch.is_empty() {
+            let count = batch.len() as u64;
+            sink.flush(std::mem::replace(&mut batch, Vec::with_capacity(max_batch)))
+                .await;
+            counters.flushed.fetch_add(count, Ordering::Relaxed);
+        }
+        if closed {
+            return counters.stats();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct Collect(Arc<Mutex<Vec<usize>>>);
+
+    impl BatchSink for Collect {
+        fn flush(&self, batch: Vec<AnyError>) -> impl Future<Output = ()> + Send {
+            self.0.lock().unwrap().push(batch.len());
+            std::future::ready(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_flushes_full_batches_and_remainder() {
+        let sink = Collect::default();
+        let config = BatchConfig {
+            max_batch: 2,
+            interval: Duration::from_secs(60),
+            capacity: 10,
+        };
+        let (reporter, task) = BatchingReporter::spawn(sink.clone(), config);
+
+        for index in 0..5 {
+            reporter.report(&AnyError::msg(index));
+        }
+        drop(reporter);
+        let stats = task.await.unwrap();
+
+        assert_eq!(*sink.0.lock().unwrap(), [2, 2, 1]);
+        assert_eq!(
+            stats,
+            BatchStats {
+                flushed: 5,
+                dropped: 0
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_flushes_on_interval() {
+        let sink = Collect::default();
+        let config = BatchConfig {
+            interval: Duration::from_millis(20),
+            ..Default::default()
+        };
+        let (reporter, _task) = BatchingReporter::spawn(sink.clone(), config);
+
+        reporter.report(&AnyError::msg("boom"));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        assert_eq!(*sink.0.lock().unwrap(), [1]);
+    }
+
+    #[tokio::test]
+    async fn test_drops_when_full() {
+        let config = BatchConfig {
+            capacity: 2,
+            ..Default::default()
+        };
+        let (reporter, _task) = BatchingReporter::spawn(Collect::default(), config);
+
+        for _ in 0..5 {
+            reporter.report(&AnyError::msg("boom"));
+        }
+
+        assert_eq!(reporter.stats().dropped, 3);
+    }
+}
@@ -0,0 +1,403 @@
+use std::{mem, sync::Arc};
+
+use serde::Serialize;
+
+use crate::{AnyError, ContextDetails, truncate::truncate};
+
+/// Extension set on the outermost error of a degraded [`AnyError::serialize_bounded`] output,
+/// listing what was removed.
+pub const DROPPED_EXTENSION: &str = "dropped";
+
+/// Message lengths, in characters, tried in turn once traces and attachments are gone.
+const MESSAGE_LIMITS: [usize; 3] = [1024, 256, 64];
+
+/// One step taken to fit an error into a byte budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum Dropped {
+    Backtraces,
+    SpanTraces,
+    Locations,
+    /// Only taken with the `time` feature.
+    Timestamps,
+    Attachments,
+    Snippets,
+    /// The [related](AnyError::with_related) errors of every layer.
+    Related,
+    /// The [metadata](AnyError::with_data) of every layer.
+    Data,
+    /// Messages longer than `max_chars` were cut and end in `…`.
+    #[serde(rename_all = "camelCase")]
+    Messages {
+        max_chars: usize,
+    },
+    Extensions,
+    /// The innermost `count` causes were removed, the deepest first.
+    Causes {
+        count: usize,
+    },
+    /// Everything but the `$type` and message of the outermost error. If the list of what was
+    /// dropped doesn't fit either, it is left out too.
+    Details,
+}
+
+/// The output of [`AnyError::serialize_bounded`].
+#[derive(Debug, Clone)]
+pub struct Bounded {
+    pub json: String,
+    /// Empty if the error fit as is.
+    pub dropped: Vec<Dropped>,
+}
+
+impl Bounded {
+    /// Whether the output is within the budget. Only false when even the `$type` and shortened
+    /// message of the outermost error are larger than the budget.
+    pub fn fits(&self, max_bytes: usize) -> bool {
+        self.json.len() <= max_bytes
+    }
+}
+
+impl AnyError {
+    /// Serializes to JSON of at most `max_bytes`, degrading in order: backtraces, span traces,
+    /// locations, timestamps, attachments, snippets, related errors, metadata, message
+    /// length, extensions, the innermost causes, then every other detail. The `$type` and
+    /// message of the outermost error are always kept, so check [`Bounded::fits`] for budgets
+    /// too small to hold them.
+    pub fn serialize_bounded(&self, max_bytes: usize) -> serde_json::Result<Bounded> {
+        let mut bounded = Bounded {
+            json: serde_json::to_string(self)?,
+            dropped: Vec::new(),
+        };
+        let mut error = self.clone();
+
+        let steps = [
+            Dropped::Backtraces,
+            Dropped::SpanTraces,
+            Dropped::Locations,
+            Dropped::Timestamps,
+            Dropped::Attachments,
+            Dropped::Snippets,
+            Dropped::Related,
+            Dropped::Data,
+        ]
+        .into_iter()
+        .chain(MESSAGE_LIMITS.map(|max_chars| Dropped::Messages { max_chars }))
+        .chain([Dropped::Extensions]);
+        for step in steps {
+            if bounded.fits(max_bytes) {
+                return Ok(bounded);
+            }
+            if degrade(&mut error, step) {
+                record(&mut bounded.dropped, step);
+                bounded.json = annotated(&error, &bounded.dropped)?;
+            }
+        }
+
+        if !bounded.fits(max_bytes) {
+            let trimmer = CauseTrimmer::new(&error);
+            // The fewest causes to drop, found by bisection so the tree is rebuilt and
+            // serialized a logarithmic number of times. Dropping more never grows the output.
+            let (mut low, mut high) = (1, trimmer.len());
+            let mut fitting = None;
+            while low <= high {
+                let count = low + (high - low) / 2;
+                let mut dropped = bounded.dropped.clone();
+                dropped.push(Dropped::Causes { count });
+                let trimmed = trimmer.without(count);
+                let json = annotated(&trimmed, &dropped)?;
+                if json.len() <= max_bytes {
+                    fitting = Some((trimmed, dropped, json));
+                    high = count - 1;
+                } else {
+                    low = count + 1;
+                }
+            }
+            match fitting {
+                Some((trimmed, dropped, json)) => {
+                    error = trimmed;
+                    bounded = Bounded { json, dropped };
+                }
+                None if trimmer.len() > 0 => {
+                    error = trimmer.without(trimmer.len());
+                    record(
+                        &mut bounded.dropped,
+                        Dropped::Causes {
+                            count: trimmer.len(),
+                        },
+                    );
+                    bounded.json = annotated(&error, &bounded.dropped)?;
+                }
+                None => {}
+            }
+        }
+
+        if !bounded.fits(max_bytes) {
+            if degrade(&mut error, Dropped::Details) {
+                record(&mut bounded.dropped, Dropped::Details);
+                bounded.json = annotated(&error, &bounded.dropped)?;
+            }
+            if !bounded.fits(max_bytes) {
+                bounded.json = serde_json::to_string(&error)?;
+            }
+        }
+
+        Ok(bounded)
+    }
+}
+
+/// Adds `step` to `dropped`, replacing a previous step of the same kind.
+fn record(dropped: &mut Vec<Dropped>, step: Dropped) {
+    match dropped.last_mut() {
+        Some(last) if mem::discriminant(last) == mem::discriminant(&step) => *last = step,
+        _ => dropped.push(step),
+    }
+}
+
+/// `error` serialized with the list of what was `dropped` from it.
+fn annotated(error: &AnyError, dropped: &[Dropped]) -> serde_json::Result<String> {
+    serde_json::to_string(&error.clone().with_extension(DROPPED_EXTENSION, dropped))
+}
+
+/// Applies `step` to every layer, cause and related error, returning whether anything
+/// changed.
+fn degrade(error: &mut AnyError, step: Dropped) -> bool {
+    let mut changed = false;
+    let mut stack = vec![error];
+    while let Some(error) = stack.pop() {
+        let context = error.make_context_mut();
+        let details = &mut *context.details;
+        changed |= match step {
+            Dropped::Backtraces => details.backtrace.take().is_some(),
+            Dropped::SpanTraces => details.span_trace.take().is_some(),
+            Dropped::Locations => details.location.take().is_some(),
+            #[cfg(feature = "time")]
+            Dropped::Timestamps => details.timestamp.take().is_some(),
+            #[cfg(not(feature = "time"))]
+            Dropped::Timestamps => false,
+            Dropped::Attachments => !mem::take(&mut details.attachments).is_empty(),
+            Dropped::Snippets => details.snippet.take().is_some(),
+            Dropped::Related => !mem::take(&mut details.related).is_empty(),
+            Dropped::Data => !mem::take(&mut details.data).is_empty(),
+            Dropped::Messages { max_chars } => truncate(&mut context.message, max_chars),
+            Dropped::Extensions => !mem::take(&mut details.extensions).is_empty(),
+            // Removed by `CauseTrimmer`.
+            Dropped::Causes { .. } => false,
+            Dropped::Details => {
+                let inner_errors = mem::take(&mut details.inner_errors);
+                let serialized = serde_json::to_value(&*details).unwrap_or_default();
+                *details = ContextDetails {
+                    inner_errors,
+                    ..ContextDetails::default()
+                };
+                serialized
+                    .as_object()
+                    .is_some_and(|fields| !fields.is_empty())
+            }
+        };
+        let details = &mut *context.details;
+        stack.extend(
+            context
+                .inner_error
+                .iter_mut()
+                .chain(&mut details.inner_errors)
+                .map(Arc::make_mut)
+                .chain(&mut details.related),
+        );
+    }
+    changed
+}
+
+/// The layers of a tree in [`AnyError::split_layers`] order, and the order its causes are
+/// dropped in: the deepest first and, among equally deep ones, the last of their siblings.
+/// Layers never get deeper, so the deepest remaining one is always a leaf.
+struct CauseTrimmer {
+    layers: Vec<(AnyError, usize)>,
+    parents: Vec<Option<usize>>,
+    order: Vec<usize>,
+}
+
+impl CauseTrimmer {
+    fn new(error: &AnyError) -> Self {
+        let layers = error.split_layers().collect::<Vec<_>>();
+        let mut parents = Vec::with_capacity(layers.len());
+        let mut depths = Vec::with_capacity(layers.len());
+        // The layers whose causes are still being listed, with how many are left.
+        let mut open: Vec<(usize, usize)> = Vec::new();
+        for (index, (_, causes)) in layers.iter().enumerate() {
+            while let Some((_, 0)) = open.last() {
+                open.pop();
+            }
+            depths.push(open.len());
+            parents.push(open.last_mut().map(|(parent, remaining)| {
+                *remaining -= 1;
+                *parent
+            }));
+            open.push((index, *causes));
+        }
+
+        let mut order = (1..layers.len()).collect::<Vec<_>>();
+        order.sort_unstable_by(|a, b| (depths[*b], *b).cmp(&(depths[*a], *a)));
+        Self {
+            layers,
+            parents,
+            order,
+        }
+    }
+
+    /// How many causes there are to drop.
+    fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    /// The tree without the first `count` causes of the drop order.
+    fn without(&self, count: usize) -> AnyError {
+        let mut kept = vec![true; self.layers.len()];
+        let mut causes = self
+            .layers
+            .iter()
+            .map(|(_, causes)| *causes)
+            .collect::<Vec<_>>();
+        for &index in &self.order[..count] {
+            kept[index] = false;
+            if let Some(parent) = self.parents[index] {
+                causes[parent] -= 1;
+            }
+        }
+        let layers = self
+            .layers
+            .iter()
+            .zip(causes)
+            .zip(kept)
+            .filter(|(_, kept)| *kept)
+            .map(|(((layer, _), causes), _)| (layer.clone(), causes));
+        AnyError::join_layers(layers).expect("dropping the deepest layers keeps a single tree")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain(depth: usize, message_len: usize) -> AnyError {
+        let mut error = AnyError::msg("x".repeat(message_len)).attach(vec![0u8; 256]);
+        for _ in 1..depth {
+            error.wrap_with("y".repeat(message_len));
+        }
+        error
+    }
+
+    #[test]
+    fn test_fits_untouched() {
+        let error = AnyError::msg("small");
+        let bounded = error.serialize_bounded(1024).unwrap();
+
+        assert!(bounded.dropped.is_empty());
+        assert_eq!(bounded.json, serde_json::to_string(&error).unwrap());
+    }
+
+    #[test]
+    fn test_degrades_in_order() {
+        let error = chain(4, 2000);
+        let mut provenance = vec![Dropped::Locations];
+        if cfg!(feature = "time") {
+            provenance.push(Dropped::Timestamps);
+        }
+
+        let bounded = error.serialize_bounded(4000).unwrap();
+        assert!(bounded.fits(4000));
+        assert_eq!(
+            bounded.dropped,
+            [
+                provenance.as_slice(),
+                &[Dropped::Attachments, Dropped::Messages { max_chars: 256 }]
+            ]
+            .concat()
+        );
+
+        let bounded = error.serialize_bounded(440).unwrap();
+        assert!(bounded.fits(440));
+        assert_eq!(
+            bounded.dropped,
+            [
+                provenance.as_slice(),
+                &[
+                    Dropped::Attachments,
+                    Dropped::Messages { max_chars: 64 },
+                    Dropped::Causes { count: 2 },
+                ]
+            ]
+            .concat()
+        );
+
+        let value: serde_json::Value = serde_json::from_str(&bounded.json).unwrap();
+        assert_eq!(
+            value["context"]["extensions"]["dropped"][provenance.len() + 2]["count"],
+            2
+        );
+    }
+
+    #[test]
+    fn test_aggregate() {
+        let causes = (0..20).map(|index| {
+            AnyError::msg(format!("upload {index} failed: {}", "x".repeat(2000)))
+                .with_data("payload", "y".repeat(2000))
+                .with_related(AnyError::msg("z".repeat(2000)))
+        });
+        let error = AnyError::msg("20 of 20 uploads failed").with_causes(causes);
+
+        let bounded = error.serialize_bounded(1000).unwrap();
+        assert!(bounded.fits(1000), "{}", bounded.json);
+        assert!(bounded.dropped.contains(&Dropped::Related));
+        assert!(bounded.dropped.contains(&Dropped::Data));
+        let value: serde_json::Value = serde_json::from_str(&bounded.json).unwrap();
+        let kept = value["context"]["innerErrors"]
+            .as_array()
+            .map_or(0, Vec::len);
+        assert!(kept > 1 && kept < 20, "{kept} causes kept");
+        assert_eq!(
+            bounded.dropped.last(),
+            Some(&Dropped::Causes { count: 20 - kept })
+        );
+        assert!(
+            value["context"]["innerErrors"][0]["context"]["message"]
+                .as_str()
+                .unwrap()
+                .starts_with("upload 0 failed")
+        );
+    }
+
+    #[test]
+    fn test_type_and_message_last() {
+        let error = AnyError::msg("boom")
+            .with_help("h".repeat(100))
+            .with_docs_url("https://docs.example.com/boom")
+            .with_extension("code", "E1");
+        let bare = serde_json::to_string(&AnyError::from_parts(crate::MESSAGE_TYPE, "boom", None))
+            .unwrap();
+
+        let bounded = error.serialize_bounded(bare.len()).unwrap();
+        assert!(bounded.fits(bare.len()));
+        assert_eq!(bounded.json, bare);
+        assert_eq!(bounded.dropped.last(), Some(&Dropped::Details));
+    }
+
+    #[test]
+    fn test_deep_chain() {
+        let bounded = chain(20_000, 10).serialize_bounded(200).unwrap();
+
+        assert!(bounded.fits(200), "{}", bounded.json);
+        assert!(
+            matches!(bounded.dropped.last(), Some(Dropped::Causes { count }) if *count > 19_000),
+            "{:?}",
+            bounded.dropped
+        );
+    }
+
+    #[test]
+    fn test_keeps_outermost() {
+        let bounded = chain(3, 100).serialize_bounded(10).unwrap();
+
+        assert!(!bounded.fits(10));
+        assert_eq!(bounded.dropped.last(), Some(&Dropped::Causes { count: 2 }));
+    }
+}
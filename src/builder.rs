@@ -0,0 +1,106 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::{AnyError, CODE_EXTENSION, MESSAGE_TYPE, docs};
+
+/// Builds a layer without an `Error` value, e.g. from a foreign payload or an FFI status, see
+/// [`AnyError::builder`].
+#[derive(Debug, Clone, Default)]
+#[must_use]
+pub struct AnyErrorBuilder {
+    r#type: Option<String>,
+    message: String,
+    source: Option<AnyError>,
+    code: Option<String>,
+    data: BTreeMap<String, serde_json::Value>,
+}
+
+impl AnyError {
+    /// `AnyError::builder().type_name("billing.CardDeclined").message("card declined").build()`
+    pub fn builder() -> AnyErrorBuilder {
+        AnyErrorBuilder::default()
+    }
+}
+
+impl AnyErrorBuilder {
+    /// The `$type`, [`MESSAGE_TYPE`] if not set.
+    pub fn type_name(mut self, r#type: impl Into<String>) -> Self {
+        self.r#type = Some(r#type.into());
+        self
+    }
+
+    pub fn message(mut self, message: impl Into<String>) -> Self {
+        self.message = message.into();
+        self
+    }
+
+    /// The inner error.
+    #[track_caller]
+    pub fn source(mut self, source: impl Into<AnyError>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    /// Stored as the [`CODE_EXTENSION`], and looked up in the [docs registry](crate::docs)
+    /// before the `$type`.
+    pub fn code(mut self, code: impl Into<String>) -> Self {
+        self.code = Some(code.into());
+        self
+    }
+
+    /// See [`AnyError::with_data`].
+    pub fn data(mut self, key: impl Into<String>, value: impl Serialize) -> Self {
+        self.data
+            .insert(key.into(), serde_json::to_value(value).unwrap_or_default());
+        self
+    }
+
+    #[track_caller]
+    pub fn build(self) -> AnyError {
+        let mut error = AnyError::new(
+            self.r#type.unwrap_or_else(|| MESSAGE_TYPE.to_string()),
+            self.message,
+            self.source,
+        );
+        error.record_creation();
+        if let Some(code) = self.code {
+            error = error.with_extension(CODE_EXTENSION, code);
+        }
+        error.context.details.docs_url = docs::url_for_error(&error);
+        error.context.details.data = self.data;
+        error
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build() {
+        let error = AnyError::builder()
+            .type_name("billing.CardDeclined")
+            .message("card declined")
+            .code("PAY042")
+            .data("orderId", 1234)
+            .source(AnyError::msg("insufficient funds"))
+            .build();
+
+        assert_eq!(error.r#type, "billing.CardDeclined");
+        assert_eq!(error.message(), "card declined");
+        assert_eq!(error.extension(CODE_EXTENSION), Some(&"PAY042".into()));
+        assert_eq!(error.data("orderId"), Some(&1234.into()));
+        assert_eq!(error.inner().unwrap().message(), "insufficient funds");
+        assert_eq!(error.location().unwrap().line, line!() - 7);
+    }
+
+    #[test]
+    fn test_defaults() {
+        let error = AnyError::builder().message("something failed").build();
+
+        assert_eq!(error.r#type, MESSAGE_TYPE);
+        assert!(error.inner().is_none());
+        assert!(error.context.extensions().is_empty());
+    }
+}
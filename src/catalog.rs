@@ -0,0 +1,165 @@
+use std::{
+    collections::BTreeSet,
+    sync::{LazyLock, RwLock},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{AnyError, config, tag::TypeTag};
+
+/// The set of `$type` tags an application can emit.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Catalog {
+    tags: BTreeSet<String>,
+}
+
+impl Catalog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a tag, returning `false` if it was already registered.
+    pub fn register(&mut self, tag: impl Into<String>) -> bool {
+        self.tags.insert(tag.into())
+    }
+
+    /// Registers every tag `T` can produce, under the configured namespace.
+    pub fn register_type<T: TypeTag>(&mut self) {
+        let config = config::current();
+        self.tags.extend(
+            T::known_tags()
+                .into_iter()
+                .map(|tag| config.namespaced(tag)),
+        );
+    }
+
+    pub fn is_known(&self, tag: &str) -> bool {
+        self.tags.contains(tag)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.tags.iter().map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.tags.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+    }
+
+    /// The `$type`s along `error`'s chain that are not in the catalog, outermost first.
+    pub fn unknown_types<'a>(&self, error: &'a AnyError) -> Vec<&'a str> {
+        let mut unknown = Vec::new();
+        let mut node = Some(error);
+        while let Some(error) = node {
+            if !self.is_known(&error.r#type) {
+                unknown.push(error.r#type.as_str());
+            }
+            node = error.inner();
+        }
+        unknown
+    }
+}
+
+static CATALOG: LazyLock<RwLock<Catalog>> = LazyLock::new(Default::default);
+
+pub fn register(tag: impl Into<String>) -> bool {
+    CATALOG
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .register(tag)
+}
+
+pub fn register_type<T: TypeTag>() {
+    CATALOG
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .register_type::<T>();
+}
+
+pub fn is_known(tag: &str) -> bool {
+    CATALOG
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .is_known(tag)
+}
+
+/// A copy of the global catalog, e.g. for serving the listing or generating client enums.
+pub fn snapshot() -> Catalog {
+    CATALOG.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TaggedError;
+
+    impl TypeTag for TaggedError {
+        fn type_tag(&self) -> String {
+            "app.tagged_error".to_string()
+        }
+
+        fn known_tags() -> Vec<String> {
+            vec!["app.tagged_error".to_string()]
+        }
+    }
+
+    #[test]
+    fn test_register_and_lookup() {
+        let mut catalog = Catalog::new();
+
+        assert!(catalog.register("app.user.not_found"));
+        assert!(!catalog.register("app.user.not_found"));
+        catalog.register_type::<TaggedError>();
+
+        assert!(catalog.is_known("app.user.not_found"));
+        assert!(catalog.is_known("app.tagged_error"));
+        assert!(!catalog.is_known("app.other"));
+        assert_eq!(
+            catalog.iter().collect::<Vec<_>>(),
+            vec!["app.tagged_error", "app.user.not_found"]
+        );
+    }
+
+    #[test]
+    fn test_unknown_types() {
+        let mut catalog = Catalog::new();
+        catalog.register("app.outer");
+
+        let error = AnyError::new(
+            "app.outer".to_string(),
+            "outer".to_string(),
+            Some(AnyError::new(
+                "vendor.Error".to_string(),
+                "inner".to_string(),
+                None,
+            )),
+        );
+
+        assert_eq!(catalog.unknown_types(&error), vec!["vendor.Error"]);
+    }
+
+    #[test]
+    fn test_serialized_listing() {
+        let mut catalog = Catalog::new();
+        catalog.register("app.b");
+        catalog.register("app.a");
+
+        assert_eq!(
+            serde_json::to_value(&catalog).unwrap(),
+            serde_json::json!({ "tags": ["app.a", "app.b"] })
+        );
+    }
+
+    #[test]
+    fn test_global_registry() {
+        register("catalog.tests.global");
+
+        assert!(is_known("catalog.tests.global"));
+        assert!(snapshot().is_known("catalog.tests.global"));
+    }
+}
@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+
+use crate::AnyError;
+
+/// A coarse classification of an error, for routing, alerting and client handling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, valuable::Valuable)]
+#[serde(rename_all = "camelCase")]
+pub enum Category {
+    /// The input was malformed or failed validation.
+    Validation,
+    NotFound,
+    Conflict,
+    Unauthorized,
+    Forbidden,
+    RateLimited,
+    Timeout,
+    Unavailable,
+    Internal,
+}
+
+impl Category {
+    /// The HTTP status an error of this category is answered with when nothing more specific
+    /// is declared, see [`AnyError::http_status`].
+    pub fn http_status(self) -> u16 {
+        match self {
+            Self::Validation => 400,
+            Self::NotFound => 404,
+            Self::Conflict => 409,
+            Self::Unauthorized => 401,
+            Self::Forbidden => 403,
+            Self::RateLimited => 429,
+            Self::Timeout => 504,
+            Self::Unavailable => 503,
+            Self::Internal => 500,
+        }
+    }
+}
+
+impl AnyError {
+    pub fn with_category(mut self, category: Category) -> Self {
+        self.context.details.category = Some(category);
+        self
+    }
+
+    /// The category of the outermost layer that has one.
+    pub fn category(&self) -> Option<Category> {
+        let mut node = Some(self);
+        while let Some(error) = node {
+            if let Some(category) = error.context.details.category {
+                return Some(category);
+            }
+            node = error.inner();
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_category_from_chain() {
+        let mut error = AnyError::msg("no user").with_category(Category::NotFound);
+        error.wrap_with("while loading profile");
+
+        assert_eq!(error.category(), Some(Category::NotFound));
+        assert_eq!(AnyError::msg("plain").category(), None);
+    }
+
+    #[test]
+    fn test_category_serialization() {
+        let error = AnyError::msg("bad").with_category(Category::RateLimited);
+        let json = serde_json::to_value(&error).unwrap();
+
+        assert_eq!(json["context"]["category"], "rateLimited");
+        let round_trip: AnyError = serde_json::from_value(json).unwrap();
+        assert_eq!(round_trip.category(), Some(Category::RateLimited));
+    }
+}
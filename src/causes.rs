@@ -0,0 +1,164 @@
+//! Errors with several independent causes, such as a batch of concurrent tasks. A single
+//! cause serializes as `innerError`, more than one as `innerErrors`. Chain walks such as
+//! [`AnyError::inner`] follow the first cause.
+
+use std::sync::Arc;
+
+use crate::AnyError;
+
+/// `$type` of errors built by [`AnyError::from_results`].
+pub const AGGREGATE_TYPE: &str = "Aggregate";
+
+impl AnyError {
+    /// The direct causes of this layer.
+    pub fn causes(&self) -> impl Iterator<Item = &AnyError> {
+        self.context
+            .inner_error
+            .iter()
+            .chain(&self.context.details.inner_errors)
+            .map(Arc::as_ref)
+    }
+
+    /// Adds `cause` next to the existing causes of this layer, unlike
+    /// [`AnyError::push_cause`] which appends below the innermost error.
+    pub fn add_cause(&mut self, cause: AnyError) {
+        let context = &mut self.context;
+        match context.inner_error.take() {
+            None if context.details.inner_errors.is_empty() => {
+                context.inner_error = Some(Arc::new(cause))
+            }
+            first => {
+                context.details.inner_errors.extend(first);
+                context.details.inner_errors.push(Arc::new(cause));
+            }
+        }
+    }
+
+    /// Replaces the causes of this layer.
+    pub fn with_causes(mut self, causes: impl IntoIterator<Item = AnyError>) -> Self {
+        self.set_inner(None);
+        for cause in causes {
+            self.add_cause(cause);
+        }
+        self
+    }
+
+    /// Collects the results of a batch, e.g. from `join_all`, failing with an
+    /// [`AGGREGATE_TYPE`] error caused by every failure if any of them failed.
+    #[track_caller]
+    pub fn from_results<T, E: Into<AnyError>>(
+        results: impl IntoIterator<Item = Result<T, E>>,
+    ) -> Result<Vec<T>, AnyError> {
+        let (values, failures) = partition_results(results);
+        if failures.is_empty() {
+            return Ok(values);
+        }
+
+        let total = values.len() + failures.len();
+        Err(AnyError::aggregate(failures, total))
+    }
+
+    /// An [`AGGREGATE_TYPE`] error caused by `failures` out of `total` operations.
+    #[track_caller]
+    pub(crate) fn aggregate(failures: Vec<AnyError>, total: usize) -> Self {
+        AnyError::msg(aggregate_message(failures.len(), total))
+            .with_type(AGGREGATE_TYPE)
+            .with_causes(failures)
+    }
+}
+
+/// The message of [`AGGREGATE_TYPE`] errors, e.g. `2 of 5 operations failed`.
+pub(crate) fn aggregate_message(failed: usize, total: usize) -> String {
+    format!("{failed} of {total} operations failed")
+}
+
+/// Splits a batch into its values and its failures, converting each failure where it is
+/// collected.
+#[track_caller]
+pub(crate) fn partition_results<T, E: Into<AnyError>>(
+    results: impl IntoIterator<Item = Result<T, E>>,
+) -> (Vec<T>, Vec<AnyError>) {
+    let mut values = Vec::new();
+    let mut failures = Vec::new();
+    for result in results {
+        match result {
+            Ok(value) => values.push(value),
+            Err(error) => failures.push(error.into()),
+        }
+    }
+    (values, failures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_cause_unchanged() {
+        let mut error = AnyError::msg("outer");
+        error.add_cause(AnyError::msg("only"));
+
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["context"]["innerError"]["context"]["message"], "only");
+        assert!(json["context"].get("innerErrors").is_none());
+        assert_eq!(error.causes().count(), 1);
+    }
+
+    #[test]
+    fn test_multiple_causes() {
+        let error = AnyError::msg("replication failed").with_causes([
+            AnyError::msg("replica a unreachable"),
+            AnyError::msg("replica b rejected write"),
+        ]);
+
+        assert_eq!(
+            error.causes().map(AnyError::message).collect::<Vec<_>>(),
+            vec!["replica a unreachable", "replica b rejected write"]
+        );
+        assert_eq!(error.inner().unwrap().message(), "replica a unreachable");
+        assert_eq!(
+            error.to_string(),
+            "Message: replication failed(Message: replica a unreachable; Message: replica b rejected write)"
+        );
+
+        let json = serde_json::to_value(&error).unwrap();
+        assert!(json["context"]["innerError"].is_null());
+        assert_eq!(
+            json["context"]["innerErrors"][1]["context"]["message"],
+            "replica b rejected write"
+        );
+        let decoded: AnyError = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded.causes().count(), 2);
+    }
+
+    #[test]
+    fn test_from_results() {
+        let results: Vec<Result<u32, AnyError>> = vec![
+            Ok(1),
+            Err(AnyError::msg("row 2 invalid")),
+            Ok(3),
+            Err(AnyError::msg("row 4 invalid")),
+        ];
+        let error = AnyError::from_results(results).unwrap_err();
+
+        assert_eq!(error.r#type, AGGREGATE_TYPE);
+        assert_eq!(error.message(), "2 of 4 operations failed");
+        assert_eq!(error.causes().count(), 2);
+
+        let ok = AnyError::from_results([Ok::<_, AnyError>(1), Ok(2)]).unwrap();
+        assert_eq!(ok, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_set_inner_replaces_all() {
+        let mut error =
+            AnyError::msg("outer").with_causes([AnyError::msg("a"), AnyError::msg("b")]);
+
+        let previous = error.set_inner(Some(AnyError::msg("c")));
+        assert_eq!(previous.unwrap().message(), "a");
+        assert_eq!(
+            error.causes().map(AnyError::message).collect::<Vec<_>>(),
+            vec!["c"]
+        );
+    }
+}
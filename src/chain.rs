@@ -0,0 +1,62 @@
+use std::iter::FusedIterator;
+
+use crate::AnyError;
+
+/// Iterator over an error and its inner errors, outermost first, see [`AnyError::chain`].
+#[derive(Debug, Clone)]
+pub struct Chain<'a> {
+    next: Option<&'a AnyError>,
+}
+
+impl<'a> Iterator for Chain<'a> {
+    type Item = &'a AnyError;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let error = self.next?;
+        self.next = error.inner();
+        Some(error)
+    }
+}
+
+impl FusedIterator for Chain<'_> {}
+
+impl AnyError {
+    /// This error followed by each inner error, outermost first. Only the first of several
+    /// [causes](AnyError::causes) is followed.
+    pub fn chain(&self) -> Chain<'_> {
+        Chain { next: Some(self) }
+    }
+
+    /// The innermost error of the chain, `self` if there is no inner error.
+    pub fn root_cause(&self) -> &AnyError {
+        self.chain().last().unwrap_or(self)
+    }
+
+    /// The number of errors in the chain, at least 1.
+    pub fn depth(&self) -> usize {
+        self.chain().count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chain() {
+        let mut error = AnyError::msg("connection refused");
+        error.wrap_with("loading user");
+        error.wrap_with("handling request");
+
+        assert_eq!(
+            error.chain().map(AnyError::message).collect::<Vec<_>>(),
+            ["handling request", "loading user", "connection refused"]
+        );
+        assert_eq!(error.root_cause().message(), "connection refused");
+        assert_eq!(error.depth(), 3);
+
+        let single = AnyError::msg("alone");
+        assert_eq!(single.root_cause().message(), "alone");
+        assert_eq!(single.depth(), 1);
+    }
+}
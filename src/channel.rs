@@ -0,0 +1,295 @@
+//! Channel send and receive errors. Their messages only say that the channel is closed, so
+//! the conversions record the state of the channel and, for sends, the type of the payload
+//! that was handed back.
+
+use std::sync::mpsc;
+
+use serde::Serialize;
+
+use crate::{AnyError, IntoAnyError, type_name::standardized_type_name_of};
+
+pub const STATE_EXTENSION: &str = "channelState";
+pub const PAYLOAD_TYPE_EXTENSION: &str = "payloadType";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChannelState {
+    /// The other side was dropped.
+    Closed,
+    Full,
+    Empty,
+    TimedOut,
+    /// A broadcast receiver fell behind and missed messages.
+    Lagged,
+}
+
+#[track_caller]
+fn send_error<E, T>(error: &E, payload: &T, state: ChannelState) -> AnyError {
+    let payload_type = standardized_type_name_of(payload);
+    let message = match state {
+        ChannelState::Full => format!("sending {payload_type} on a full channel"),
+        ChannelState::TimedOut => format!("timed out sending {payload_type} on a channel"),
+        _ => format!("sending {payload_type} on a closed channel"),
+    };
+    channel_error(error, message, state).with_extension(PAYLOAD_TYPE_EXTENSION, payload_type)
+}
+
+#[track_caller]
+fn recv_error<E>(error: &E, state: ChannelState) -> AnyError {
+    let message = match state {
+        ChannelState::Empty => "receiving on an empty channel",
+        ChannelState::TimedOut => "timed out receiving on a channel",
+        _ => "receiving on a closed channel",
+    };
+    channel_error(error, message.to_string(), state)
+}
+
+/// Converted like any other value, so the `$type` is namespaced and its docs URL looked up.
+#[track_caller]
+fn channel_error<E>(error: &E, message: String, state: ChannelState) -> AnyError {
+    let error = AnyError::capture_text::<E>(standardized_type_name_of(error), message)
+        .with_extension(STATE_EXTENSION, state);
+    match state {
+        ChannelState::TimedOut => error.with_timeout(None, None),
+        _ => error,
+    }
+}
+
+impl<T> IntoAnyError for mpsc::SendError<T> {
+    fn into_any_error(self) -> AnyError {
+        send_error(&self, &self.0, ChannelState::Closed)
+    }
+}
+
+impl<T> IntoAnyError for mpsc::TrySendError<T> {
+    fn into_any_error(self) -> AnyError {
+        match &self {
+            mpsc::TrySendError::Full(payload) => send_error(&self, payload, ChannelState::Full),
+            mpsc::TrySendError::Disconnected(payload) => {
+                send_error(&self, payload, ChannelState::Closed)
+            }
+        }
+    }
+}
+
+impl IntoAnyError for mpsc::RecvError {
+    fn into_any_error(self) -> AnyError {
+        recv_error(&self, ChannelState::Closed)
+    }
+}
+
+impl IntoAnyError for mpsc::TryRecvError {
+    fn into_any_error(self) -> AnyError {
+        match self {
+            mpsc::TryRecvError::Empty => recv_error(&self, ChannelState::Empty),
+            mpsc::TryRecvError::Disconnected => recv_error(&self, ChannelState::Closed),
+        }
+    }
+}
+
+impl IntoAnyError for mpsc::RecvTimeoutError {
+    fn into_any_error(self) -> AnyError {
+        match self {
+            mpsc::RecvTimeoutError::Timeout => recv_error(&self, ChannelState::TimedOut),
+            mpsc::RecvTimeoutError::Disconnected => recv_error(&self, ChannelState::Closed),
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+mod tokio_channels {
+    use tokio::sync::{broadcast, mpsc, oneshot};
+
+    use super::*;
+
+    impl<T> IntoAnyError for mpsc::error::SendError<T> {
+        fn into_any_error(self) -> AnyError {
+            send_error(&self, &self.0, ChannelState::Closed)
+        }
+    }
+
+    impl<T> IntoAnyError for mpsc::error::TrySendError<T> {
+        fn into_any_error(self) -> AnyError {
+            match &self {
+                mpsc::error::TrySendError::Full(payload) => {
+                    send_error(&self, payload, ChannelState::Full)
+                }
+                mpsc::error::TrySendError::Closed(payload) => {
+                    send_error(&self, payload, ChannelState::Closed)
+                }
+            }
+        }
+    }
+
+    impl<T> IntoAnyError for mpsc::error::SendTimeoutError<T> {
+        fn into_any_error(self) -> AnyError {
+            match &self {
+                mpsc::error::SendTimeoutError::Timeout(payload) => {
+                    send_error(&self, payload, ChannelState::TimedOut)
+                }
+                mpsc::error::SendTimeoutError::Closed(payload) => {
+                    send_error(&self, payload, ChannelState::Closed)
+                }
+            }
+        }
+    }
+
+    impl IntoAnyError for mpsc::error::TryRecvError {
+        fn into_any_error(self) -> AnyError {
+            match self {
+                mpsc::error::TryRecvError::Empty => recv_error(&self, ChannelState::Empty),
+                mpsc::error::TryRecvError::Disconnected => recv_error(&self, ChannelState::Closed),
+            }
+        }
+    }
+
+    impl IntoAnyError for oneshot::error::RecvError {
+        fn into_any_error(self) -> AnyError {
+            recv_error(&self, ChannelState::Closed)
+        }
+    }
+
+    impl IntoAnyError for oneshot::error::TryRecvError {
+        fn into_any_error(self) -> AnyError {
+            match self {
+                oneshot::error::TryRecvError::Empty => recv_error(&self, ChannelState::Empty),
+                oneshot::error::TryRecvError::Closed => recv_error(&self, ChannelState::Closed),
+            }
+        }
+    }
+
+    impl<T> IntoAnyError for broadcast::error::SendError<T> {
+        fn into_any_error(self) -> AnyError {
+            send_error(&self, &self.0, ChannelState::Closed)
+        }
+    }
+
+    impl IntoAnyError for broadcast::error::RecvError {
+        fn into_any_error(self) -> AnyError {
+            match self {
+                broadcast::error::RecvError::Closed => recv_error(&self, ChannelState::Closed),
+                broadcast::error::RecvError::Lagged(missed) => channel_error(
+                    &self,
+                    format!("receiver lagged behind and missed {missed} messages"),
+                    ChannelState::Lagged,
+                ),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct Job;
+
+    #[test]
+    fn test_std_send_closed() {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        drop(receiver);
+
+        let error = sender.send(Job).unwrap_err().into_any_error();
+        assert_eq!(error.r#type, "SendError<liberror.channel.tests.Job>");
+        assert_eq!(
+            error.message(),
+            "sending liberror.channel.tests.Job on a closed channel"
+        );
+        assert_eq!(
+            error.extension(STATE_EXTENSION),
+            Some(&serde_json::json!("closed"))
+        );
+        assert_eq!(
+            error.extension(PAYLOAD_TYPE_EXTENSION),
+            Some(&serde_json::json!("liberror.channel.tests.Job"))
+        );
+    }
+
+    #[test]
+    fn test_docs_url() {
+        #[derive(Debug)]
+        struct Documented;
+
+        let r#type = "SendError<liberror.channel.tests.test_docs_url.Documented>";
+        crate::docs::register(r#type, "https://docs.example.com/channels");
+        let (sender, receiver) = mpsc::channel::<Documented>();
+        drop(receiver);
+
+        let line = line!() + 1;
+        let error = sender.send(Documented).unwrap_err().into_any_error();
+        assert_eq!(error.r#type, r#type);
+        assert_eq!(error.location().unwrap().line, line);
+        assert_eq!(error.docs_url(), Some("https://docs.example.com/channels"));
+        assert!(error.context.details.origin.is_some());
+    }
+
+    #[test]
+    fn test_std_full_and_receive() {
+        let (sender, receiver) = mpsc::sync_channel::<u8>(0);
+
+        let error = sender.try_send(1).unwrap_err().into_any_error();
+        assert_eq!(
+            error.extension(STATE_EXTENSION),
+            Some(&serde_json::json!("full"))
+        );
+        assert_eq!(error.message(), "sending u8 on a full channel");
+
+        let error = receiver.try_recv().unwrap_err().into_any_error();
+        assert_eq!(
+            error.extension(STATE_EXTENSION),
+            Some(&serde_json::json!("empty"))
+        );
+
+        let error = receiver
+            .recv_timeout(Duration::from_millis(1))
+            .unwrap_err()
+            .into_any_error();
+        assert!(error.is_timeout());
+
+        drop(sender);
+        let error = receiver.recv().unwrap_err().into_any_error();
+        assert_eq!(error.message(), "receiving on a closed channel");
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_tokio_channels() {
+        use tokio::sync::{broadcast, mpsc, oneshot};
+
+        let (sender, receiver) = mpsc::channel::<String>(1);
+        drop(receiver);
+        let error = sender
+            .send("job".into())
+            .await
+            .unwrap_err()
+            .into_any_error();
+        assert_eq!(
+            error.extension(PAYLOAD_TYPE_EXTENSION),
+            Some(&serde_json::json!("String"))
+        );
+
+        let (sender, receiver) = oneshot::channel::<u8>();
+        drop(sender);
+        let error = receiver.await.unwrap_err().into_any_error();
+        assert_eq!(
+            error.extension(STATE_EXTENSION),
+            Some(&serde_json::json!("closed"))
+        );
+
+        let (sender, mut receiver) = broadcast::channel::<u8>(1);
+        sender.send(1).unwrap();
+        sender.send(2).unwrap();
+        let error = receiver.recv().await.unwrap_err().into_any_error();
+        assert_eq!(
+            error.extension(STATE_EXTENSION),
+            Some(&serde_json::json!("lagged"))
+        );
+        assert_eq!(
+            error.message(),
+            "receiver lagged behind and missed 1 messages"
+        );
+    }
+}
@@ -0,0 +1,97 @@
+/// Declares an enum of error codes, rejecting duplicate codes at compile time.
+///
+/// ```
+/// liberror::define_codes! {
+///     pub enum UserCode {
+///         USR001 => UserNotFound,
+///         USR002 => EmailTaken,
+///     }
+/// }
+///
+/// assert_eq!(UserCode::UserNotFound.code(), "USR001");
+/// assert_eq!(UserCode::from_code("USR002"), Some(UserCode::EmailTaken));
+/// ```
+///
+/// Without an enum header the type is named `ErrorCode`. Reusing a code is an error:
+///
+/// ```compile_fail
+/// liberror::define_codes! {
+///     USR001 => UserNotFound,
+///     USR001 => EmailTaken,
+/// }
+/// ```
+#[macro_export]
+macro_rules! define_codes {
+    ($vis:vis enum $name:ident { $($code:ident => $variant:ident),* $(,)? }) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        $vis enum $name {
+            $($variant,)*
+        }
+
+        // Each code becomes an item in the same scope, so a repeated code fails with
+        // "the name is defined multiple times".
+        #[allow(non_upper_case_globals, dead_code)]
+        const _: () = {
+            $(const $code: () = ();)*
+        };
+
+        impl $name {
+            pub const ALL: &'static [$name] = &[$($name::$variant,)*];
+
+            pub const fn code(&self) -> &'static str {
+                match self {
+                    $($name::$variant => ::core::stringify!($code),)*
+                }
+            }
+
+            pub fn from_code(code: &str) -> ::core::option::Option<Self> {
+                match code {
+                    $(::core::stringify!($code) => ::core::option::Option::Some($name::$variant),)*
+                    _ => ::core::option::Option::None,
+                }
+            }
+        }
+
+        impl ::core::fmt::Display for $name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.write_str(self.code())
+            }
+        }
+    };
+    ($($code:ident => $variant:ident),* $(,)?) => {
+        $crate::define_codes! {
+            pub enum ErrorCode { $($code => $variant,)* }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    mod default_name {
+        crate::define_codes! {
+            USR001 => UserNotFound,
+            USR002 => EmailTaken,
+        }
+    }
+
+    use default_name::ErrorCode;
+
+    #[test]
+    fn test_codes() {
+        assert_eq!(ErrorCode::UserNotFound.code(), "USR001");
+        assert_eq!(ErrorCode::EmailTaken.to_string(), "USR002");
+        assert_eq!(
+            ErrorCode::ALL,
+            &[ErrorCode::UserNotFound, ErrorCode::EmailTaken]
+        );
+    }
+
+    #[test]
+    fn test_from_code() {
+        assert_eq!(
+            ErrorCode::from_code("USR001"),
+            Some(ErrorCode::UserNotFound)
+        );
+        assert_eq!(ErrorCode::from_code("USR999"), None);
+    }
+}
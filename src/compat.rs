@@ -0,0 +1,28 @@
+pub mod anyhow;
+#[cfg(feature = "aws")]
+pub mod aws;
+#[cfg(feature = "axum")]
+pub mod axum;
+#[cfg(feature = "clap")]
+pub mod clap;
+#[cfg(feature = "config-rs")]
+pub mod config_rs;
+#[cfg(any(feature = "mysql_async", feature = "redis", feature = "tokio-postgres"))]
+pub mod db;
+#[cfg(feature = "defmt")]
+pub mod defmt;
+#[cfg(feature = "diesel")]
+pub mod diesel;
+#[cfg(feature = "figment")]
+pub mod figment;
+#[cfg(feature = "kube")]
+pub mod kube;
+#[cfg(feature = "mysql_async")]
+pub mod mysql_async;
+#[cfg(feature = "redis")]
+pub mod redis;
+pub mod serde_json;
+#[cfg(feature = "tokio-postgres")]
+pub mod tokio_postgres;
+#[cfg(feature = "validator")]
+pub mod validator;
@@ -0,0 +1,125 @@
+//! Drop-in replacements for the `anyhow` API backed by [`AnyError`](crate::AnyError), so a codebase can
+//! migrate by swapping `use anyhow::...` for `use liberror::compat::anyhow::...`.
+
+pub use crate::AnyError as Error;
+pub use crate::context::ContextExt as Context;
+pub use crate::{anyhow, bail, ensure};
+
+pub type Result<T, E = Error> = core::result::Result<T, E>;
+
+/// Builds an [`AnyError`](crate::AnyError) from a format string, or converts an error value.
+#[macro_export]
+macro_rules! anyhow {
+    ($msg:literal $(,)?) => {
+        $crate::AnyError::msg(::std::format!($msg))
+    };
+    ($err:expr $(,)?) => {
+        $crate::AnyError::from($err)
+    };
+    ($fmt:expr, $($arg:tt)*) => {
+        $crate::AnyError::msg(::std::format!($fmt, $($arg)*))
+    };
+}
+
+/// Returns early with an [`AnyError`](crate::AnyError), see [`anyhow!`].
+#[macro_export]
+macro_rules! bail {
+    ($($arg:tt)*) => {
+        return ::core::result::Result::Err($crate::anyhow!($($arg)*).into())
+    };
+}
+
+/// Returns early with an [`AnyError`](crate::AnyError) if the condition is false.
+#[macro_export]
+macro_rules! ensure {
+    ($cond:expr $(,)?) => {
+        if !$cond {
+            return ::core::result::Result::Err(
+                $crate::AnyError::msg(::core::concat!(
+                    "Condition failed: `",
+                    ::core::stringify!($cond),
+                    "`"
+                ))
+                .into(),
+            );
+        }
+    };
+    ($cond:expr, $($arg:tt)+) => {
+        if !$cond {
+            $crate::bail!($($arg)+);
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::*;
+    use crate::{CONTEXT_TYPE, MESSAGE_TYPE};
+
+    fn read_config(path: &str) -> Result<String> {
+        ensure!(!path.is_empty(), "no config path given");
+        if path == "missing" {
+            bail!("config {path} not found");
+        }
+        Err(io::Error::new(io::ErrorKind::PermissionDenied, "denied"))
+            .with_context(|| format!("reading {path}"))
+    }
+
+    #[test]
+    fn test_bail_and_ensure() {
+        let error = read_config("").unwrap_err();
+        assert_eq!(error.r#type, MESSAGE_TYPE);
+        assert_eq!(error.message(), "no config path given");
+
+        let error = read_config("missing").unwrap_err();
+        assert_eq!(error.message(), "config missing not found");
+    }
+
+    #[test]
+    fn test_ensure_without_message() {
+        fn check(value: u8) -> Result<()> {
+            ensure!(value < 10);
+            Ok(())
+        }
+
+        assert_eq!(
+            check(12).unwrap_err().message(),
+            "Condition failed: `value < 10`"
+        );
+    }
+
+    #[test]
+    fn test_result_context() {
+        let error = read_config("app.toml").unwrap_err();
+
+        assert_eq!(error.r#type, CONTEXT_TYPE);
+        assert_eq!(error.message(), "reading app.toml");
+        assert_eq!(error.inner().unwrap().message(), "denied");
+    }
+
+    #[test]
+    fn test_context_on_any_error_result() {
+        let result: Result<()> = Err(anyhow!("inner"));
+        let error = result.context("outer").unwrap_err();
+
+        assert_eq!(error.message(), "outer");
+        assert_eq!(error.inner().unwrap().message(), "inner");
+    }
+
+    #[test]
+    fn test_option_context() {
+        let error = None::<u8>.context("value missing").unwrap_err();
+
+        assert_eq!(error.message(), "value missing");
+        assert!(error.inner().is_none());
+    }
+
+    #[test]
+    fn test_anyhow_from_error() {
+        let error = anyhow!(io::Error::other("boom"));
+
+        assert_eq!(error.message(), "boom");
+    }
+}
@@ -0,0 +1,105 @@
+//! [`IntoAnyError`] for the AWS SDK's `SdkError`, shared by every `aws_sdk_*` crate. Records
+//! the extensions:
+//!
+//! - `failure`: `construction`, `timeout`, `dispatch`, `response` or `service`.
+//! - `requestId`: the `x-amzn-requestid` of the response, when one was received.
+//! - `status`: the HTTP status of the response, when one was received.
+//! - `serviceCode` and `serviceMessage`: the error code and message reported by the service,
+//!   which `SdkError`'s `Display` hides.
+//!
+//! Timeouts are categorized [`Category::Timeout`].
+
+use std::error::Error;
+
+use aws_smithy_runtime_api::client::{orchestrator::HttpResponse, result::SdkError};
+use aws_smithy_types::error::metadata::ProvideErrorMetadata;
+use aws_types::request_id::RequestId;
+
+use crate::{AnyError, IntoAnyError, category::Category};
+
+impl<E> IntoAnyError for SdkError<E, HttpResponse>
+where
+    E: Error + ProvideErrorMetadata + Send + Sync + 'static,
+{
+    fn into_any_error(self) -> AnyError {
+        let failure = match &self {
+            SdkError::ConstructionFailure(_) => "construction",
+            SdkError::TimeoutError(_) => "timeout",
+            SdkError::DispatchFailure(_) => "dispatch",
+            SdkError::ResponseError(_) => "response",
+            SdkError::ServiceError(_) => "service",
+            _ => "unknown",
+        };
+        let request_id = self.request_id().map(str::to_string);
+        let status = self
+            .raw_response()
+            .map(|response| response.status().as_u16());
+        let service_code = self.code().map(str::to_string);
+        let service_message = self.message().map(str::to_string);
+
+        let mut error = AnyError::from(self).with_extension("failure", failure);
+        if failure == "timeout" {
+            error = error.with_category(Category::Timeout);
+        }
+        if let Some(request_id) = request_id {
+            error = error.with_extension("requestId", request_id);
+        }
+        if let Some(status) = status {
+            error = error.with_extension("status", status);
+        }
+        if let Some(service_code) = service_code {
+            error = error.with_extension("serviceCode", service_code);
+        }
+        if let Some(service_message) = service_message {
+            error = error.with_extension("serviceMessage", service_message);
+        }
+        error
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aws_smithy_runtime_api::http::StatusCode;
+    use aws_smithy_types::{body::SdkBody, error::ErrorMetadata};
+
+    use super::*;
+
+    #[test]
+    fn test_service_error() {
+        let mut raw = HttpResponse::new(StatusCode::try_from(404).unwrap(), SdkBody::empty());
+        raw.headers_mut().insert("x-amzn-requestid", "req-123");
+        let service = ErrorMetadata::builder()
+            .code("NoSuchKey")
+            .message("The specified key does not exist.")
+            .build();
+
+        let error = SdkError::service_error(service, raw).into_any_error();
+
+        assert_eq!(
+            error.extension("failure"),
+            Some(&serde_json::json!("service"))
+        );
+        assert_eq!(
+            error.extension("requestId"),
+            Some(&serde_json::json!("req-123"))
+        );
+        assert_eq!(error.extension("status"), Some(&serde_json::json!(404)));
+        assert_eq!(
+            error.extension("serviceCode"),
+            Some(&serde_json::json!("NoSuchKey"))
+        );
+    }
+
+    #[test]
+    fn test_timeout() {
+        let error = SdkError::<ErrorMetadata, HttpResponse>::timeout_error("operation timed out")
+            .into_any_error();
+
+        assert_eq!(
+            error.extension("failure"),
+            Some(&serde_json::json!("timeout"))
+        );
+        assert_eq!(error.category(), Some(Category::Timeout));
+        assert_eq!(error.extension("requestId"), None);
+    }
+}
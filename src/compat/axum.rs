@@ -0,0 +1,159 @@
+//! [`IntoAnyError`] for axum's extractor rejections, so a failed `Json`, `Path` or `Query`
+//! extraction enters the same pipeline as domain errors.
+//!
+//! Each rejection becomes an [`AnyError`] categorized [`Category::Validation`] (or
+//! [`Category::Internal`] for server-side rejections such as a missing extension), with the
+//! extensions `part` (the failing part of the request), `reason` and
+//! [`HTTP_STATUS_EXTENSION`], so [`AnyError::http_status`] answers with axum's status. Path
+//! rejections also record the offending `key`, `value` and `expectedType` when known.
+
+use axum::{
+    extract::{
+        path::ErrorKind,
+        rejection::{
+            BytesRejection, ExtensionRejection, FormRejection, JsonRejection, PathRejection,
+            QueryRejection, RawFormRejection, RawPathParamsRejection, StringRejection,
+        },
+    },
+    http::StatusCode,
+};
+
+use crate::{AnyError, IntoAnyError, category::Category, mapping::HTTP_STATUS_EXTENSION};
+
+fn from_rejection(error: AnyError, part: &str, status: StatusCode, reason: String) -> AnyError {
+    let category = if status.is_client_error() {
+        Category::Validation
+    } else {
+        Category::Internal
+    };
+
+    error
+        .with_category(category)
+        .with_extension("part", part)
+        .with_extension(HTTP_STATUS_EXTENSION, status.as_u16())
+        .with_extension("reason", reason)
+}
+
+macro_rules! impl_rejection {
+    ($($rejection:ty => $part:literal),* $(,)?) => {
+        $(
+            impl IntoAnyError for $rejection {
+                fn into_any_error(self) -> AnyError {
+                    let (status, reason) = (self.status(), self.body_text());
+                    from_rejection(AnyError::from(self), $part, status, reason)
+                }
+            }
+        )*
+    };
+}
+
+impl_rejection! {
+    JsonRejection => "body",
+    FormRejection => "body",
+    RawFormRejection => "body",
+    BytesRejection => "body",
+    StringRejection => "body",
+    QueryRejection => "query",
+    RawPathParamsRejection => "path",
+    ExtensionRejection => "extension",
+}
+
+impl IntoAnyError for PathRejection {
+    fn into_any_error(self) -> AnyError {
+        let (status, reason) = (self.status(), self.body_text());
+        let (key, value, expected_type) = match &self {
+            PathRejection::FailedToDeserializePathParams(error) => match error.kind() {
+                ErrorKind::ParseErrorAtKey {
+                    key,
+                    value,
+                    expected_type,
+                } => (Some(key.clone()), Some(value.clone()), Some(*expected_type)),
+                ErrorKind::ParseErrorAtIndex {
+                    index,
+                    value,
+                    expected_type,
+                } => (
+                    Some(index.to_string()),
+                    Some(value.clone()),
+                    Some(*expected_type),
+                ),
+                ErrorKind::ParseError {
+                    value,
+                    expected_type,
+                } => (None, Some(value.clone()), Some(*expected_type)),
+                ErrorKind::InvalidUtf8InPathParam { key } => (Some(key.clone()), None, None),
+                ErrorKind::DeserializeError { key, value, .. } => {
+                    (Some(key.clone()), Some(value.clone()), None)
+                }
+                _ => (None, None, None),
+            },
+            _ => (None, None, None),
+        };
+
+        let mut error = from_rejection(AnyError::from(self), "path", status, reason);
+        if let Some(key) = key {
+            error = error.with_extension("key", key);
+        }
+        if let Some(value) = value {
+            error = error.with_extension("value", value);
+        }
+        if let Some(expected_type) = expected_type {
+            error = error.with_extension("expectedType", expected_type);
+        }
+        error
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{Json, extract::Query, http::Uri};
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Params {
+        page: u32,
+    }
+
+    #[test]
+    fn test_query_rejection() {
+        let uri: Uri = "/users?page=abc".parse().unwrap();
+        let rejection = Query::<Params>::try_from_uri(&uri).unwrap_err();
+        let error = rejection.into_any_error();
+
+        assert_eq!(error.category(), Some(Category::Validation));
+        assert_eq!(error.extension("part"), Some(&serde_json::json!("query")));
+        assert_eq!(error.http_status(), 400);
+        assert!(
+            error
+                .extension("reason")
+                .unwrap()
+                .as_str()
+                .unwrap()
+                .starts_with("Failed to deserialize query string")
+        );
+    }
+
+    #[test]
+    fn test_json_rejection() {
+        let rejection = Json::<Params>::from_bytes(br#"{"page": "abc"}"#).unwrap_err();
+        let error = rejection.into_any_error();
+
+        assert_eq!(error.category(), Some(Category::Validation));
+        assert_eq!(error.extension("part"), Some(&serde_json::json!("body")));
+        assert_eq!(error.http_status(), 422);
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn test_rejection_response() {
+        let rejection = Json::<Params>::from_bytes(b"{").unwrap_err();
+        let mut error = rejection.into_any_error();
+        error.wrap_with("creating user");
+
+        let response = error.to_http_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
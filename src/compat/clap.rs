@@ -0,0 +1,105 @@
+//! [`IntoAnyError`] for `clap::Error`, recording the error `kind`, the offending `argument`
+//! and `value` when known, and whether it is a help or version `display` rather than a
+//! failure. clap's intended exit code (0 for displays, 2 for usage errors) is kept, so
+//! [`Exit`](crate::exit::Exit) exits the way `clap::Error::exit` would.
+//!
+//! `?` converts through the blanket `From<E: Error>` instead, which knows nothing of clap:
+//! return the `clap::Error` to `Exit` directly, or convert it with
+//! `.map_err(IntoAnyError::into_any_error)?`.
+
+use std::any::Any;
+
+use clap::error::{ContextKind, ErrorKind};
+
+use crate::{AnyError, IntoAnyError, category::Category, mapping::EXIT_CODE_EXTENSION};
+
+impl IntoAnyError for clap::Error {
+    fn into_any_error(self) -> AnyError {
+        let kind = self.kind();
+        let display = matches!(
+            kind,
+            ErrorKind::DisplayHelp
+                | ErrorKind::DisplayVersion
+                | ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand
+        );
+        let argument = self
+            .get(ContextKind::InvalidArg)
+            .or_else(|| self.get(ContextKind::InvalidSubcommand))
+            .map(ToString::to_string);
+        let value = self.get(ContextKind::InvalidValue).map(ToString::to_string);
+        let exit_code = self.exit_code();
+
+        let mut error = AnyError::from(self)
+            .with_extension("kind", format!("{kind:?}"))
+            .with_extension("display", display)
+            .with_extension(EXIT_CODE_EXTENSION, exit_code);
+        if !display {
+            error = error.with_category(Category::Validation);
+        }
+        if let Some(argument) = argument {
+            error = error.with_extension("argument", argument);
+        }
+        if let Some(value) = value {
+            error = error.with_extension("value", value);
+        }
+        error
+    }
+}
+
+/// Converts `error` with [`IntoAnyError`] if it is a `clap::Error`, for conversions generic
+/// over the error type.
+pub(crate) fn downcast<E: 'static>(error: E) -> Result<AnyError, E> {
+    let mut error = Some(error);
+    match (&mut error as &mut dyn Any).downcast_mut::<Option<clap::Error>>() {
+        Some(clap) => Ok(clap.take().expect("taken once").into_any_error()),
+        None => Err(error.expect("not taken")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::{Arg, Command};
+
+    use super::*;
+
+    fn command() -> Command {
+        Command::new("app").version("1.0.0").arg(
+            Arg::new("port")
+                .long("port")
+                .value_parser(clap::value_parser!(u16)),
+        )
+    }
+
+    #[test]
+    fn test_usage_error() {
+        let error = command()
+            .try_get_matches_from(["app", "--port", "http"])
+            .unwrap_err()
+            .into_any_error();
+
+        assert_eq!(error.exit_code(), 2);
+        assert_eq!(error.category(), Some(Category::Validation));
+        assert_eq!(
+            error.extension("kind"),
+            Some(&serde_json::json!("ValueValidation"))
+        );
+        assert_eq!(
+            error.extension("argument"),
+            Some(&serde_json::json!("--port <port>"))
+        );
+        assert_eq!(error.extension("value"), Some(&serde_json::json!("http")));
+        assert_eq!(error.extension("display"), Some(&serde_json::json!(false)));
+    }
+
+    #[test]
+    fn test_version_display() {
+        let error = command()
+            .try_get_matches_from(["app", "--version"])
+            .unwrap_err()
+            .into_any_error();
+
+        assert_eq!(error.exit_code(), 0);
+        assert_eq!(error.category(), None);
+        assert_eq!(error.extension("display"), Some(&serde_json::json!(true)));
+    }
+}
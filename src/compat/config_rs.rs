@@ -0,0 +1,101 @@
+//! [`IntoAnyError`] for `config::ConfigError` (enabled by the `config-rs` feature), recording
+//! the offending `keyPath`, the `file` it came from, and for type mismatches the `expected`
+//! type, as extensions.
+
+use config_rs::ConfigError;
+
+use crate::{AnyError, IntoAnyError};
+
+#[derive(Default)]
+struct Location {
+    key_path: Option<String>,
+    file: Option<String>,
+    expected: Option<&'static str>,
+}
+
+fn locate(error: &ConfigError) -> Location {
+    match error {
+        ConfigError::NotFound(key) => Location {
+            key_path: Some(key.clone()),
+            ..Default::default()
+        },
+        ConfigError::FileParse { uri, .. } => Location {
+            file: uri.clone(),
+            ..Default::default()
+        },
+        ConfigError::Type {
+            origin,
+            expected,
+            key,
+            ..
+        } => Location {
+            key_path: key.clone(),
+            file: origin.clone(),
+            expected: Some(*expected),
+        },
+        ConfigError::At { error, origin, key } => {
+            let inner = locate(error);
+            Location {
+                key_path: key.clone().or(inner.key_path),
+                file: origin.clone().or(inner.file),
+                expected: inner.expected,
+            }
+        }
+        _ => Location::default(),
+    }
+}
+
+impl IntoAnyError for ConfigError {
+    fn into_any_error(self) -> AnyError {
+        let location = locate(&self);
+        let mut error = AnyError::from(self);
+        if let Some(key_path) = location.key_path {
+            error = error.with_extension("keyPath", key_path);
+        }
+        if let Some(file) = location.file {
+            error = error.with_extension("file", file);
+        }
+        if let Some(expected) = location.expected {
+            error = error.with_extension("expected", expected);
+        }
+        error
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use config_rs::Config;
+
+    use super::*;
+
+    #[test]
+    fn test_type_error_key_path() {
+        let config = Config::builder()
+            .set_override("server.port", "not a port")
+            .unwrap()
+            .build()
+            .unwrap();
+        let error = config
+            .get::<u16>("server.port")
+            .unwrap_err()
+            .into_any_error();
+
+        assert_eq!(
+            error.extension("keyPath"),
+            Some(&serde_json::json!("server.port"))
+        );
+        assert!(error.extension("expected").is_some());
+    }
+
+    #[test]
+    fn test_not_found_key_path() {
+        let config = Config::builder().build().unwrap();
+        let error = config.get::<u16>("missing").unwrap_err().into_any_error();
+
+        assert_eq!(
+            error.extension("keyPath"),
+            Some(&serde_json::json!("missing"))
+        );
+        assert_eq!(error.extension("file"), None);
+    }
+}
@@ -0,0 +1,67 @@
+//! Shared shape of the database driver adapters. Every adapter records the same extensions
+//! so DB errors can be filtered uniformly regardless of driver:
+//!
+//! - `sqlState`: the five-character SQLSTATE, when the server reported one.
+//! - `serverCode`: the driver- or server-specific error code (MySQL error number, Redis error
+//!   prefix).
+//! - `level`: whether the failure was at the `connection` or `statement` level.
+
+use serde::{Deserialize, Serialize};
+
+use crate::AnyError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FailureLevel {
+    /// Connecting, authenticating, or the connection dropping mid-flight.
+    Connection,
+    /// The server rejected or failed a single statement or command.
+    Statement,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct DbErrorInfo {
+    pub sql_state: Option<String>,
+    pub server_code: Option<String>,
+    pub level: Option<FailureLevel>,
+}
+
+impl DbErrorInfo {
+    pub fn apply(self, mut error: AnyError) -> AnyError {
+        if let Some(sql_state) = self.sql_state {
+            error = error.with_extension("sqlState", sql_state);
+        }
+        if let Some(server_code) = self.server_code {
+            error = error.with_extension("serverCode", server_code);
+        }
+        if let Some(level) = self.level {
+            error = error.with_extension("level", level);
+        }
+        error
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply() {
+        let error = DbErrorInfo {
+            sql_state: Some("23505".to_string()),
+            server_code: None,
+            level: Some(FailureLevel::Statement),
+        }
+        .apply(AnyError::msg("duplicate key"));
+
+        assert_eq!(
+            error.extension("sqlState"),
+            Some(&serde_json::json!("23505"))
+        );
+        assert_eq!(error.extension("serverCode"), None);
+        assert_eq!(
+            error.extension("level"),
+            Some(&serde_json::json!("statement"))
+        );
+    }
+}
@@ -0,0 +1,40 @@
+//! `defmt::Format` for `AnyError`, rendering the chain as `{}` does so firmware can log it
+//! over RTT without going through `core::fmt`.
+
+use crate::AnyError;
+
+impl defmt::Format for AnyError {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        enum Part<'a> {
+            Error(&'a AnyError),
+            Text(&'static str),
+        }
+
+        // The same explicit stack as `Display`, so deep chains don't overflow the small
+        // stacks of firmware.
+        let mut stack = vec![Part::Error(self)];
+        while let Some(part) = stack.pop() {
+            let error = match part {
+                Part::Error(error) => error,
+                Part::Text(text) => {
+                    defmt::write!(f, "{=str}", text);
+                    continue;
+                }
+            };
+            defmt::write!(f, "{=str}: {=str}", error.r#type, error.context.message);
+
+            let causes = error.causes().collect::<Vec<_>>();
+            if causes.is_empty() {
+                continue;
+            }
+            stack.push(Part::Text(")"));
+            for (index, cause) in causes.into_iter().enumerate().rev() {
+                stack.push(Part::Error(cause));
+                if index > 0 {
+                    stack.push(Part::Text("; "));
+                }
+            }
+            stack.push(Part::Text("("));
+        }
+    }
+}
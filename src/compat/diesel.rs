@@ -0,0 +1,191 @@
+//! [`IntoAnyError`] for `diesel::result::Error`. Records the extension `kind`
+//! (`notFound`, `uniqueViolation`, `serializationFailure`, `closedConnection`, ...) and, for
+//! database errors, the `constraint`, `table` and `column` the database reported. Sets the
+//! [`Category`] and retryability: serialization failures and lost connections are retryable,
+//! constraint violations are not.
+
+use diesel::result::{DatabaseErrorKind, Error};
+
+use crate::{AnyError, IntoAnyError, category::Category};
+
+struct Classification {
+    kind: &'static str,
+    category: Option<Category>,
+    retryable: Option<bool>,
+}
+
+impl Classification {
+    fn new(kind: &'static str, category: Category, retryable: bool) -> Self {
+        Self {
+            kind,
+            category: Some(category),
+            retryable: Some(retryable),
+        }
+    }
+}
+
+fn classify(error: &Error) -> Classification {
+    match error {
+        Error::NotFound => Classification::new("notFound", Category::NotFound, false),
+        Error::DatabaseError(kind, _) => match kind {
+            DatabaseErrorKind::UniqueViolation => {
+                Classification::new("uniqueViolation", Category::Conflict, false)
+            }
+            DatabaseErrorKind::ForeignKeyViolation => {
+                Classification::new("foreignKeyViolation", Category::Conflict, false)
+            }
+            DatabaseErrorKind::RestrictViolation => {
+                Classification::new("restrictViolation", Category::Conflict, false)
+            }
+            DatabaseErrorKind::ExclusionViolation => {
+                Classification::new("exclusionViolation", Category::Conflict, false)
+            }
+            DatabaseErrorKind::NotNullViolation => {
+                Classification::new("notNullViolation", Category::Validation, false)
+            }
+            DatabaseErrorKind::CheckViolation => {
+                Classification::new("checkViolation", Category::Validation, false)
+            }
+            DatabaseErrorKind::SerializationFailure => {
+                Classification::new("serializationFailure", Category::Conflict, true)
+            }
+            DatabaseErrorKind::ReadOnlyTransaction => {
+                Classification::new("readOnlyTransaction", Category::Internal, false)
+            }
+            DatabaseErrorKind::UnableToSendCommand => {
+                Classification::new("unableToSendCommand", Category::Unavailable, true)
+            }
+            DatabaseErrorKind::ClosedConnection => {
+                Classification::new("closedConnection", Category::Unavailable, true)
+            }
+            _ => Classification {
+                kind: "database",
+                category: None,
+                retryable: None,
+            },
+        },
+        _ => Classification {
+            kind: "other",
+            category: None,
+            retryable: None,
+        },
+    }
+}
+
+impl IntoAnyError for Error {
+    fn into_any_error(self) -> AnyError {
+        let classification = classify(&self);
+        let (constraint, table, column) = match &self {
+            Error::DatabaseError(_, info) => (
+                info.constraint_name().map(str::to_string),
+                info.table_name().map(str::to_string),
+                info.column_name().map(str::to_string),
+            ),
+            _ => (None, None, None),
+        };
+
+        let mut error = AnyError::from(self).with_extension("kind", classification.kind);
+        if let Some(category) = classification.category {
+            error = error.with_category(category);
+        }
+        if let Some(retryable) = classification.retryable {
+            error = error.with_retryable(retryable);
+        }
+        if let Some(constraint) = constraint {
+            error = error.with_extension("constraint", constraint);
+        }
+        if let Some(table) = table {
+            error = error.with_extension("table", table);
+        }
+        if let Some(column) = column {
+            error = error.with_extension("column", column);
+        }
+        error
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use diesel::result::DatabaseErrorInformation;
+
+    use super::*;
+
+    struct Info {
+        constraint: Option<&'static str>,
+    }
+
+    impl DatabaseErrorInformation for Info {
+        fn message(&self) -> &str {
+            "duplicate key value violates unique constraint"
+        }
+
+        fn details(&self) -> Option<&str> {
+            None
+        }
+
+        fn hint(&self) -> Option<&str> {
+            None
+        }
+
+        fn table_name(&self) -> Option<&str> {
+            Some("users")
+        }
+
+        fn column_name(&self) -> Option<&str> {
+            None
+        }
+
+        fn constraint_name(&self) -> Option<&str> {
+            self.constraint
+        }
+
+        fn statement_position(&self) -> Option<i32> {
+            None
+        }
+    }
+
+    fn database_error(kind: DatabaseErrorKind, constraint: Option<&'static str>) -> Error {
+        Error::DatabaseError(kind, Box::new(Info { constraint }))
+    }
+
+    #[test]
+    fn test_unique_violation() {
+        let error = database_error(DatabaseErrorKind::UniqueViolation, Some("users_email_key"))
+            .into_any_error();
+
+        assert_eq!(error.category(), Some(Category::Conflict));
+        assert_eq!(error.retryable(), Some(false));
+        assert_eq!(
+            error.extension("kind"),
+            Some(&serde_json::json!("uniqueViolation"))
+        );
+        assert_eq!(
+            error.extension("constraint"),
+            Some(&serde_json::json!("users_email_key"))
+        );
+        assert_eq!(error.extension("table"), Some(&serde_json::json!("users")));
+    }
+
+    #[test]
+    fn test_retryable_failures() {
+        let serialization =
+            database_error(DatabaseErrorKind::SerializationFailure, None).into_any_error();
+        let closed = database_error(DatabaseErrorKind::ClosedConnection, None).into_any_error();
+
+        assert_eq!(serialization.retryable(), Some(true));
+        assert_eq!(closed.retryable(), Some(true));
+        assert_eq!(closed.category(), Some(Category::Unavailable));
+        assert_eq!(closed.extension("constraint"), None);
+    }
+
+    #[test]
+    fn test_not_found() {
+        let error = Error::NotFound.into_any_error();
+
+        assert_eq!(error.category(), Some(Category::NotFound));
+        assert_eq!(
+            error.extension("kind"),
+            Some(&serde_json::json!("notFound"))
+        );
+    }
+}
@@ -0,0 +1,84 @@
+//! [`IntoAnyError`] for `figment::Error`, recording the offending `keyPath`, the `profile`
+//! being extracted, the `provider` name and, for file-backed providers, the `file`, as
+//! extensions. When figment reports several errors, only the first is described and the
+//! total is recorded as `errorCount`.
+
+use figment::Error;
+
+use crate::{AnyError, IntoAnyError};
+
+impl IntoAnyError for Error {
+    fn into_any_error(self) -> AnyError {
+        let count = self.count();
+        let key_path = (!self.path.is_empty()).then(|| self.path.join("."));
+        let profile = self.profile.as_ref().map(|profile| profile.to_string());
+        let provider = self
+            .metadata
+            .as_ref()
+            .map(|metadata| metadata.name.to_string());
+        let file = self
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.source.as_ref())
+            .and_then(|source| source.file_path())
+            .map(|path| path.display().to_string());
+
+        let mut error = AnyError::from(self);
+        if let Some(key_path) = key_path {
+            error = error.with_extension("keyPath", key_path);
+        }
+        if let Some(profile) = profile {
+            error = error.with_extension("profile", profile);
+        }
+        if let Some(provider) = provider {
+            error = error.with_extension("provider", provider);
+        }
+        if let Some(file) = file {
+            error = error.with_extension("file", file);
+        }
+        if count > 1 {
+            error = error.with_extension("errorCount", count);
+        }
+        error
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use figment::{Figment, providers::Serialized};
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Server {
+        port: u16,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct AppConfig {
+        server: Server,
+    }
+
+    #[test]
+    fn test_invalid_type() {
+        let error = Figment::new()
+            .merge(Serialized::default("server.port", "not a port"))
+            .extract::<AppConfig>()
+            .unwrap_err()
+            .into_any_error();
+
+        assert_eq!(
+            error.extension("keyPath"),
+            Some(&serde_json::json!("server.port"))
+        );
+        assert_eq!(
+            error.extension("profile"),
+            Some(&serde_json::json!("default"))
+        );
+        assert!(error.extension("provider").is_some());
+        assert_eq!(error.extension("file"), None);
+    }
+}
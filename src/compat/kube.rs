@@ -0,0 +1,117 @@
+//! [`IntoAnyError`] for `kube::Error`. API errors record the extensions `status` (the HTTP
+//! code), `reason` (`NotFound`, `Conflict`, ...) and, when the API server reported it,
+//! `resource` with the involved object's `group`, `kind` and `name`.
+//!
+//! Common reasons also set a [`Category`], so reconcile loops can tell a missing object from
+//! a conflicting write.
+
+use kube_client::{Error, core::Status};
+use serde::Serialize;
+
+use crate::{AnyError, IntoAnyError, category::Category};
+
+#[derive(Serialize)]
+struct Resource {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    group: String,
+    kind: String,
+    name: String,
+}
+
+fn category(status: &Status) -> Option<Category> {
+    Some(match status.reason.as_str() {
+        "NotFound" | "Gone" => Category::NotFound,
+        "AlreadyExists" | "Conflict" => Category::Conflict,
+        "Invalid" | "BadRequest" => Category::Validation,
+        "Unauthorized" => Category::Unauthorized,
+        "Forbidden" => Category::Forbidden,
+        "TooManyRequests" => Category::RateLimited,
+        "Timeout" | "ServerTimeout" => Category::Timeout,
+        "ServiceUnavailable" => Category::Unavailable,
+        "InternalError" => Category::Internal,
+        _ => return None,
+    })
+}
+
+impl IntoAnyError for Error {
+    fn into_any_error(self) -> AnyError {
+        let Error::Api(status) = &self else {
+            return AnyError::from(self);
+        };
+
+        let code = status.code;
+        let reason = status.reason.clone();
+        let category = category(status);
+        let resource = status
+            .details
+            .as_ref()
+            .filter(|details| !details.kind.is_empty())
+            .map(|details| Resource {
+                group: details.group.clone(),
+                kind: details.kind.clone(),
+                name: details.name.clone(),
+            });
+
+        let mut error = AnyError::from(self)
+            .with_extension("status", code)
+            .with_extension("reason", reason);
+        if let Some(resource) = resource {
+            error = error.with_extension("resource", resource);
+        }
+        if let Some(category) = category {
+            error = error.with_category(category);
+        }
+        error
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kube_client::core::response::StatusDetails;
+
+    use super::*;
+
+    fn details(group: &str, kind: &str, name: &str) -> StatusDetails {
+        StatusDetails {
+            name: name.to_string(),
+            group: group.to_string(),
+            kind: kind.to_string(),
+            uid: String::new(),
+            causes: Vec::new(),
+            retry_after_seconds: 0,
+        }
+    }
+
+    #[test]
+    fn test_api_error() {
+        let status = Status::failure("deployments.apps \"web\" not found", "NotFound")
+            .with_code(404)
+            .with_details(details("apps", "deployments", "web"));
+        let error = Error::Api(status.boxed()).into_any_error();
+
+        assert_eq!(error.category(), Some(Category::NotFound));
+        assert_eq!(error.extension("status"), Some(&serde_json::json!(404)));
+        assert_eq!(
+            error.extension("reason"),
+            Some(&serde_json::json!("NotFound"))
+        );
+        assert_eq!(
+            error.extension("resource"),
+            Some(&serde_json::json!({ "group": "apps", "kind": "deployments", "name": "web" }))
+        );
+    }
+
+    #[test]
+    fn test_core_group_omitted() {
+        let status = Status::failure("pods \"api\" already exists", "AlreadyExists")
+            .with_code(409)
+            .with_details(details("", "pods", "api"));
+        let error = Error::Api(status.boxed()).into_any_error();
+
+        assert_eq!(error.category(), Some(Category::Conflict));
+        assert_eq!(
+            error.extension("resource"),
+            Some(&serde_json::json!({ "kind": "pods", "name": "api" }))
+        );
+    }
+}
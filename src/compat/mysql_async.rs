@@ -0,0 +1,151 @@
+//! [`IntoAnyError`] for `mysql_async::Error`, see [`db`](super::db) for the recorded
+//! extensions. I/O errors, protocol and handshake failures and the server errors for a lost
+//! or refused connection are connection level. Other server errors and the driver's
+//! parameter, conversion and transaction errors are statement level. URL errors are
+//! configuration problems and get no level.
+
+use mysql_async::{DriverError, Error};
+
+use crate::{
+    AnyError, IntoAnyError,
+    compat::db::{DbErrorInfo, FailureLevel},
+};
+
+/// Server error numbers for a connection that was refused, dropped or shut down.
+const CONNECTION_SERVER_CODES: [u16; 6] = [
+    1040, // ER_CON_COUNT_ERROR: too many connections
+    1043, // ER_HANDSHAKE_ERROR
+    1045, // ER_ACCESS_DENIED_ERROR
+    1053, // ER_SERVER_SHUTDOWN
+    2006, // CR_SERVER_GONE_ERROR
+    2013, // CR_SERVER_LOST
+];
+
+fn driver_level(error: &DriverError) -> FailureLevel {
+    match error {
+        DriverError::FromValue { .. }
+        | DriverError::FromRow { .. }
+        | DriverError::MissingNamedParam { .. }
+        | DriverError::MixedParams
+        | DriverError::NamedParamsForPositionalQuery
+        | DriverError::NestedTransaction
+        | DriverError::ReadOnlyTransNotSupported
+        | DriverError::StmtParamsMismatch { .. }
+        | DriverError::StmtParamsNumberExceedsLimit { .. }
+        | DriverError::PacketTooLarge
+        | DriverError::LocalInfile(_) => FailureLevel::Statement,
+        DriverError::CantParseServerVersion { .. }
+        | DriverError::ConnectionClosed
+        | DriverError::PacketOutOfOrder
+        | DriverError::PoolDisconnected
+        | DriverError::UnexpectedPacket { .. }
+        | DriverError::UnknownAuthPlugin { .. }
+        | DriverError::BadCompressedPacketHeader
+        | DriverError::NamedPipesDisabled
+        | DriverError::MysqlOldPasswordDisabled
+        | DriverError::NoKeyFound
+        | DriverError::NoClientSslFlagFromServer
+        | DriverError::CleartextPluginDisabled => FailureLevel::Connection,
+    }
+}
+
+impl IntoAnyError for Error {
+    fn into_any_error(self) -> AnyError {
+        let info = match &self {
+            Error::Server(server) => DbErrorInfo {
+                sql_state: Some(server.state.clone()),
+                server_code: Some(server.code.to_string()),
+                level: Some(if CONNECTION_SERVER_CODES.contains(&server.code) {
+                    FailureLevel::Connection
+                } else {
+                    FailureLevel::Statement
+                }),
+            },
+            Error::Driver(driver) => DbErrorInfo {
+                level: Some(driver_level(driver)),
+                ..Default::default()
+            },
+            Error::Io(_) => DbErrorInfo {
+                level: Some(FailureLevel::Connection),
+                ..Default::default()
+            },
+            Error::Url(_) | Error::Other(_) => DbErrorInfo::default(),
+        };
+
+        info.apply(AnyError::from(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mysql_async::{ServerError, UrlError};
+
+    use super::*;
+
+    fn level(error: Error) -> Option<serde_json::Value> {
+        error.into_any_error().extension("level").cloned()
+    }
+
+    #[test]
+    fn test_server_error() {
+        let error = Error::Server(ServerError {
+            code: 1062,
+            message: "Duplicate entry 'a' for key 'name'".to_string(),
+            state: "23000".to_string(),
+        })
+        .into_any_error();
+
+        assert_eq!(
+            error.extension("sqlState"),
+            Some(&serde_json::json!("23000"))
+        );
+        assert_eq!(
+            error.extension("serverCode"),
+            Some(&serde_json::json!("1062"))
+        );
+        assert_eq!(
+            error.extension("level"),
+            Some(&serde_json::json!("statement"))
+        );
+    }
+
+    #[test]
+    fn test_server_connection_error() {
+        let error = Error::Server(ServerError {
+            code: 2013,
+            message: "Lost connection to MySQL server during query".to_string(),
+            state: "HY000".to_string(),
+        });
+
+        assert_eq!(level(error), Some(serde_json::json!("connection")));
+    }
+
+    #[test]
+    fn test_io_error() {
+        let io = std::io::Error::from(std::io::ErrorKind::ConnectionReset);
+        let error = Error::Io(io.into()).into_any_error();
+
+        assert_eq!(error.extension("sqlState"), None);
+        assert_eq!(
+            error.extension("level"),
+            Some(&serde_json::json!("connection"))
+        );
+    }
+
+    #[test]
+    fn test_driver_errors() {
+        let mismatch = Error::Driver(DriverError::StmtParamsMismatch {
+            required: 2,
+            supplied: 1,
+        });
+        assert_eq!(level(mismatch), Some(serde_json::json!("statement")));
+
+        let closed = Error::Driver(DriverError::ConnectionClosed);
+        assert_eq!(level(closed), Some(serde_json::json!("connection")));
+    }
+
+    #[test]
+    fn test_url_error() {
+        assert_eq!(level(Error::Url(UrlError::Invalid)), None);
+    }
+}
@@ -0,0 +1,58 @@
+//! [`IntoAnyError`] for `redis::RedisError`, see [`db`](super::db) for the recorded
+//! extensions. Redis has no SQLSTATE; its error prefix (`WRONGTYPE`, `NOSCRIPT`) is recorded
+//! as the server code. I/O failures and refused or dropped connections are connection level,
+//! everything else is a failed command.
+
+use redis::RedisError;
+
+use crate::{
+    AnyError, IntoAnyError,
+    compat::db::{DbErrorInfo, FailureLevel},
+};
+
+impl IntoAnyError for RedisError {
+    fn into_any_error(self) -> AnyError {
+        let connection =
+            self.is_io_error() || self.is_connection_dropped() || self.is_connection_refusal();
+        let info = DbErrorInfo {
+            sql_state: None,
+            server_code: self.code().map(str::to_string),
+            level: Some(if connection {
+                FailureLevel::Connection
+            } else {
+                FailureLevel::Statement
+            }),
+        };
+
+        info.apply(AnyError::from(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use redis::ErrorKind;
+
+    use super::*;
+
+    #[test]
+    fn test_io_error() {
+        let error = RedisError::from((ErrorKind::Io, "connection reset")).into_any_error();
+
+        assert_eq!(
+            error.extension("level"),
+            Some(&serde_json::json!("connection"))
+        );
+    }
+
+    #[test]
+    fn test_command_error() {
+        let error =
+            RedisError::from((ErrorKind::UnexpectedReturnType, "not a string")).into_any_error();
+
+        assert_eq!(
+            error.extension("level"),
+            Some(&serde_json::json!("statement"))
+        );
+        assert_eq!(error.extension("serverCode"), None);
+    }
+}
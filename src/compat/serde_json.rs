@@ -0,0 +1,80 @@
+//! `IntoAnyError` for `serde_json::Error`, recording where and how parsing failed. Use
+//! [`with_source`] when the input is at hand to also attach a [`Snippet`] of it.
+
+use serde_json::error::Category as JsonCategory;
+
+use crate::{AnyError, IntoAnyError, category::Category, snippet::Snippet};
+
+pub const LINE_EXTENSION: &str = "line";
+pub const COLUMN_EXTENSION: &str = "column";
+/// One of `io`, `syntax`, `data` or `eof`.
+pub const KIND_EXTENSION: &str = "kind";
+
+impl IntoAnyError for serde_json::Error {
+    fn into_any_error(self) -> AnyError {
+        let (kind, category) = match self.classify() {
+            JsonCategory::Io => ("io", None),
+            JsonCategory::Syntax => ("syntax", Some(Category::Validation)),
+            JsonCategory::Data => ("data", Some(Category::Validation)),
+            JsonCategory::Eof => ("eof", Some(Category::Validation)),
+        };
+        let (line, column) = (self.line(), self.column());
+
+        let mut error = AnyError::from(self).with_extension(KIND_EXTENSION, kind);
+        if let Some(category) = category {
+            error = error.with_category(category);
+        }
+        if line > 0 {
+            error = error
+                .with_extension(LINE_EXTENSION, line)
+                .with_extension(COLUMN_EXTENSION, column);
+        }
+        error
+    }
+}
+
+/// Converts a parse error of `source`, attaching the snippet it points at.
+pub fn with_source(error: serde_json::Error, source_name: &str, source: &str) -> AnyError {
+    let (line, column) = (error.line(), error.column());
+    let error = error.into_any_error();
+    if line == 0 {
+        return error;
+    }
+    error.with_snippet(Snippet::at_line_column(source_name, source, line, column))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_syntax_error() {
+        let source = "{\n  \"port\": 80,\n  \"host\" \"localhost\"\n}";
+        let error = serde_json::from_str::<serde_json::Value>(source).unwrap_err();
+        let error = with_source(error, "config.json", source);
+
+        assert_eq!(
+            error.extension(KIND_EXTENSION),
+            Some(&serde_json::json!("syntax"))
+        );
+        assert_eq!(error.extension(LINE_EXTENSION), Some(&serde_json::json!(3)));
+        assert_eq!(error.category(), Some(Category::Validation));
+
+        let snippet = error.snippet().unwrap();
+        assert_eq!(snippet.line, 3);
+        assert_eq!(snippet.text, "  \"host\" \"localhost\"");
+        assert_eq!(snippet.column(), 10);
+    }
+
+    #[test]
+    fn test_data_error() {
+        let error = serde_json::from_str::<u8>("300")
+            .unwrap_err()
+            .into_any_error();
+
+        assert_eq!(
+            error.extension(KIND_EXTENSION),
+            Some(&serde_json::json!("data"))
+        );
+    }
+}
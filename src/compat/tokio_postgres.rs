@@ -0,0 +1,31 @@
+//! [`IntoAnyError`] for `tokio_postgres::Error`, see [`db`](super::db) for the recorded
+//! extensions. Errors reported by the server are statement level; a closed connection or an
+//! I/O failure is connection level.
+
+use std::io;
+
+use crate::{
+    AnyError, IntoAnyError,
+    compat::db::{DbErrorInfo, FailureLevel},
+};
+
+impl IntoAnyError for tokio_postgres::Error {
+    fn into_any_error(self) -> AnyError {
+        let level = if self.as_db_error().is_some() {
+            Some(FailureLevel::Statement)
+        } else if self.is_closed()
+            || std::error::Error::source(&self).is_some_and(|source| source.is::<io::Error>())
+        {
+            Some(FailureLevel::Connection)
+        } else {
+            None
+        };
+        let info = DbErrorInfo {
+            sql_state: self.code().map(|code| code.code().to_string()),
+            server_code: None,
+            level,
+        };
+
+        info.apply(AnyError::from(self))
+    }
+}
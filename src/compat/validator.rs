@@ -0,0 +1,101 @@
+//! [`IntoAnyError`] for `validator::ValidationErrors`. The resulting [`AnyError`] is
+//! categorized [`Category::Validation`] and carries a `fields` extension mapping each field
+//! path (`address.city`, `items[1].name`) to its errors' codes, messages and params.
+
+use std::collections::BTreeMap;
+
+use validator::{ValidationError, ValidationErrors, ValidationErrorsKind};
+
+use crate::{AnyError, IntoAnyError, category::Category};
+
+/// Flattens nested struct and list errors into a map keyed by field path.
+pub fn field_map(errors: &ValidationErrors) -> BTreeMap<String, Vec<ValidationError>> {
+    let mut fields = BTreeMap::new();
+    collect(errors, None, &mut fields);
+    fields
+}
+
+fn collect(
+    errors: &ValidationErrors,
+    prefix: Option<&str>,
+    fields: &mut BTreeMap<String, Vec<ValidationError>>,
+) {
+    for (field, kind) in errors.errors() {
+        let path = match prefix {
+            Some(prefix) => format!("{prefix}.{field}"),
+            None => field.to_string(),
+        };
+        match kind {
+            ValidationErrorsKind::Field(errors) => {
+                fields
+                    .entry(path)
+                    .or_default()
+                    .extend(errors.iter().cloned());
+            }
+            ValidationErrorsKind::Struct(errors) => collect(errors, Some(&path), fields),
+            ValidationErrorsKind::List(items) => {
+                for (index, errors) in items {
+                    collect(errors, Some(&format!("{path}[{index}]")), fields);
+                }
+            }
+        }
+    }
+}
+
+impl IntoAnyError for ValidationErrors {
+    fn into_any_error(self) -> AnyError {
+        let fields = field_map(&self);
+        AnyError::from(self)
+            .with_category(Category::Validation)
+            .with_extension("fields", fields)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_errors() -> ValidationErrors {
+        let mut address = ValidationErrors::new();
+        address.add("city", ValidationError::new("required"));
+
+        let mut item = ValidationErrors::new();
+        item.add(
+            "name",
+            ValidationError::new("length").with_message("too long".into()),
+        );
+
+        let mut errors = ValidationErrors::new();
+        errors.add("email", ValidationError::new("email"));
+        errors.errors_mut().insert(
+            "address".into(),
+            ValidationErrorsKind::Struct(Box::new(address)),
+        );
+        errors.errors_mut().insert(
+            "items".into(),
+            ValidationErrorsKind::List(BTreeMap::from([(1, Box::new(item))])),
+        );
+        errors
+    }
+
+    #[test]
+    fn test_field_map_paths() {
+        let fields = field_map(&sample_errors());
+
+        assert_eq!(
+            fields.keys().collect::<Vec<_>>(),
+            vec!["address.city", "email", "items[1].name"]
+        );
+        assert_eq!(fields["items[1].name"][0].code, "length");
+    }
+
+    #[test]
+    fn test_into_any_error() {
+        let error = sample_errors().into_any_error();
+
+        assert_eq!(error.category(), Some(Category::Validation));
+        let fields = error.extension("fields").unwrap();
+        assert_eq!(fields["email"][0]["code"], "email");
+        assert_eq!(fields["items[1].name"][0]["message"], "too long");
+    }
+}
@@ -0,0 +1,98 @@
+use std::sync::{Arc, LazyLock, RwLock};
+
+use crate::{
+    backtrace::{BacktracePolicy, FrameFilter},
+    fingerprint::FingerprintStrategy,
+    mask::MaskPolicy,
+    tag::TagCase,
+    type_name::TypeAliases,
+};
+
+/// Process-wide settings consulted when errors are captured.
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    pub frame_filter: FrameFilter,
+    /// Overrides the policy read from `RUST_LIB_BACKTRACE`/`RUST_BACKTRACE`.
+    pub backtrace: Option<BacktracePolicy>,
+    /// Prepended to every `$type` derived from a Rust type or a `TypeTag`, e.g. `acme.billing.`
+    pub namespace: Option<String>,
+    /// Shortening rules for third-party type paths, see [`TypeAliases::popular`].
+    pub type_aliases: TypeAliases,
+    /// Casing of the identifiers in `$type`s derived from Rust types, kept as declared if
+    /// `None`.
+    pub type_name_case: Option<TagCase>,
+    /// How many `source()`s are followed when converting an error, [`DEFAULT_MAX_SOURCE_DEPTH`]
+    /// if `None`. Deeper chains are cut and marked with
+    /// [`TRUNCATED_EXTENSION`](crate::TRUNCATED_EXTENSION).
+    pub max_source_depth: Option<usize>,
+    /// Messages of converted errors longer than this many characters are cut, see
+    /// [`AnyError::truncate_messages`](crate::AnyError::truncate_messages).
+    pub max_message_chars: Option<usize>,
+    /// What [`AnyError::fingerprint`](crate::AnyError::fingerprint) groups by.
+    pub fingerprint_strategy: FingerprintStrategy,
+    /// Which `$type`s [`mask::serialize`](crate::mask::serialize) exposes, all of them if
+    /// `None`.
+    pub mask_policy: Option<MaskPolicy>,
+    /// Which `$type`s [`AnyError::to_public`](crate::AnyError::to_public) exposes, the ones
+    /// registered in the [code mapping](crate::mapping) if `None`.
+    pub public_mask_policy: Option<MaskPolicy>,
+    /// Scrubs every error as it is converted.
+    #[cfg(feature = "redact")]
+    pub redactor: Option<Arc<crate::redact::Redactor>>,
+}
+
+/// Deep enough for real chains, shallow enough that formatting and serializing a converted
+/// error stays well within a 2 MB thread stack.
+pub const DEFAULT_MAX_SOURCE_DEPTH: usize = 128;
+
+impl Config {
+    pub fn max_source_depth(&self) -> usize {
+        self.max_source_depth.unwrap_or(DEFAULT_MAX_SOURCE_DEPTH)
+    }
+
+    pub fn namespaced(&self, r#type: String) -> String {
+        match self.namespace.as_deref() {
+            None | Some("") => r#type,
+            Some(namespace) if namespace.ends_with('.') => format!("{namespace}{type}"),
+            Some(namespace) => format!("{namespace}.{type}"),
+        }
+    }
+}
+
+static CONFIG: LazyLock<RwLock<Arc<Config>>> = LazyLock::new(Default::default);
+
+pub fn current() -> Arc<Config> {
+    CONFIG.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+pub fn set(config: Config) {
+    *CONFIG.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(config);
+}
+
+pub fn update(f: impl FnOnce(&mut Config)) {
+    let mut config = CONFIG.write().unwrap_or_else(|e| e.into_inner());
+    f(Arc::make_mut(&mut config));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_namespaced() {
+        let mut config = Config::default();
+        assert_eq!(config.namespaced("io.Error".to_string()), "io.Error");
+
+        config.namespace = Some("acme.billing.".to_string());
+        assert_eq!(
+            config.namespaced("io.Error".to_string()),
+            "acme.billing.io.Error"
+        );
+
+        config.namespace = Some("acme.billing".to_string());
+        assert_eq!(
+            config.namespaced("io.Error".to_string()),
+            "acme.billing.io.Error"
+        );
+    }
+}
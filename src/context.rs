@@ -0,0 +1,135 @@
+use std::{convert::Infallible, error::Error, fmt::Display};
+
+use crate::{AnyError, AnyResult};
+
+/// Adds a [`CONTEXT_TYPE`](crate::CONTEXT_TYPE) layer on failure, e.g.
+/// `fs::read(path).context("reading config")?`. The original error becomes its inner error.
+/// Also available as `compat::anyhow::Context`.
+pub trait ContextExt<T, E> {
+    #[track_caller]
+    fn context<C>(self, context: C) -> AnyResult<T>
+    where
+        C: Display + Send + Sync + 'static;
+
+    #[track_caller]
+    fn with_context<C, F>(self, f: F) -> AnyResult<T>
+    where
+        C: Display + Send + Sync + 'static,
+        F: FnOnce() -> C;
+}
+
+impl<T, E: Error + Send + Sync + 'static> ContextExt<T, E> for Result<T, E> {
+    fn context<C>(self, context: C) -> AnyResult<T>
+    where
+        C: Display + Send + Sync + 'static,
+    {
+        match self {
+            Ok(value) => Ok(value),
+            Err(error) => Err(AnyError::from(error)).context(context),
+        }
+    }
+
+    fn with_context<C, F>(self, f: F) -> AnyResult<T>
+    where
+        C: Display + Send + Sync + 'static,
+        F: FnOnce() -> C,
+    {
+        match self {
+            Ok(value) => Ok(value),
+            Err(error) => Err(AnyError::from(error)).with_context(f),
+        }
+    }
+}
+
+impl<T> ContextExt<T, AnyError> for AnyResult<T> {
+    fn context<C>(self, context: C) -> AnyResult<T>
+    where
+        C: Display + Send + Sync + 'static,
+    {
+        match self {
+            Ok(value) => Ok(value),
+            Err(mut error) => {
+                error.wrap_with(context.to_string());
+                Err(error)
+            }
+        }
+    }
+
+    fn with_context<C, F>(self, f: F) -> AnyResult<T>
+    where
+        C: Display + Send + Sync + 'static,
+        F: FnOnce() -> C,
+    {
+        match self {
+            Ok(value) => Ok(value),
+            Err(mut error) => {
+                error.wrap_with(f().to_string());
+                Err(error)
+            }
+        }
+    }
+}
+
+impl<T> ContextExt<T, Infallible> for Option<T> {
+    fn context<C>(self, context: C) -> AnyResult<T>
+    where
+        C: Display + Send + Sync + 'static,
+    {
+        match self {
+            Some(value) => Ok(value),
+            None => Err(AnyError::msg(context)),
+        }
+    }
+
+    fn with_context<C, F>(self, f: F) -> AnyResult<T>
+    where
+        C: Display + Send + Sync + 'static,
+        F: FnOnce() -> C,
+    {
+        match self {
+            Some(value) => Ok(value),
+            None => Err(AnyError::msg(f())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::ParseIntError;
+
+    use super::*;
+    use crate::{CONTEXT_TYPE, MESSAGE_TYPE};
+
+    #[test]
+    fn test_wraps_original() {
+        let result: Result<u16, ParseIntError> = "x".parse();
+        let error = result.context("invalid port").unwrap_err();
+
+        assert_eq!(error.r#type, CONTEXT_TYPE);
+        assert_eq!(error.message(), "invalid port");
+        assert_eq!(error.inner().unwrap().r#type, "ParseIntError");
+
+        let error = Err::<(), _>(error)
+            .with_context(|| format!("loading {}", "app.toml"))
+            .unwrap_err();
+        assert_eq!(
+            error.chain_messages(),
+            [
+                "loading app.toml",
+                "invalid port",
+                "invalid digit found in string"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_option() {
+        assert_eq!(Some(1).context("missing").unwrap(), 1);
+
+        let error = None::<u8>
+            .with_context(|| "no port configured")
+            .unwrap_err();
+        assert_eq!(error.r#type, MESSAGE_TYPE);
+        assert_eq!(error.message(), "no port configured");
+    }
+}
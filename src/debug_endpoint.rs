@@ -0,0 +1,99 @@
+//! An axum route serving the contents of a [`RecentErrors`] buffer, so on-call engineers can
+//! look at recent failures without access to the logs.
+//!
+//! `GET /debug/errors` responds with `{"stats": ErrorStats, "errors": [RecentError]}`, newest
+//! error first.
+
+use std::{convert::Infallible, sync::Arc};
+
+use axum::{Json, Router, extract::Request, response::IntoResponse, routing::Route, routing::get};
+use serde::Serialize;
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::recent::{ErrorStats, RecentError, RecentErrors};
+
+pub const DEBUG_ERRORS_PATH: &str = "/debug/errors";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebugErrors {
+    pub stats: ErrorStats,
+    pub errors: Vec<RecentError>,
+}
+
+/// A router serving [`DEBUG_ERRORS_PATH`], behind `auth`. The layer is required since error
+/// messages can contain user data; merge the router into the application's.
+pub fn debug_errors_router<S, L>(recent: Arc<RecentErrors>, auth: L) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+    L: Layer<Route> + Clone + Send + Sync + 'static,
+    L::Service: Service<Request> + Clone + Send + Sync + 'static,
+    <L::Service as Service<Request>>::Response: IntoResponse + 'static,
+    <L::Service as Service<Request>>::Error: Into<Infallible> + 'static,
+    <L::Service as Service<Request>>::Future: Send + 'static,
+{
+    Router::new()
+        .route(
+            DEBUG_ERRORS_PATH,
+            get(move || {
+                let body = DebugErrors {
+                    stats: recent.stats(),
+                    errors: recent.snapshot(),
+                };
+                std::future::ready(Json(body))
+            }),
+        )
+        .route_layer(auth)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{Body, to_bytes},
+        http::{StatusCode, header::AUTHORIZATION},
+        middleware::{self, Next},
+        response::Response,
+    };
+
+    use super::*;
+    use crate::{AnyError, sink::ErrorSink};
+
+    async fn require_token(request: Request, next: Next) -> Response {
+        match request.headers().get(AUTHORIZATION) {
+            Some(value) if value == "Bearer on-call" => next.run(request).await,
+            _ => StatusCode::UNAUTHORIZED.into_response(),
+        }
+    }
+
+    async fn get(router: &mut Router, token: Option<&str>) -> Response {
+        let mut request = Request::get(DEBUG_ERRORS_PATH);
+        if let Some(token) = token {
+            request = request.header(AUTHORIZATION, format!("Bearer {token}"));
+        }
+        router
+            .call(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_serves_recent_errors_behind_auth() {
+        let recent = Arc::new(RecentErrors::new(4));
+        recent.report(&AnyError::msg("first"));
+        recent.report(&AnyError::msg("second"));
+        let mut router = debug_errors_router(recent, middleware::from_fn(require_token));
+
+        assert_eq!(
+            get(&mut router, None).await.status(),
+            StatusCode::UNAUTHORIZED
+        );
+
+        let response = get(&mut router, Some("on-call")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["stats"]["total"], 2);
+        assert_eq!(json["errors"][0]["error"]["context"]["message"], "second");
+    }
+}
@@ -0,0 +1,210 @@
+use std::{
+    collections::BTreeMap,
+    sync::{LazyLock, RwLock},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{AnyError, CODE_EXTENSION};
+
+/// Maps `$type` tags or error codes to documentation URLs. Conversions into [`AnyError`] look
+/// up the global registry by `$type` and fill in [`AnyError::docs_url`];
+/// [`AnyErrorBuilder::code`](crate::builder::AnyErrorBuilder::code) looks up the code first.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocsRegistry {
+    urls: BTreeMap<String, String>,
+}
+
+impl DocsRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maps `key` to `url`, returning the URL it replaced.
+    pub fn register(&mut self, key: impl Into<String>, url: impl Into<String>) -> Option<String> {
+        self.urls.insert(key.into(), url.into())
+    }
+
+    pub fn url_for(&self, key: &str) -> Option<&str> {
+        self.urls.get(key).map(String::as_str)
+    }
+
+    /// The URL of one layer of `error`: by its [`CODE_EXTENSION`] if it has a registered one,
+    /// else by its `$type`.
+    pub fn url_for_error(&self, error: &AnyError) -> Option<&str> {
+        error
+            .context
+            .details
+            .extensions
+            .get(CODE_EXTENSION)
+            .and_then(serde_json::Value::as_str)
+            .and_then(|code| self.url_for(code))
+            .or_else(|| self.url_for(&error.r#type))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.urls
+            .iter()
+            .map(|(key, url)| (key.as_str(), url.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.urls.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.urls.is_empty()
+    }
+
+    /// Sets the docs URL of every layer of `error` that has none and whose code or `$type` is
+    /// registered, e.g. after adding codes to converted errors.
+    pub fn apply(&self, error: &mut AnyError) {
+        let mut node = Some(error);
+        while let Some(error) = node {
+            if error.context.details.docs_url.is_none()
+                && let Some(url) = self.url_for_error(error)
+            {
+                error.context.details.docs_url = Some(url.to_string());
+            }
+            node = error.context.inner_mut();
+        }
+    }
+}
+
+static DOCS: LazyLock<RwLock<DocsRegistry>> = LazyLock::new(Default::default);
+
+pub fn register(key: impl Into<String>, url: impl Into<String>) -> Option<String> {
+    DOCS.write()
+        .unwrap_or_else(|e| e.into_inner())
+        .register(key, url)
+}
+
+pub fn url_for(key: &str) -> Option<String> {
+    DOCS.read()
+        .unwrap_or_else(|e| e.into_inner())
+        .url_for(key)
+        .map(str::to_string)
+}
+
+/// See [`DocsRegistry::url_for_error`].
+pub fn url_for_error(error: &AnyError) -> Option<String> {
+    DOCS.read()
+        .unwrap_or_else(|e| e.into_inner())
+        .url_for_error(error)
+        .map(str::to_string)
+}
+
+pub fn snapshot() -> DocsRegistry {
+    DOCS.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_and_lookup() {
+        let mut registry = DocsRegistry::new();
+
+        assert_eq!(registry.register("app.user.not_found", "https://a"), None);
+        assert_eq!(
+            registry.register("app.user.not_found", "https://b"),
+            Some("https://a".to_string())
+        );
+        registry.register("USR002", "https://c");
+
+        assert_eq!(registry.url_for("app.user.not_found"), Some("https://b"));
+        assert_eq!(registry.url_for("USR002"), Some("https://c"));
+        assert_eq!(registry.url_for("missing"), None);
+        assert_eq!(registry.len(), 2);
+    }
+
+    #[test]
+    fn test_apply_fills_chain() {
+        let mut registry = DocsRegistry::new();
+        registry.register("DiskError", "https://docs.example.com/disk");
+        registry.register("SaveError", "https://docs.example.com/save");
+
+        let root = AnyError::new("DiskError".to_string(), "disk full".to_string(), None);
+        let mut error = AnyError::new("SaveError".to_string(), "save failed".to_string(), None)
+            .with_inner(root)
+            .with_docs_url("https://docs.example.com/custom");
+        registry.apply(&mut error);
+
+        assert_eq!(error.docs_url(), Some("https://docs.example.com/custom"));
+        assert_eq!(
+            error.inner().and_then(AnyError::docs_url),
+            Some("https://docs.example.com/disk")
+        );
+    }
+
+    #[test]
+    fn test_lookup_by_code() {
+        let mut registry = DocsRegistry::new();
+        registry.register("billing.CardDeclined", "https://docs.example.com/declined");
+        registry.register("PAY042", "https://docs.example.com/pay042");
+
+        let error = AnyError::msg("card declined").with_type("billing.CardDeclined");
+        assert_eq!(
+            registry.url_for_error(&error),
+            Some("https://docs.example.com/declined")
+        );
+        let mut error = error.with_extension(CODE_EXTENSION, "PAY042");
+        assert_eq!(
+            registry.url_for_error(&error),
+            Some("https://docs.example.com/pay042")
+        );
+        registry.apply(&mut error);
+        assert_eq!(error.docs_url(), Some("https://docs.example.com/pay042"));
+
+        let unregistered = AnyError::msg("card declined")
+            .with_type("billing.CardDeclined")
+            .with_extension(CODE_EXTENSION, "PAY043");
+        assert_eq!(
+            registry.url_for_error(&unregistered),
+            Some("https://docs.example.com/declined")
+        );
+    }
+
+    #[test]
+    fn test_global_registry_populates_builder_by_code() {
+        register("DOCS940", "https://docs.example.com/docs940");
+        let error = AnyError::builder()
+            .type_name("docs.Unregistered")
+            .message("failed")
+            .code("DOCS940")
+            .build();
+
+        assert_eq!(error.docs_url(), Some("https://docs.example.com/docs940"));
+    }
+
+    #[test]
+    fn test_global_registry_populates_conversion() {
+        #[derive(Debug)]
+        struct DocumentedError;
+
+        impl std::fmt::Display for DocumentedError {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "documented")
+            }
+        }
+
+        impl std::error::Error for DocumentedError {}
+
+        register(
+            crate::type_name::standardized_type_name::<DocumentedError>(),
+            "https://docs.example.com/documented",
+        );
+        let error = AnyError::from(DocumentedError);
+
+        assert_eq!(
+            error.docs_url(),
+            Some("https://docs.example.com/documented")
+        );
+        assert_eq!(
+            serde_json::to_value(&error).unwrap()["context"]["docsUrl"],
+            "https://docs.example.com/documented"
+        );
+    }
+}
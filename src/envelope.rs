@@ -0,0 +1,160 @@
+//! A versioned wrapper for errors crossing a transport, so consumers know which layout a
+//! payload uses. Bare `AnyError` JSON, from before envelopes, reads as [`LEGACY_SCHEMA`].
+
+use std::{collections::BTreeMap, fmt};
+
+use serde::{Deserialize, Serialize};
+
+use crate::AnyError;
+
+pub const SCHEMA: &str = "liberror/v2";
+/// A bare serialized `AnyError`.
+pub const LEGACY_SCHEMA: &str = "liberror/v1";
+/// Every schema [`ErrorEnvelope::from_json`] reads, newest first.
+pub const SUPPORTED_SCHEMAS: &[&str] = &[SCHEMA, LEGACY_SCHEMA];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorEnvelope {
+    pub schema: String,
+    pub error: AnyError,
+    /// Transport-level details about the payload, such as the producing service.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub meta: BTreeMap<String, serde_json::Value>,
+}
+
+#[derive(Debug)]
+pub enum EnvelopeError {
+    Json(serde_json::Error),
+    UnsupportedSchema(String),
+}
+
+impl fmt::Display for EnvelopeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json(error) => write!(f, "invalid error payload: {error}"),
+            Self::UnsupportedSchema(schema) => write!(f, "unsupported error schema {schema}"),
+        }
+    }
+}
+
+impl std::error::Error for EnvelopeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Json(error) => Some(error),
+            Self::UnsupportedSchema(_) => None,
+        }
+    }
+}
+
+impl ErrorEnvelope {
+    pub fn new(error: AnyError) -> Self {
+        Self {
+            schema: SCHEMA.to_string(),
+            error,
+            meta: BTreeMap::new(),
+        }
+    }
+
+    /// Sets a meta field. Values that fail to serialize are stored as `null`.
+    pub fn with_meta(mut self, key: impl Into<String>, value: impl Serialize) -> Self {
+        self.meta
+            .insert(key.into(), serde_json::to_value(value).unwrap_or_default());
+        self
+    }
+
+    /// Reads an envelope of any supported schema, or a bare legacy `AnyError`.
+    pub fn from_json(bytes: &[u8]) -> Result<Self, EnvelopeError> {
+        let value =
+            serde_json::from_slice::<serde_json::Value>(bytes).map_err(EnvelopeError::Json)?;
+        match value.get("schema").and_then(serde_json::Value::as_str) {
+            Some(SCHEMA) => serde_json::from_value(value).map_err(EnvelopeError::Json),
+            Some(schema) => Err(EnvelopeError::UnsupportedSchema(schema.to_string())),
+            None => Ok(Self {
+                schema: LEGACY_SCHEMA.to_string(),
+                ..Self::new(serde_json::from_value(value).map_err(EnvelopeError::Json)?)
+            }),
+        }
+    }
+
+    /// Picks the newest schema both sides support from the ones a peer accepts, e.g. from an
+    /// `Accept`-style header.
+    pub fn negotiate<'a>(accepted: impl IntoIterator<Item = &'a str>) -> Option<&'static str> {
+        let accepted = accepted.into_iter().map(str::trim).collect::<Vec<_>>();
+        SUPPORTED_SCHEMAS
+            .iter()
+            .find(|schema| accepted.contains(schema))
+            .copied()
+    }
+
+    /// Serializes in `schema`, which must be one of [`SUPPORTED_SCHEMAS`].
+    pub fn to_json(&self, schema: &str) -> Result<Vec<u8>, EnvelopeError> {
+        match schema {
+            SCHEMA => serde_json::to_vec(self).map_err(EnvelopeError::Json),
+            LEGACY_SCHEMA => serde_json::to_vec(&self.error).map_err(EnvelopeError::Json),
+            schema => Err(EnvelopeError::UnsupportedSchema(schema.to_string())),
+        }
+    }
+}
+
+impl From<AnyError> for ErrorEnvelope {
+    fn from(error: AnyError) -> Self {
+        Self::new(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let envelope = ErrorEnvelope::new(AnyError::msg("boom")).with_meta("service", "billing");
+        let json = envelope.to_json(SCHEMA).unwrap();
+
+        let value: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        assert_eq!(value["schema"], "liberror/v2");
+        assert_eq!(value["meta"]["service"], "billing");
+        assert_eq!(value["error"]["context"]["message"], "boom");
+
+        let decoded = ErrorEnvelope::from_json(&json).unwrap();
+        assert_eq!(decoded.schema, SCHEMA);
+        assert_eq!(decoded.error.message(), "boom");
+    }
+
+    #[test]
+    fn test_legacy_payload() {
+        let json = serde_json::to_vec(&AnyError::msg("old")).unwrap();
+        let decoded = ErrorEnvelope::from_json(&json).unwrap();
+
+        assert_eq!(decoded.schema, LEGACY_SCHEMA);
+        assert_eq!(decoded.error.message(), "old");
+        assert_eq!(decoded.to_json(LEGACY_SCHEMA).unwrap(), json);
+    }
+
+    #[test]
+    fn test_unsupported_schema() {
+        assert!(matches!(
+            ErrorEnvelope::from_json(br#"{"schema": "liberror/v9", "error": {}}"#),
+            Err(EnvelopeError::UnsupportedSchema(schema)) if schema == "liberror/v9"
+        ));
+        assert!(
+            ErrorEnvelope::new(AnyError::msg("x"))
+                .to_json("other")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_negotiate() {
+        assert_eq!(
+            ErrorEnvelope::negotiate("liberror/v1, liberror/v2".split(',')),
+            Some(SCHEMA)
+        );
+        assert_eq!(
+            ErrorEnvelope::negotiate(["liberror/v1"]),
+            Some(LEGACY_SCHEMA)
+        );
+        assert_eq!(ErrorEnvelope::negotiate(["liberror/v9"]), None);
+    }
+}
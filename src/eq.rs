@@ -0,0 +1,85 @@
+//! Equality and hashing over what an error says rather than where it came from: the `$type`
+//! and message of every layer and cause. Locations, timestamps, backtraces and the rest of
+//! the details are ignored, so the same failure raised twice compares equal.
+
+use std::hash::{Hash, Hasher};
+
+use crate::AnyError;
+
+impl AnyError {
+    /// Every layer and cause, depth first, with its number of causes so differently shaped
+    /// trees don't produce the same sequence.
+    fn layers(&self) -> impl Iterator<Item = (&str, &str, usize)> {
+        let mut stack = vec![self];
+        std::iter::from_fn(move || {
+            let error = stack.pop()?;
+            let len = stack.len();
+            stack.extend(error.causes());
+            stack[len..].reverse();
+            Some((error.r#type.as_str(), error.message(), stack.len() - len))
+        })
+    }
+}
+
+impl PartialEq for AnyError {
+    fn eq(&self, other: &Self) -> bool {
+        self.layers().eq(other.layers())
+    }
+}
+
+impl Eq for AnyError {}
+
+impl Hash for AnyError {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for layer in self.layers() {
+            layer.hash(state);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    fn not_found(id: u32) -> AnyError {
+        let mut error = AnyError::msg(format!("user {id} not found")).with_type("app.not_found");
+        error.wrap_with("loading profile");
+        error
+    }
+
+    #[test]
+    fn test_ignores_details() {
+        let first = not_found(1);
+        let mut second = AnyError::msg("user 1 not found").with_type("app.not_found");
+        second.wrap_with("loading profile");
+        let second = second
+            .with_help("check the id")
+            .with_extension("retry", false);
+
+        assert_ne!(first.location(), second.location());
+        assert_eq!(first, second);
+        assert_ne!(first, not_found(2));
+        assert_ne!(first, not_found(1).wrap_msg("handling request"));
+    }
+
+    #[test]
+    // The lazily resolved frames of a backtrace are the interior mutability, they aren't hashed.
+    #[allow(clippy::mutable_key_type)]
+    fn test_causes_and_hash() {
+        let mut aggregate = AnyError::msg("2 errors");
+        aggregate.add_cause(not_found(1));
+        aggregate.add_cause(not_found(2));
+        let mut reordered = AnyError::msg("2 errors");
+        reordered.add_cause(not_found(2));
+        reordered.add_cause(not_found(1));
+        assert_ne!(aggregate, reordered);
+
+        let errors = [not_found(1), not_found(1), not_found(2), aggregate.clone()]
+            .into_iter()
+            .collect::<HashSet<_>>();
+        assert_eq!(errors.len(), 3);
+        assert!(errors.contains(&aggregate));
+    }
+}
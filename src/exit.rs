@@ -0,0 +1,121 @@
+use std::{
+    io::{self, Write},
+    process::{ExitCode, Termination},
+};
+
+use crate::AnyError;
+
+/// A `main` return type that exits with [`AnyError::exit_code`] instead of the generic
+/// failure code `Result<(), E>` uses.
+///
+/// An error whose exit code is 0 (such as a clap `--help` display) is printed to stdout
+/// as-is; any other error is printed to stderr with its help text.
+///
+/// A `clap::Error` converted here keeps clap's exit code. One converted into an `AnyError`
+/// first with `?` loses it: convert those with [`IntoAnyError`](crate::IntoAnyError), e.g.
+/// `.map_err(IntoAnyError::into_any_error)?`.
+///
+/// ```no_run
+/// use liberror::{AnyError, exit::Exit};
+///
+/// fn run() -> Result<(), AnyError> {
+///     Ok(())
+/// }
+///
+/// fn main() -> Exit {
+///     run().into()
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Exit(pub Result<(), AnyError>);
+
+impl<E: Into<AnyError> + 'static> From<Result<(), E>> for Exit {
+    fn from(result: Result<(), E>) -> Self {
+        Self(result.map_err(|error| {
+            #[cfg(feature = "clap")]
+            let error = match crate::compat::clap::downcast(error) {
+                Ok(error) => return error,
+                Err(error) => error,
+            };
+            error.into()
+        }))
+    }
+}
+
+impl Exit {
+    /// Writes the error the way [`Exit::report`](Termination::report) does, returning the
+    /// exit code.
+    fn write_to(self, stdout: &mut impl Write, stderr: &mut impl Write) -> u8 {
+        let Err(error) = self.0 else {
+            return 0;
+        };
+
+        // Nothing useful can be done if the streams are closed, the exit code still applies.
+        match error.exit_code() {
+            0 => {
+                let _ = write!(stdout, "{}", error.message());
+                0
+            }
+            code => {
+                let _ = writeln!(stderr, "Error: {error:#}");
+                code
+            }
+        }
+    }
+}
+
+impl Termination for Exit {
+    fn report(self) -> ExitCode {
+        ExitCode::from(self.write_to(&mut io::stdout(), &mut io::stderr()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(exit: Exit) -> (u8, String, String) {
+        let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
+        let code = exit.write_to(&mut stdout, &mut stderr);
+        (
+            code,
+            String::from_utf8(stdout).unwrap(),
+            String::from_utf8(stderr).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_failure() {
+        let (code, stdout, stderr) = write(Err(AnyError::msg("disk full")).into());
+        assert_eq!(code, 1);
+        assert!(stdout.is_empty());
+        assert_eq!(stderr, "Error: Message: disk full\n");
+
+        assert_eq!(
+            write(Ok::<(), AnyError>(()).into()),
+            (0, String::new(), String::new())
+        );
+    }
+
+    #[cfg(feature = "clap")]
+    #[test]
+    fn test_clap_help() {
+        let result = clap::Command::new("app")
+            .about("does things")
+            .try_get_matches_from(["app", "--help"])
+            .map(drop);
+
+        let (code, stdout, stderr) = write(result.into());
+        assert_eq!(code, 0);
+        assert!(stdout.contains("does things"), "{stdout}");
+        assert!(!stdout.contains("Error:"), "{stdout}");
+        assert!(stderr.is_empty(), "{stderr}");
+
+        let result = clap::Command::new("app")
+            .try_get_matches_from(["app", "--port"])
+            .map(drop);
+        let (code, _, stderr) = write(result.into());
+        assert_eq!(code, 2);
+        assert!(stderr.starts_with("Error: "), "{stderr}");
+    }
+}
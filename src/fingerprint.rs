@@ -0,0 +1,147 @@
+use crate::{AnyError, config};
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// What [`AnyError::fingerprint`] groups by. Messages are normalized first: words containing
+/// a digit, such as ids, counts and durations, are replaced by `#`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum FingerprintStrategy {
+    /// The outermost `$type`.
+    Type,
+    /// The outermost `$type` and message.
+    TypeAndMessage,
+    /// The `$type`s of the chain.
+    #[default]
+    ChainTypes,
+    /// The `$type`s and messages of the chain.
+    FullChain,
+}
+
+fn normalized(message: &str) -> String {
+    let mut normalized = String::with_capacity(message.len());
+    let mut rest = message;
+    while let Some(start) = rest.find(is_word) {
+        normalized.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = rest.find(|c| !is_word(c)).unwrap_or(rest.len());
+        let word = &rest[..end];
+        if word.contains(|c: char| c.is_ascii_digit()) {
+            normalized.push('#');
+        } else {
+            normalized.push_str(word);
+        }
+        rest = &rest[end..];
+    }
+    normalized.push_str(rest);
+    normalized
+}
+
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-'
+}
+
+impl AnyError {
+    /// A grouping key, stable across processes and releases, using the configured
+    /// [`FingerprintStrategy`]. By default messages are left out so errors differing only in
+    /// ids or values group together.
+    pub fn fingerprint(&self) -> u64 {
+        self.fingerprint_with(config::current().fingerprint_strategy)
+    }
+
+    pub fn fingerprint_with(&self, strategy: FingerprintStrategy) -> u64 {
+        let (layers, messages) = match strategy {
+            FingerprintStrategy::Type => (1, false),
+            FingerprintStrategy::TypeAndMessage => (1, true),
+            FingerprintStrategy::ChainTypes => (usize::MAX, false),
+            FingerprintStrategy::FullChain => (usize::MAX, true),
+        };
+
+        let mut hash = FNV_OFFSET;
+        for error in self.chain().take(layers) {
+            hash = fnv1a(hash, error.r#type.as_bytes());
+            hash = fnv1a(hash, &[0]);
+            if messages {
+                hash = fnv1a(hash, normalized(error.message()).as_bytes());
+                hash = fnv1a(hash, &[0]);
+            }
+        }
+        hash
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint_ignores_messages() {
+        let first = AnyError::msg("user 1 not found").with_type("app.not_found");
+        let second = AnyError::msg("user 2 not found").with_type("app.not_found");
+        let other = AnyError::msg("user 1 not found").with_type("app.forbidden");
+
+        assert_eq!(first.fingerprint(), second.fingerprint());
+        assert_ne!(first.fingerprint(), other.fingerprint());
+    }
+
+    #[test]
+    fn test_fingerprint_covers_chain() {
+        let bare = AnyError::msg("failed").with_type("app.save");
+        let caused = bare.clone().with_inner(AnyError::msg("disk full"));
+
+        assert_ne!(bare.fingerprint(), caused.fingerprint());
+        assert_eq!(bare.fingerprint(), 0x7bb1_96cd_aa10_7219);
+    }
+
+    #[test]
+    fn test_strategies() {
+        let error = |id: u32, cause: &str| {
+            AnyError::msg(format!("user {id} not found"))
+                .with_type("app.not_found")
+                .with_inner(AnyError::msg(cause))
+        };
+        let first = error(1, "no rows");
+        let other_id = error(2, "no rows");
+        let other_cause = error(1, "timed out after 30s");
+
+        assert_eq!(
+            normalized("timed out after 30s (request 4f9a-11, user_7)"),
+            "timed out after # (request #, #)"
+        );
+
+        let fingerprint = |error: &AnyError, strategy| error.fingerprint_with(strategy);
+        for strategy in [
+            FingerprintStrategy::Type,
+            FingerprintStrategy::TypeAndMessage,
+            FingerprintStrategy::ChainTypes,
+            FingerprintStrategy::FullChain,
+        ] {
+            assert_eq!(
+                fingerprint(&first, strategy),
+                fingerprint(&other_id, strategy)
+            );
+        }
+        assert_eq!(
+            fingerprint(&first, FingerprintStrategy::TypeAndMessage),
+            fingerprint(&other_cause, FingerprintStrategy::TypeAndMessage)
+        );
+        assert_ne!(
+            fingerprint(&first, FingerprintStrategy::FullChain),
+            fingerprint(&other_cause, FingerprintStrategy::FullChain)
+        );
+        assert_ne!(
+            fingerprint(&first, FingerprintStrategy::Type),
+            fingerprint(&first, FingerprintStrategy::TypeAndMessage)
+        );
+        assert_eq!(
+            first.fingerprint(),
+            fingerprint(&first, FingerprintStrategy::ChainTypes)
+        );
+    }
+}
@@ -0,0 +1,153 @@
+//! A flat serialized shape: the chain as an array of layers, outermost first, instead of
+//! nested `innerError` objects. Log indexers handle it better and deep chains don't hit
+//! parser recursion limits.
+//!
+//! Use [`AnyError::to_flat`] directly or `#[serde(with = "liberror::flat")]` on an `AnyError`
+//! field. An error with several [causes](AnyError::causes) lists them depth first, each layer
+//! followed by its own causes, the same as [`AnyError::frames`].
+
+use std::mem;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{AnyError, AnyErrorContext, ContextDetails, frame::is_zero};
+
+/// One layer of a flattened chain: its `$type`, message, number of causes and the rest of its
+/// context.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlatFrame {
+    #[serde(rename = "$type")]
+    pub r#type: String,
+    pub message: String,
+    /// How many of the following layers, each with its own causes, are direct causes.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub causes: usize,
+    #[serde(flatten)]
+    details: ContextDetails,
+}
+
+impl AnyError {
+    pub fn to_flat(&self) -> Vec<FlatFrame> {
+        self.split_layers()
+            .map(|(mut layer, causes)| {
+                let context = layer.make_context_mut();
+                FlatFrame {
+                    message: mem::take(&mut context.message),
+                    details: mem::take(&mut *context.details),
+                    r#type: mem::take(&mut layer.r#type),
+                    causes,
+                }
+            })
+            .collect()
+    }
+
+    /// Rebuilds the error from [`AnyError::to_flat`]'s frames. `None` if `frames` is empty or
+    /// the cause counts don't describe a single tree.
+    pub fn from_flat(frames: impl IntoIterator<Item = FlatFrame>) -> Option<Self> {
+        AnyError::join_layers(frames.into_iter().map(|frame| {
+            let error = AnyError {
+                r#type: frame.r#type,
+                context: AnyErrorContext {
+                    message: frame.message,
+                    inner_error: None,
+                    details: Box::new(frame.details),
+                },
+            };
+            (error, frame.causes)
+        }))
+    }
+}
+
+pub fn serialize<S: Serializer>(error: &AnyError, serializer: S) -> Result<S::Ok, S::Error> {
+    error.to_flat().serialize(serializer)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<AnyError, D::Error> {
+    let frames = Vec::<FlatFrame>::deserialize(deserializer)?;
+    AnyError::from_flat(frames)
+        .ok_or_else(|| serde::de::Error::custom("expected the frames of a single error"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct LogLine {
+        level: String,
+        #[serde(with = "crate::flat")]
+        error: AnyError,
+    }
+
+    fn chain() -> AnyError {
+        let mut error = AnyError::msg("connection refused")
+            .with_type("io.Error")
+            .with_extension("port", 5432);
+        error.wrap_with("loading user");
+        error
+    }
+
+    #[test]
+    fn test_shape() {
+        let json = serde_json::to_value(chain().to_flat()).unwrap();
+
+        assert_eq!(json[0]["$type"], crate::CONTEXT_TYPE);
+        assert_eq!(json[0]["message"], "loading user");
+        assert_eq!(json[1]["$type"], "io.Error");
+        assert_eq!(json[1]["extensions"]["port"], 5432);
+        assert!(json[0].get("innerError").is_none());
+    }
+
+    #[test]
+    fn test_serde_with_round_trip() {
+        let error = chain();
+        let line = LogLine {
+            level: "error".to_string(),
+            error: error.clone(),
+        };
+        let json = serde_json::to_string(&line).unwrap();
+        let decoded: LogLine = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(decoded.level, "error");
+        assert_eq!(
+            serde_json::to_value(&decoded.error).unwrap(),
+            serde_json::to_value(&error).unwrap()
+        );
+        assert!(serde_json::from_str::<LogLine>(r#"{"level":"error","error":[]}"#).is_err());
+    }
+
+    #[test]
+    fn test_several_causes() {
+        let mut batch = AnyError::msg("2 of 2 uploads failed");
+        batch.add_cause(chain());
+        batch.add_cause(AnyError::msg("timed out").with_type("io.TimedOut"));
+
+        let json = serde_json::to_value(batch.to_flat()).unwrap();
+        assert_eq!(json[0]["causes"], 2);
+        assert_eq!(json[1]["causes"], 1);
+        assert_eq!(json[3]["$type"], "io.TimedOut");
+        assert!(json[0].get("innerErrors").is_none());
+
+        let frames: Vec<FlatFrame> = serde_json::from_value(json).unwrap();
+        let decoded = AnyError::from_flat(frames).unwrap();
+        assert_eq!(
+            serde_json::to_value(&decoded).unwrap(),
+            serde_json::to_value(&batch).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_deep_chain() {
+        let mut error = AnyError::from_parts("Root", "root", None);
+        for index in 0..1000 {
+            error = AnyError::from_parts("Retry", format!("attempt {index}"), Some(error));
+        }
+
+        let json = serde_json::to_string(&error.to_flat()).unwrap();
+        let frames: Vec<FlatFrame> = serde_json::from_str(&json).unwrap();
+        let decoded = AnyError::from_flat(frames).unwrap();
+        assert_eq!(decoded.depth(), 1001);
+        assert_eq!(decoded.root_cause().message(), "root");
+    }
+}
@@ -0,0 +1,289 @@
+//! A flat view of an error tree as frames: each cause or context layer followed by its
+//! location and attachments.
+//!
+//! This is a projection, not the storage. `AnyError` stays nested, because `$type` and
+//! `context` are public fields and the nested JSON is the wire shape; [`AnyError::frames`]
+//! and [`AnyError::from_frames`] convert between the two without loss.
+
+use std::mem;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    AnyError, AnyErrorContext, CONTEXT_TYPE, ContextDetails, attachment::Attachment,
+    location::SourceLocation,
+};
+
+/// One entry of the flattened view of an error tree, see [`AnyError::frames`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum Frame {
+    Cause {
+        #[serde(rename = "$type")]
+        r#type: String,
+        message: String,
+        /// How many of the following layers, each with its own causes, are direct causes.
+        #[serde(default, skip_serializing_if = "is_zero")]
+        causes: usize,
+        #[serde(flatten)]
+        details: FrameDetails,
+    },
+    Context {
+        message: String,
+        #[serde(default, skip_serializing_if = "is_zero")]
+        causes: usize,
+        #[serde(flatten)]
+        details: FrameDetails,
+    },
+    /// Where the preceding cause or context layer was created.
+    Location(SourceLocation),
+    Attachment(Attachment),
+}
+
+/// The rest of a layer's context besides its location and attachments: help, extensions,
+/// related errors and so on, serialized the same as in the nested shape.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct FrameDetails(ContextDetails);
+
+pub(crate) fn is_zero(count: &usize) -> bool {
+    *count == 0
+}
+
+impl AnyError {
+    /// Every layer and cause depth first, outermost first, without its causes and with the
+    /// number of causes it had. [`AnyError::join_layers`] undoes it.
+    pub(crate) fn split_layers(&self) -> impl Iterator<Item = (AnyError, usize)> {
+        let mut stack = vec![self];
+        std::iter::from_fn(move || {
+            let error = stack.pop()?;
+            let len = stack.len();
+            stack.extend(error.causes());
+            stack[len..].reverse();
+            let mut details = ContextDetails::clone(&error.context.details);
+            details.inner_errors.clear();
+            let layer = AnyError {
+                r#type: error.r#type.clone(),
+                context: AnyErrorContext {
+                    message: error.context.message.clone(),
+                    inner_error: None,
+                    details: Box::new(details),
+                },
+            };
+            Some((layer, stack.len() - len))
+        })
+    }
+
+    /// Rebuilds the tree from [`AnyError::split_layers`]. `None` if `layers` is empty or the
+    /// cause counts don't describe a single tree.
+    pub(crate) fn join_layers(layers: impl IntoIterator<Item = (AnyError, usize)>) -> Option<Self> {
+        let layers = layers.into_iter().collect::<Vec<_>>();
+        let mut built: Vec<AnyError> = Vec::new();
+        for (mut layer, causes) in layers.into_iter().rev() {
+            let first = built.len().checked_sub(causes)?;
+            for cause in built.drain(first..).rev() {
+                layer.add_cause(cause);
+            }
+            built.push(layer);
+        }
+        match <[AnyError; 1]>::try_from(built) {
+            Ok([error]) => Some(error),
+            Err(_) => None,
+        }
+    }
+
+    /// Flattens the tree into frames, outermost first and causes depth first. Each cause or
+    /// context frame is followed by the location and attachments of that layer. Nothing is lost:
+    /// [`AnyError::from_frames`] rebuilds the same error.
+    pub fn frames(&self) -> Vec<Frame> {
+        let mut frames = Vec::new();
+        for (mut layer, causes) in self.split_layers() {
+            let context = layer.make_context_mut();
+            let message = mem::take(&mut context.message);
+            let mut details = mem::take(&mut *context.details);
+            let location = details.location.take();
+            let attachments = mem::take(&mut details.attachments);
+            let details = FrameDetails(details);
+            frames.push(if layer.r#type == CONTEXT_TYPE {
+                Frame::Context {
+                    message,
+                    causes,
+                    details,
+                }
+            } else {
+                Frame::Cause {
+                    r#type: mem::take(&mut layer.r#type),
+                    message,
+                    causes,
+                    details,
+                }
+            });
+            frames.extend(location.map(Frame::Location));
+            frames.extend(attachments.into_iter().map(Frame::Attachment));
+        }
+        frames
+    }
+
+    /// Rebuilds an error from frames produced by [`AnyError::frames`]. Locations and
+    /// attachments that precede the first cause or context frame are dropped. `None` if there
+    /// is no cause or context frame, or the cause counts don't describe a single tree.
+    pub fn from_frames(frames: impl IntoIterator<Item = Frame>) -> Option<AnyError> {
+        let mut layers: Vec<(AnyError, usize)> = Vec::new();
+        for frame in frames {
+            let (r#type, message, causes, FrameDetails(details)) = match frame {
+                Frame::Cause {
+                    r#type,
+                    message,
+                    causes,
+                    details,
+                } => (r#type, message, causes, details),
+                Frame::Context {
+                    message,
+                    causes,
+                    details,
+                } => (CONTEXT_TYPE.to_string(), message, causes, details),
+                Frame::Location(location) => {
+                    if let Some((layer, _)) = layers.last_mut() {
+                        layer.context.details.location = Some(location);
+                    }
+                    continue;
+                }
+                Frame::Attachment(attachment) => {
+                    if let Some((layer, _)) = layers.last_mut() {
+                        layer.context.details.attachments.push(attachment);
+                    }
+                    continue;
+                }
+            };
+            let layer = AnyError {
+                r#type,
+                context: AnyErrorContext {
+                    message,
+                    inner_error: None,
+                    details: Box::new(details),
+                },
+            };
+            layers.push((layer, causes));
+        }
+        AnyError::join_layers(layers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_chain() -> AnyError {
+        let root = AnyError::new("RootError".to_string(), "disk full".to_string(), None)
+            .attach("sda1".to_string());
+        let error = AnyError::new("WriteError".to_string(), "write failed".to_string(), None)
+            .with_inner(root);
+        AnyError::new(
+            CONTEXT_TYPE.to_string(),
+            "while saving report".to_string(),
+            None,
+        )
+        .with_inner(error)
+    }
+
+    #[test]
+    fn test_frames_order() {
+        let frames = sample_chain().frames();
+
+        assert_eq!(frames.len(), 4);
+        assert!(
+            matches!(&frames[0], Frame::Context { message, causes: 1, .. } if message == "while saving report")
+        );
+        assert!(matches!(&frames[1], Frame::Cause { r#type, .. } if r#type == "WriteError"));
+        assert!(matches!(&frames[2], Frame::Cause { r#type, .. } if r#type == "RootError"));
+        assert!(matches!(&frames[3], Frame::Attachment(attachment) if attachment.value == "sda1"));
+    }
+
+    #[test]
+    fn test_from_frames_round_trip() {
+        let original = sample_chain();
+        let rebuilt = AnyError::from_frames(original.frames()).expect("non-empty frames");
+
+        assert_eq!(
+            serde_json::to_value(&rebuilt).unwrap(),
+            serde_json::to_value(&original).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_round_trip_details_and_causes() {
+        let mut batch = AnyError::msg("2 of 3 uploads failed")
+            .with_help("retry the failed uploads")
+            .with_extension("batch", 7)
+            .with_related(AnyError::msg("cleanup failed"));
+        batch.add_cause(sample_chain());
+        batch.add_cause(
+            AnyError::msg("timed out")
+                .with_type("io.TimedOut")
+                .attach(3u32),
+        );
+        let error = batch.wrap_msg("syncing");
+
+        let frames = error.frames();
+        assert_eq!(frames.len(), 11);
+        assert!(matches!(&frames[1], Frame::Location(location) if location.file == file!()));
+        assert!(matches!(&frames[2], Frame::Cause { causes: 2, .. }));
+
+        let json = serde_json::to_string(&frames).unwrap();
+        let frames: Vec<Frame> = serde_json::from_str(&json).unwrap();
+        let rebuilt = AnyError::from_frames(frames).expect("a single tree");
+        assert_eq!(
+            serde_json::to_value(&rebuilt).unwrap(),
+            serde_json::to_value(&error).unwrap()
+        );
+        assert_eq!(rebuilt.inner().unwrap().causes().count(), 2);
+    }
+
+    #[test]
+    fn test_from_frames_empty() {
+        assert!(AnyError::from_frames(Vec::new()).is_none());
+    }
+
+    #[test]
+    fn test_from_frames_inconsistent_causes() {
+        let mut frames = sample_chain().frames();
+        frames.truncate(2);
+        assert!(AnyError::from_frames(frames).is_none());
+
+        let mut frames = sample_chain().frames();
+        frames.extend(sample_chain().frames());
+        assert!(AnyError::from_frames(frames).is_none());
+    }
+
+    #[test]
+    fn test_frame_serialization() {
+        let json = serde_json::to_value(Frame::Cause {
+            r#type: "RootError".to_string(),
+            message: "disk full".to_string(),
+            causes: 0,
+            details: FrameDetails::default(),
+        })
+        .unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({ "kind": "cause", "$type": "RootError", "message": "disk full" })
+        );
+
+        let json = serde_json::to_value(Frame::Location(SourceLocation {
+            file: "src/disk.rs".to_string(),
+            line: 40,
+            column: 17,
+        }))
+        .unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "kind": "location",
+                "file": "src/disk.rs",
+                "line": 40,
+                "column": 17,
+            })
+        );
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::BTreeMap;
+
+use crate::AnyError;
+
+/// Errors sharing a grouping key, see [`GroupErrors`].
+#[derive(Debug, Clone, Copy)]
+pub struct ErrorGroup<'a> {
+    pub count: usize,
+    /// The first error seen with this key.
+    pub first: &'a AnyError,
+}
+
+/// Summaries over a collection of errors, e.g. for a batch job reporting how many distinct
+/// failures it hit. Implemented for anything iterating over `&AnyError`.
+pub trait GroupErrors<'a>: Sized {
+    /// Groups by the outermost `$type`.
+    fn group_by_type(self) -> BTreeMap<&'a str, ErrorGroup<'a>>;
+
+    /// Groups by [`AnyError::fingerprint`].
+    fn group_by_fingerprint(self) -> BTreeMap<u64, ErrorGroup<'a>>;
+
+    /// The `n` most common outermost `$type`s, most frequent first.
+    fn top_n(self, n: usize) -> Vec<(&'a str, ErrorGroup<'a>)> {
+        let mut groups = self.group_by_type().into_iter().collect::<Vec<_>>();
+        groups.sort_by(|(a_type, a), (b_type, b)| b.count.cmp(&a.count).then(a_type.cmp(b_type)));
+        groups.truncate(n);
+        groups
+    }
+}
+
+fn group_by<'a, K: Ord>(
+    errors: impl IntoIterator<Item = &'a AnyError>,
+    key: impl Fn(&'a AnyError) -> K,
+) -> BTreeMap<K, ErrorGroup<'a>> {
+    let mut groups = BTreeMap::new();
+    for error in errors {
+        groups
+            .entry(key(error))
+            .and_modify(|group: &mut ErrorGroup<'a>| group.count += 1)
+            .or_insert(ErrorGroup {
+                count: 1,
+                first: error,
+            });
+    }
+    groups
+}
+
+impl<'a, I: IntoIterator<Item = &'a AnyError>> GroupErrors<'a> for I {
+    fn group_by_type(self) -> BTreeMap<&'a str, ErrorGroup<'a>> {
+        group_by(self, |error| error.r#type.as_str())
+    }
+
+    fn group_by_fingerprint(self) -> BTreeMap<u64, ErrorGroup<'a>> {
+        group_by(self, AnyError::fingerprint)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn errors() -> Vec<AnyError> {
+        let mut errors = Vec::new();
+        for row in 0..5 {
+            errors.push(AnyError::msg(format!("row {row}: bad date")).with_type("app.parse"));
+        }
+        for row in 0..3 {
+            errors.push(AnyError::msg(format!("row {row}: duplicate")).with_type("app.conflict"));
+        }
+        errors.push(
+            AnyError::msg("row 9: duplicate")
+                .with_type("app.conflict")
+                .with_inner(AnyError::msg("unique violation")),
+        );
+        errors.push(AnyError::msg("timeout").with_type("app.timeout"));
+        errors
+    }
+
+    #[test]
+    fn test_group_by_type() {
+        let errors = errors();
+        let groups = errors.iter().group_by_type();
+
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups["app.parse"].count, 5);
+        assert_eq!(groups["app.conflict"].count, 4);
+        assert_eq!(groups["app.parse"].first.message(), "row 0: bad date");
+    }
+
+    #[test]
+    fn test_group_by_fingerprint() {
+        let errors = errors();
+
+        assert_eq!((&errors).group_by_fingerprint().len(), 4);
+    }
+
+    #[test]
+    fn test_top_n() {
+        let errors = errors();
+        let top = errors.iter().top_n(2);
+
+        assert_eq!(
+            top.iter()
+                .map(|(r#type, group)| (*r#type, group.count))
+                .collect::<Vec<_>>(),
+            vec![("app.parse", 5), ("app.conflict", 4)]
+        );
+    }
+}
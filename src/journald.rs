@@ -0,0 +1,128 @@
+//! Sends errors straight to systemd-journald over its native protocol, with the error's
+//! metadata as fields so `journalctl ERROR_TYPE=io.Error` finds them.
+
+use std::{io, os::unix::net::UnixDatagram};
+
+use crate::{AnyError, CODE_EXTENSION, severity::Severity};
+
+pub const JOURNAL_SOCKET: &str = "/run/systemd/journal/socket";
+
+impl AnyError {
+    /// The journal fields for this error:
+    ///
+    /// - `MESSAGE`, the chain's messages joined by `: `
+    /// - `PRIORITY`, from the error's [`Severity`], `err` if it has none
+    /// - `ERROR_TYPE`, the outermost `$type`
+    /// - `ERROR_FINGERPRINT`, the [fingerprint](AnyError::fingerprint) in hex, stable
+    ///   across occurrences of the same failure
+    /// - `ERROR_CHAIN`, every `$type` of the chain joined by ` > `
+    /// - `ERROR_CODE`, the [`CODE_EXTENSION`], when the error has one
+    /// - `ERROR_CATEGORY`, when the error has one
+    pub fn journal_fields(&self) -> Vec<(&'static str, String)> {
+        let mut fields = vec![
+            ("MESSAGE", self.chain_messages().join(": ")),
+            (
+                "PRIORITY",
+                self.severity()
+                    .unwrap_or(Severity::Error)
+                    .syslog_severity()
+                    .to_string(),
+            ),
+            ("ERROR_TYPE", self.r#type.clone()),
+            ("ERROR_FINGERPRINT", format!("{:016x}", self.fingerprint())),
+            ("ERROR_CHAIN", self.chain_types().join(" > ")),
+        ];
+        if let Some(code) = self.extension(CODE_EXTENSION) {
+            let code = match code {
+                serde_json::Value::String(code) => code.clone(),
+                code => code.to_string(),
+            };
+            fields.push(("ERROR_CODE", code));
+        }
+        if let Some(serde_json::Value::String(category)) = self
+            .category()
+            .and_then(|category| serde_json::to_value(category).ok())
+        {
+            fields.push(("ERROR_CATEGORY", category));
+        }
+        fields
+    }
+
+    /// Logs the error to the local journal, see [`AnyError::journal_fields`].
+    pub fn send_to_journal(&self) -> io::Result<()> {
+        let socket = UnixDatagram::unbound()?;
+        socket.send_to(&encode(&self.journal_fields()), JOURNAL_SOCKET)?;
+        Ok(())
+    }
+}
+
+/// The native protocol's datagram: `KEY=value\n`, or `KEY\n`, a little-endian 64-bit length
+/// and the raw value for values containing newlines.
+fn encode(fields: &[(&str, String)]) -> Vec<u8> {
+    let mut datagram = Vec::new();
+    for (key, value) in fields {
+        datagram.extend_from_slice(key.as_bytes());
+        if value.contains('\n') {
+            datagram.push(b'\n');
+            datagram.extend_from_slice(&(value.len() as u64).to_le_bytes());
+        } else {
+            datagram.push(b'=');
+        }
+        datagram.extend_from_slice(value.as_bytes());
+        datagram.push(b'\n');
+    }
+    datagram
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::category::Category;
+
+    #[test]
+    fn test_journal_fields() {
+        let mut error = AnyError::msg("connection refused")
+            .with_type("io.Error")
+            .with_category(Category::Unavailable);
+        error.wrap_with("loading user");
+
+        let fields = error.journal_fields();
+        let field = |key| {
+            fields
+                .iter()
+                .find_map(|(k, value)| (*k == key).then_some(value.as_str()))
+        };
+        assert_eq!(field("MESSAGE"), Some("loading user: connection refused"));
+        assert_eq!(field("ERROR_TYPE"), Some("Context"));
+        assert_eq!(field("ERROR_CHAIN"), Some("Context > io.Error"));
+        assert_eq!(field("ERROR_FINGERPRINT").map(str::len), Some(16));
+        assert_eq!(field("ERROR_CODE"), None);
+        assert_eq!(field("ERROR_CATEGORY"), Some("unavailable"));
+        assert_eq!(field("PRIORITY"), Some("3"));
+
+        let fields = error
+            .with_severity(Severity::Warning)
+            .with_extension(CODE_EXTENSION, "DB007")
+            .journal_fields();
+        assert!(fields.contains(&("PRIORITY", "4".to_string())));
+        assert!(fields.contains(&("ERROR_CODE", "DB007".to_string())));
+    }
+
+    #[test]
+    fn test_encode() {
+        let datagram = encode(&[
+            ("MESSAGE", "a\nb".to_string()),
+            ("ERROR_TYPE", "io.Error".to_string()),
+        ]);
+
+        assert_eq!(
+            datagram,
+            [
+                b"MESSAGE\n".as_slice(),
+                &3u64.to_le_bytes(),
+                b"a\nb\nERROR_TYPE=io.Error\n",
+            ]
+            .concat()
+        );
+    }
+}
@@ -1,10 +1,119 @@
-use std::{error::Error, fmt::Display};
+#![cfg_attr(feature = "nightly", feature(error_generic_member_access))]
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    error::Error,
+    fmt::Display,
+    panic::{RefUnwindSafe, UnwindSafe},
+    sync::Arc,
+    time::Duration,
+};
+pub mod aggregate;
+#[cfg(feature = "arrow")]
+pub mod arrow;
+pub mod attachment;
+pub mod backtrace;
+#[cfg(feature = "tokio")]
+pub mod batch;
+pub mod bounded;
+pub mod builder;
+pub mod catalog;
+pub mod category;
+pub mod causes;
+pub mod chain;
+pub mod channel;
+pub mod codes;
+pub mod compat;
+pub mod config;
+pub mod context;
+#[cfg(feature = "axum")]
+pub mod debug_endpoint;
+pub mod docs;
+pub mod envelope;
+mod eq;
+pub mod exit;
+pub mod fingerprint;
+pub mod flat;
+pub mod frame;
+pub mod group;
+#[cfg(all(unix, feature = "journald"))]
+pub mod journald;
+pub mod local;
+pub mod location;
+pub mod mapping;
+pub mod mask;
+mod nesting;
+pub mod option;
+pub mod os_error;
+pub mod panic;
+pub mod poison;
+pub mod prelude;
+pub mod preserve;
+#[cfg(feature = "nightly")]
+mod provide;
+pub mod recent;
+#[cfg(feature = "redact")]
+pub mod redact;
+pub mod related;
+pub mod remote;
+pub mod render;
+pub mod report;
+#[cfg(feature = "http")]
+pub mod response;
+pub mod retry;
+pub mod scope;
+pub mod severity;
+pub mod sink;
+pub mod snippet;
+pub mod span_trace;
+pub mod structured;
+pub mod syslog;
+pub mod tag;
+pub mod text;
+pub mod timed;
+pub mod timeout;
+#[cfg(feature = "time")]
+pub mod timestamp;
+#[cfg(feature = "tiny")]
+pub mod tiny;
+#[cfg(feature = "token")]
+pub mod token;
+pub mod truncate;
 pub mod type_name;
+pub mod view;
+pub mod wire;
+
+#[cfg(feature = "derive")]
+pub use liberror_derive::LibError;
+pub use scope::context_scope;
+pub use timed::{timed, timed_async};
+
+use attachment::Attachment;
+use backtrace::Backtrace;
+use category::Category;
+use config::Config;
+use location::SourceLocation;
+use severity::Severity;
+use snippet::Snippet;
+use span_trace::SpanTrace;
 
 use serde::{Deserialize, Serialize};
-use type_name::standardized_type_name_of;
-
-#[derive(Debug, Serialize, Deserialize, Clone, valuable::Valuable)]
+use type_name::{Origin, standardized_type_name_of};
+
+pub type AnyResult<T> = Result<T, AnyError>;
+
+/// `$type` given to layers added with [`AnyError::wrap_with`].
+pub const CONTEXT_TYPE: &str = "Context";
+/// `$type` given to errors created from a plain message with [`AnyError::msg`].
+pub const MESSAGE_TYPE: &str = "Message";
+/// An application error code, e.g. from [`define_codes!`], see
+/// [`AnyErrorBuilder::code`](builder::AnyErrorBuilder::code).
+pub const CODE_EXTENSION: &str = "code";
+/// Set on the innermost converted layer when its sources weren't followed: `"cycle"` if the
+/// next source was already in the chain, `"depth"` past [`Config::max_source_depth`].
+pub const TRUNCATED_EXTENSION: &str = "truncated";
+
+#[derive(Serialize, Deserialize, Clone, valuable::Valuable)]
 #[serde(rename_all = "camelCase")]
 pub struct AnyError {
     #[serde(rename = "$type")]
@@ -12,42 +121,713 @@ pub struct AnyError {
     pub context: AnyErrorContext,
 }
 impl<E: Error + Sized> From<E> for AnyError {
+    #[track_caller]
     fn from(value: E) -> Self {
-        let r#type = standardized_type_name_of(&value);
-        let message = format!("{value}");
-        let inner_error = value.source().map(|e| Box::new(AnyError::from(e)));
+        let mut error = Self::capture(standardized_type_name_of(&value), &value);
+        error.finish_conversion();
+
+        error
+    }
+}
+impl AnyError {
+    /// Converts `value` under `r#type`, capturing traces and the caller's location for the
+    /// outermost error.
+    #[track_caller]
+    fn capture<E: Error + ?Sized>(r#type: String, value: &E) -> Self {
+        let config = config::current();
+        let mut error = Self::from_error(r#type, value, &config);
+        error.context.details.origin = type_name::origin::<E>();
+        if error.context.details.backtrace.is_none() {
+            error.context.details.backtrace = Backtrace::capture();
+        }
+        error.context.details.span_trace = SpanTrace::capture();
+        error.record_creation();
+
+        error
+    }
+
+    /// Converts `value` and its sources, walking the sources iteratively so arbitrarily deep
+    /// chains don't overflow the stack.
+    fn from_error<E: Error + ?Sized>(r#type: String, value: &E, config: &Config) -> Self {
+        let mut sources = Vec::<&(dyn Error + 'static)>::new();
+        let mut seen = HashSet::<*const (dyn Error + 'static)>::new();
+        let mut inner = None;
+        let mut truncated = None;
+        // `value` may not be `Sized` or `'static`, so it can't join `seen`. A source is taken
+        // to be `value` itself if it has the same address, size and first source.
+        let root = (value as *const E).cast::<()>();
+        let first = value
+            .source()
+            .map(|source| source as *const (dyn Error + 'static));
+        let is_root = |source: &(dyn Error + 'static)| {
+            std::ptr::addr_eq(source, root)
+                && size_of_val(source) == size_of_val(value)
+                && source
+                    .source()
+                    .map(|source| source as *const (dyn Error + 'static))
+                    .zip(first)
+                    .is_some_and(|(next, first)| std::ptr::eq(next, first))
+        };
+        let mut next = value.source();
+        while let Some(source) = next {
+            if let Some(report) = source.downcast_ref::<report::Report>() {
+                inner = Some(report.as_any_error().clone());
+                break;
+            }
+            // Compared with their vtables, as an error shares its address with a source stored
+            // in its first field. Zero-sized errors can share an address too, a cycle of them
+            // ends at the depth cap instead.
+            if size_of_val(source) > 0 && (!seen.insert(source) || is_root(source)) {
+                truncated = Some("cycle");
+                break;
+            }
+            if sources.len() == config.max_source_depth() {
+                truncated = Some("depth");
+                break;
+            }
+            sources.push(source);
+            next = source.source();
+        }
+
+        for source in sources.iter().rev() {
+            let mut error = Self::layer(standardized_type_name_of(source), *source, config);
+            error.context.inner_error = inner.map(Arc::new);
+            timeout::classify(*source, &mut error);
+            os_error::apply(*source, &mut error);
+            structured::apply(*source, &mut error);
+            if let Some(reason) = truncated.take() {
+                error = error.with_extension(TRUNCATED_EXTENSION, reason);
+            }
+            inner = Some(error);
+        }
+
+        let mut error = Self::layer(r#type, value, config);
+        error.context.inner_error = inner.map(Arc::new);
+        match truncated {
+            Some(reason) => error.with_extension(TRUNCATED_EXTENSION, reason),
+            None => error,
+        }
+    }
+
+    /// A single layer for `value`, without its sources.
+    fn layer<E: Error + ?Sized>(r#type: String, value: &E, config: &Config) -> Self {
+        #[cfg(feature = "nightly")]
+        let (backtrace, attachments) = provide::extract(value);
+        #[cfg(not(feature = "nightly"))]
+        let (backtrace, attachments) = (None, Vec::new());
+
+        let r#type = config.namespaced(r#type);
+        let docs_url = docs::url_for(&r#type);
+        let mut error = Self {
+            r#type,
+            context: AnyErrorContext {
+                message: format!("{value}"),
+                inner_error: None,
+                details: Box::new(ContextDetails {
+                    attachments,
+                    backtrace,
+                    docs_url,
+                    ..Default::default()
+                }),
+            },
+        };
+        if let Some(max_chars) = config.max_message_chars {
+            truncate::apply(&mut error, max_chars);
+        }
+        error
+    }
 
+    fn new(r#type: String, message: String, inner_error: Option<AnyError>) -> Self {
         Self {
             r#type,
             context: AnyErrorContext {
                 message,
-                inner_error,
+                inner_error: inner_error.map(Arc::new),
+                details: Box::default(),
             },
         }
     }
+
+    /// The last step of every conversion: wraps the error in the active context scopes and,
+    /// with the `redact` feature, scrubs it with the configured redactor.
+    pub(crate) fn finish_conversion(&mut self) {
+        scope::apply(self);
+        #[cfg(feature = "redact")]
+        redact::apply(self);
+    }
+
+    /// Records where and, with the `time` feature, when this layer was created.
+    #[track_caller]
+    fn record_creation(&mut self) {
+        self.context.details.location = Some(match location::located_caller() {
+            Some(caller) => caller.into(),
+            None => SourceLocation::caller(),
+        });
+        #[cfg(feature = "time")]
+        {
+            self.context.details.timestamp = Some(time::OffsetDateTime::now_utc());
+        }
+    }
+
+    /// Mutable access to this node's context. Inner nodes are shared between clones and are
+    /// only copied when reached through [`AnyErrorContext::inner_mut`].
+    pub fn make_context_mut(&mut self) -> &mut AnyErrorContext {
+        &mut self.context
+    }
+
+    /// Builds a single layer without a live `Error` value, e.g. in tests and bridges.
+    pub fn from_parts(
+        r#type: impl Into<String>,
+        message: impl Into<String>,
+        inner: Option<AnyError>,
+    ) -> Self {
+        AnyError::new(r#type.into(), message.into(), inner)
+    }
+
+    /// Builds a chain from `(type, message)` pairs, outermost first. `None` if `layers` is
+    /// empty.
+    pub fn from_chain<T: Into<String>, M: Into<String>>(
+        layers: impl IntoIterator<Item = (T, M), IntoIter: DoubleEndedIterator>,
+    ) -> Option<Self> {
+        layers
+            .into_iter()
+            .rev()
+            .fold(None, |inner, (r#type, message)| {
+                Some(AnyError::from_parts(r#type, message, inner))
+            })
+    }
+
+    /// An error from a plain message, with [`MESSAGE_TYPE`] as its `$type`. There is no
+    /// `From<String>` or `From<&str>`: they would overlap the blanket `From<E: Error>`, as the
+    /// standard library may implement `Error` for them in the future.
+    #[track_caller]
+    pub fn msg(message: impl Display) -> Self {
+        Self::msg_as(MESSAGE_TYPE, message)
+    }
+
+    /// Like [`AnyError::msg`], with `r#type` as the `$type`, e.g.
+    /// `AnyError::msg_as("billing.QuotaExceeded", "monthly quota exceeded")`.
+    #[track_caller]
+    pub fn msg_as(r#type: impl Into<String>, message: impl Display) -> Self {
+        let mut error = AnyError::new(r#type.into(), message.to_string(), None);
+        error.record_creation();
+        error
+    }
+
+    /// For foreign types that implement `Display` but not `Error`, keeping their type name.
+    #[track_caller]
+    pub fn from_display<T: Display>(value: T) -> Self {
+        Self::capture_text::<T>(standardized_type_name_of(&value), value.to_string())
+    }
+
+    /// Like [`AnyError::from_display`], for types that only implement `Debug`.
+    #[track_caller]
+    pub fn from_debug<T: std::fmt::Debug>(value: T) -> Self {
+        Self::capture_text::<T>(standardized_type_name_of(&value), format!("{value:?}"))
+    }
+
+    /// Converts a value that isn't an `Error` the way `From` converts errors.
+    #[track_caller]
+    fn capture_text<T>(r#type: String, message: String) -> Self {
+        let config = config::current();
+        let mut error = AnyError::new(config.namespaced(r#type), message, None);
+        if let Some(max_chars) = config.max_message_chars {
+            truncate::apply(&mut error, max_chars);
+        }
+        error.context.details.docs_url = docs::url_for(&error.r#type);
+        error.context.details.origin = type_name::origin::<T>();
+        error.context.details.backtrace = Backtrace::capture();
+        error.context.details.span_trace = SpanTrace::capture();
+        error.record_creation();
+        error.finish_conversion();
+        error
+    }
+
+    pub fn message(&self) -> &str {
+        self.context.message()
+    }
+
+    pub fn inner(&self) -> Option<&AnyError> {
+        self.context.inner()
+    }
+
+    /// The `$type` of every error in the chain, outermost first.
+    pub fn chain_types(&self) -> Vec<&str> {
+        self.chain().map(|error| error.r#type.as_str()).collect()
+    }
+
+    /// The message of every error in the chain, outermost first.
+    pub fn chain_messages(&self) -> Vec<&str> {
+        self.chain().map(AnyError::message).collect()
+    }
+
+    pub fn help(&self) -> Option<&str> {
+        self.context.help()
+    }
+
+    pub fn docs_url(&self) -> Option<&str> {
+        self.context.docs_url()
+    }
+
+    /// The crate the converted error type was declared in. Only the outermost converted
+    /// error has an origin, its sources are only known as `dyn Error`.
+    pub fn origin_crate(&self) -> Option<&str> {
+        self.find_origin().map(|origin| origin.crate_name.as_str())
+    }
+
+    /// The module the converted error type was declared in, see [`AnyError::origin_crate`].
+    pub fn origin_module(&self) -> Option<&str> {
+        self.find_origin().map(|origin| origin.module.as_str())
+    }
+
+    fn find_origin(&self) -> Option<&Origin> {
+        let mut node = Some(self);
+        while let Some(error) = node {
+            if let Some(origin) = error.context.origin() {
+                return Some(origin);
+            }
+            node = error.inner();
+        }
+        None
+    }
+
+    pub fn set_message(&mut self, message: impl Into<String>) {
+        self.context.message = message.into();
+    }
+
+    /// Replaces the inner error, returning the previous one. Any further causes added with
+    /// [`AnyError::add_cause`] are dropped.
+    pub fn set_inner(&mut self, inner: Option<AnyError>) -> Option<AnyError> {
+        let others = std::mem::take(&mut self.context.details.inner_errors);
+        std::mem::replace(&mut self.context.inner_error, inner.map(Arc::new))
+            .or_else(|| others.into_iter().next())
+            .map(Arc::unwrap_or_clone)
+    }
+
+    /// Pushes a new outermost [`CONTEXT_TYPE`] layer carrying `message`, with the current
+    /// chain as its inner error.
+    #[track_caller]
+    pub fn wrap_with(&mut self, message: impl Into<String>) {
+        let mut outer = AnyError::new(CONTEXT_TYPE.to_string(), message.into(), None);
+        outer.record_creation();
+        let cause = std::mem::replace(self, outer);
+        self.context.inner_error = Some(Arc::new(cause));
+    }
+
+    /// A [`CONTEXT_TYPE`] layer carrying `message` on top of `source`, e.g.
+    /// `AnyError::wrap("while migrating schema v3", error)`.
+    #[track_caller]
+    pub fn wrap(message: impl Into<String>, source: impl Into<AnyError>) -> Self {
+        let mut error = AnyError::new(
+            CONTEXT_TYPE.to_string(),
+            message.into(),
+            Some(source.into()),
+        );
+        error.record_creation();
+        error
+    }
+
+    /// Like [`AnyError::wrap_with`], with `r#type` instead of [`CONTEXT_TYPE`] for the new
+    /// layer.
+    #[track_caller]
+    pub fn wrap_in(self, r#type: impl Into<String>, message: impl Into<String>) -> Self {
+        let mut error = AnyError::new(r#type.into(), message.into(), Some(self));
+        error.record_creation();
+        error
+    }
+
+    /// [`AnyError::wrap_with`] by value, e.g. `AnyError::from(error).wrap_msg("loading user")`.
+    #[track_caller]
+    pub fn wrap_msg(self, message: impl Into<String>) -> Self {
+        self.wrap_in(CONTEXT_TYPE, message)
+    }
+
+    /// Appends `cause` below the innermost error of the chain.
+    pub fn push_cause(&mut self, cause: AnyError) {
+        let mut node = self;
+        while node.inner().is_some() {
+            node = node.context.inner_mut().expect("inner error checked above");
+        }
+        node.context.inner_error = Some(Arc::new(cause));
+    }
+
+    pub fn attach<T: Serialize + Send + Sync + 'static>(mut self, value: T) -> Self {
+        self.context
+            .details
+            .attachments
+            .push(Attachment::new(value));
+        self
+    }
+
+    /// Finds the first attachment of type `T`, searching from the outermost error inwards.
+    pub fn attachment<T: 'static>(&self) -> Option<&T> {
+        let mut node = Some(self);
+        while let Some(error) = node {
+            if let Some(value) = error
+                .context
+                .details
+                .attachments
+                .iter()
+                .find_map(Attachment::downcast_ref)
+            {
+                return Some(value);
+            }
+            node = error.inner();
+        }
+        None
+    }
+
+    pub fn with_type(mut self, r#type: impl Into<String>) -> Self {
+        self.r#type = r#type.into();
+        self
+    }
+
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.context.message = message.into();
+        self
+    }
+
+    /// Adds a remediation hint, rendered as `help: …` by `{:#}` and reports.
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.context.details.help = Some(help.into());
+        self
+    }
+
+    /// Points consumers at a troubleshooting page, overriding the one from [`docs`].
+    pub fn with_docs_url(mut self, url: impl Into<String>) -> Self {
+        self.context.details.docs_url = Some(url.into());
+        self
+    }
+
+    /// Sets a structured field on this layer. Values that fail to serialize are stored as
+    /// `null`.
+    pub fn with_extension(mut self, key: impl Into<String>, value: impl Serialize) -> Self {
+        self.context
+            .details
+            .extensions
+            .insert(key.into(), serde_json::to_value(value).unwrap_or_default());
+        self
+    }
+
+    /// Finds the extension `key`, searching from the outermost error inwards.
+    pub fn extension(&self, key: &str) -> Option<&serde_json::Value> {
+        let mut node = Some(self);
+        while let Some(error) = node {
+            if let Some(value) = error.context.details.extensions.get(key) {
+                return Some(value);
+            }
+            node = error.inner();
+        }
+        None
+    }
+
+    /// Attaches application metadata, e.g. `.with_data("requestId", id)`, instead of
+    /// formatting it into the message.
+    pub fn with_data(mut self, key: impl Into<String>, value: impl Serialize) -> Self {
+        self.context
+            .details
+            .data
+            .insert(key.into(), serde_json::to_value(value).unwrap_or_default());
+        self
+    }
+
+    /// Finds the metadata `key`, searching from the outermost error inwards.
+    pub fn data(&self, key: &str) -> Option<&serde_json::Value> {
+        let mut node = Some(self);
+        while let Some(error) = node {
+            if let Some(value) = error.context.details.data.get(key) {
+                return Some(value);
+            }
+            node = error.inner();
+        }
+        None
+    }
+
+    /// The error as a JSON tree, for middleware that inspects or patches errors generically.
+    /// Round-trips through [`AnyError::from_value`].
+    pub fn to_value(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_default()
+    }
+
+    pub fn from_value(value: serde_json::Value) -> serde_json::Result<Self> {
+        serde_json::from_value(value)
+    }
+
+    /// The `context` of [`AnyError::to_value`]: the message, inner errors and details.
+    pub fn context_value(&self) -> serde_json::Value {
+        serde_json::to_value(&self.context).unwrap_or_default()
+    }
+
+    pub fn with_inner(mut self, inner: AnyError) -> Self {
+        self.set_inner(Some(inner));
+        self
+    }
 }
+
 impl Display for AnyError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}: {}", self.r#type, self.context.message)?;
-        if let Some(inner_error) = self.context.inner_error.as_ref() {
-            write!(f, "({})", inner_error)?;
+        enum Part<'a> {
+            Error(&'a AnyError),
+            Text(&'static str),
+        }
+
+        // Renders `type: message(cause; cause)` with an explicit stack, so deep chains don't
+        // overflow the call stack.
+        let mut stack = vec![Part::Error(self)];
+        while let Some(part) = stack.pop() {
+            let error = match part {
+                Part::Error(error) => error,
+                Part::Text(text) => {
+                    f.write_str(text)?;
+                    continue;
+                }
+            };
+            write!(f, "{}: {}", error.r#type, error.context.message)?;
+
+            let causes = error.causes().collect::<Vec<_>>();
+            if causes.is_empty() {
+                continue;
+            }
+            stack.push(Part::Text(")"));
+            for (index, cause) in causes.into_iter().enumerate().rev() {
+                stack.push(Part::Error(cause));
+                if index > 0 {
+                    stack.push(Part::Text("; "));
+                }
+            }
+            stack.push(Part::Text("("));
+        }
+
+        if f.alternate() {
+            for error in self.chain() {
+                if let Some(help) = error.help() {
+                    write!(f, "\nhelp: {help}")?;
+                }
+            }
         }
 
         Ok(())
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, valuable::Valuable)]
+/// Conversion into an [`AnyError`] that applies what the type knows about itself, such as its
+/// tag and help text. Implemented by `#[derive(LibError)]`.
+pub trait IntoAnyError {
+    #[track_caller]
+    fn into_any_error(self) -> AnyError;
+}
+
+// Serialized by hand in `nesting`, so deep chains are cut rather than overflowing the stack.
+#[derive(Debug, Default, Deserialize, Clone, valuable::Valuable)]
 #[serde(rename_all = "camelCase")]
 pub struct AnyErrorContext {
     message: String,
-    inner_error: Option<Box<AnyError>>,
+    inner_error: Option<Arc<AnyError>>,
+    #[serde(flatten)]
+    details: Box<ContextDetails>,
+}
+
+// The kept original error and typed attachments are trait objects without unwind-safety
+// bounds. Like `anyhow::Error`, errors are treated as unwind safe: they are immutable once
+// built and only read after a panic.
+impl UnwindSafe for AnyErrorContext {}
+impl RefUnwindSafe for AnyErrorContext {}
+
+impl Drop for AnyErrorContext {
+    /// Unlinks uniquely owned inner errors one at a time, so dropping a deep chain doesn't
+    /// recurse once per layer.
+    fn drop(&mut self) {
+        let mut next = self.inner_error.take();
+        while let Some(inner) = next {
+            next = match Arc::try_unwrap(inner) {
+                Ok(mut error) => error.context.inner_error.take(),
+                Err(_) => None,
+            };
+        }
+    }
+}
+
+/// The optional parts of a context, boxed so `AnyError` stays small enough to return by value.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, valuable::Valuable)]
+#[serde(rename_all = "camelCase")]
+struct ContextDetails {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[valuable(skip)]
+    attachments: Vec<Attachment>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    backtrace: Option<Backtrace>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    span_trace: Option<SpanTrace>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    location: Option<SourceLocation>,
+    #[cfg(feature = "time")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "time::serde::rfc3339::option"
+    )]
+    #[valuable(skip)]
+    timestamp: Option<time::OffsetDateTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    help: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    docs_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    category: Option<Category>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    severity: Option<Severity>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    retryable: Option<bool>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "retryAfterMs",
+        with = "retry::millis"
+    )]
+    #[valuable(skip)]
+    retry_after: Option<Duration>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    origin: Option<Origin>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    remote: bool,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    #[valuable(skip)]
+    extensions: BTreeMap<String, serde_json::Value>,
+    /// Application metadata such as request and user IDs, kept apart from the extensions
+    /// liberror and its integrations set.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    #[valuable(skip)]
+    data: BTreeMap<String, serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    snippet: Option<Snippet>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[valuable(skip)]
+    related: Vec<AnyError>,
+    /// The value converted with [`AnyError::from_preserving`], not serialized.
+    #[serde(skip)]
+    #[valuable(skip)]
+    original: Option<Arc<dyn Error + Send + Sync>>,
+    /// Set instead of `inner_error` when there is more than one cause.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[valuable(skip)]
+    inner_errors: Vec<Arc<AnyError>>,
+}
+
+impl AnyErrorContext {
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The first cause, see [`AnyError::causes`] for all of them.
+    pub fn inner(&self) -> Option<&AnyError> {
+        self.inner_error
+            .as_deref()
+            .or_else(|| self.details.inner_errors.first().map(Arc::as_ref))
+    }
+
+    pub fn attachments(&self) -> &[Attachment] {
+        &self.details.attachments
+    }
+
+    pub fn backtrace(&self) -> Option<&Backtrace> {
+        self.details.backtrace.as_ref()
+    }
+
+    pub fn span_trace(&self) -> Option<&SpanTrace> {
+        self.details.span_trace.as_ref()
+    }
+
+    /// Where this layer was created, see [`AnyError::location`].
+    pub fn location(&self) -> Option<&SourceLocation> {
+        self.details.location.as_ref()
+    }
+
+    /// When this layer was created, see [`AnyError::timestamp`].
+    #[cfg(feature = "time")]
+    pub fn timestamp(&self) -> Option<time::OffsetDateTime> {
+        self.details.timestamp
+    }
+
+    pub fn help(&self) -> Option<&str> {
+        self.details.help.as_deref()
+    }
+
+    pub fn docs_url(&self) -> Option<&str> {
+        self.details.docs_url.as_deref()
+    }
+
+    pub fn category(&self) -> Option<Category> {
+        self.details.category
+    }
+
+    pub fn severity(&self) -> Option<Severity> {
+        self.details.severity
+    }
+
+    pub fn retryable(&self) -> Option<bool> {
+        self.details.retryable
+    }
+
+    pub fn retry_after(&self) -> Option<Duration> {
+        self.details.retry_after
+    }
+
+    pub fn origin(&self) -> Option<&Origin> {
+        self.details.origin.as_ref()
+    }
+
+    pub fn snippet(&self) -> Option<&Snippet> {
+        self.details.snippet.as_ref()
+    }
+
+    /// Whether this layer was raised by another service, see [`AnyError::caused_by_remote`].
+    pub fn is_remote(&self) -> bool {
+        self.details.remote
+    }
+
+    /// Errors that happened alongside this one without causing it, see
+    /// [`AnyError::with_related`].
+    pub fn related(&self) -> &[AnyError] {
+        &self.details.related
+    }
+
+    /// Structured fields describing this layer, such as the failing key of a parse error.
+    pub fn extensions(&self) -> &BTreeMap<String, serde_json::Value> {
+        &self.details.extensions
+    }
+
+    /// Metadata attached with [`AnyError::with_data`].
+    pub fn data(&self) -> &BTreeMap<String, serde_json::Value> {
+        &self.details.data
+    }
+
+    /// Splits the context into its message and first cause, cloning the cause only if it is
+    /// still shared with another `AnyError`.
+    pub fn into_parts(mut self) -> (String, Option<AnyError>) {
+        let inner = self.inner_error.take().or_else(|| {
+            std::mem::take(&mut self.details.inner_errors)
+                .into_iter()
+                .next()
+        });
+        (
+            std::mem::take(&mut self.message),
+            inner.map(Arc::unwrap_or_clone),
+        )
+    }
+
+    /// Clone-on-write access to the inner error: if the inner node is shared with another
+    /// `AnyError`, only that node is copied, its own causes stay shared.
+    pub fn inner_mut(&mut self) -> Option<&mut AnyError> {
+        self.inner_error
+            .as_mut()
+            .or_else(|| self.details.inner_errors.first_mut())
+            .map(Arc::make_mut)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use serde_json;
     use std::error::Error as StdError;
     use std::fmt;
     use std::io;
@@ -129,6 +909,24 @@ mod tests {
         assert_eq!(any_error.context.message, "File not found");
     }
 
+    #[test]
+    fn test_origin() {
+        let any_error = AnyError::from(NestedError {
+            message: "Outer".to_string(),
+            source: SimpleError {
+                message: "Inner".to_string(),
+            },
+        });
+
+        assert_eq!(any_error.origin_crate(), Some("liberror"));
+        assert_eq!(any_error.origin_module(), Some("liberror::tests"));
+        assert!(any_error.inner().unwrap().context.origin().is_none());
+
+        let json = serde_json::to_value(&any_error).unwrap();
+        assert_eq!(json["context"]["origin"]["crate"], "liberror");
+        assert!(AnyError::msg("plain").origin_crate().is_none());
+    }
+
     #[test]
     fn test_from_nested_error() {
         let inner = SimpleError {
@@ -150,7 +948,7 @@ mod tests {
 
         assert!(any_error.context.inner_error.is_some());
 
-        let inner_error = any_error.context.inner_error.unwrap();
+        let inner_error = any_error.context.inner_error.as_ref().unwrap();
 
         assert!(
             inner_error.r#type.ends_with("SimpleError")
@@ -320,7 +1118,7 @@ mod tests {
         assert_eq!(deserialized.r#type, "OuterError");
         assert_eq!(deserialized.context.message, "Outer message");
 
-        let inner = deserialized.context.inner_error.unwrap();
+        let inner = deserialized.context.inner_error.as_ref().unwrap();
         assert_eq!(inner.r#type, "InnerError");
         assert_eq!(inner.context.message, "Inner message");
         assert!(inner.context.inner_error.is_none());
@@ -341,6 +1139,554 @@ mod tests {
         assert!(cloned.context.inner_error.is_none());
     }
 
+    #[test]
+    fn test_unwind_safe() {
+        fn assert_unwind_safe<T: UnwindSafe + RefUnwindSafe>() {}
+        assert_unwind_safe::<AnyError>();
+        assert_unwind_safe::<AnyErrorContext>();
+        assert_unwind_safe::<Attachment>();
+
+        let error = AnyError::msg("before panic").attach(7u8);
+        let result = std::panic::catch_unwind(|| error.message().len());
+        assert_eq!(result.ok(), Some(12));
+    }
+
+    #[test]
+    fn test_deep_chain_without_recursion() {
+        const DEPTH: usize = 10_000;
+        static LAYERS: std::sync::OnceLock<Vec<RetryError>> = std::sync::OnceLock::new();
+
+        #[derive(Debug)]
+        struct RetryError(usize);
+
+        impl fmt::Display for RetryError {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "attempt {}", self.0)
+            }
+        }
+
+        impl StdError for RetryError {
+            fn source(&self) -> Option<&(dyn StdError + 'static)> {
+                LAYERS
+                    .get()?
+                    .get(self.0 + 1)
+                    .map(|layer| layer as &(dyn StdError + 'static))
+            }
+        }
+
+        LAYERS.get_or_init(|| (0..DEPTH).map(RetryError).collect());
+
+        // Far too small a stack for any per-layer recursion.
+        std::thread::Builder::new()
+            .stack_size(256 * 1024)
+            .spawn(|| {
+                let config = Config {
+                    max_source_depth: Some(DEPTH),
+                    ..Default::default()
+                };
+                let any_error = AnyError::from_error("Retry".to_string(), &RetryError(0), &config);
+                assert_eq!(any_error.depth(), DEPTH);
+                assert_eq!(any_error.root_cause().message(), "attempt 9999");
+
+                let rendered = any_error.to_string();
+                assert!(rendered.ends_with(&")".repeat(DEPTH - 1)));
+
+                let clone = any_error.clone();
+                drop(any_error);
+                assert_eq!(clone.depth(), DEPTH);
+
+                let any_error = AnyError::from(RetryError(0));
+                assert_eq!(any_error.depth(), config::DEFAULT_MAX_SOURCE_DEPTH + 1);
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+
+    #[derive(Debug)]
+    struct LinkedError {
+        name: &'static str,
+        next: Option<&'static LinkedError>,
+    }
+
+    impl fmt::Display for LinkedError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(self.name)
+        }
+    }
+
+    impl StdError for LinkedError {
+        fn source(&self) -> Option<&(dyn StdError + 'static)> {
+            self.next.map(|next| next as &(dyn StdError + 'static))
+        }
+    }
+
+    #[test]
+    fn test_source_cycle_truncated() {
+        static FIRST: LinkedError = LinkedError {
+            name: "first",
+            next: Some(&SECOND),
+        };
+        static SECOND: LinkedError = LinkedError {
+            name: "second",
+            next: Some(&FIRST),
+        };
+
+        let any_error = AnyError::from_error("Linked".to_string(), &FIRST, &Config::default());
+        assert_eq!(any_error.chain_messages(), ["first", "second"]);
+        assert_eq!(
+            any_error.root_cause().context.extensions()[TRUNCATED_EXTENSION],
+            "cycle"
+        );
+        assert!(any_error.context.extensions().is_empty());
+
+        static SELF: LinkedError = LinkedError {
+            name: "self",
+            next: Some(&SELF),
+        };
+        let any_error = AnyError::from_error("Linked".to_string(), &SELF, &Config::default());
+        assert_eq!(any_error.chain_messages(), ["self"]);
+        assert_eq!(any_error.context.extensions()[TRUNCATED_EXTENSION], "cycle");
+    }
+
+    #[test]
+    fn test_max_message_chars() {
+        use truncate::MESSAGE_TRUNCATED_EXTENSION;
+
+        static THIRD: LinkedError = LinkedError {
+            name: "third",
+            next: None,
+        };
+        static SECOND: LinkedError = LinkedError {
+            name: "second",
+            next: Some(&THIRD),
+        };
+        let config = Config {
+            max_message_chars: Some(5),
+            max_source_depth: Some(1),
+            ..Default::default()
+        };
+        let first = LinkedError {
+            name: "first",
+            next: Some(&SECOND),
+        };
+
+        let any_error = AnyError::from_error("Linked".to_string(), &first, &config);
+        assert_eq!(any_error.chain_messages(), ["first", "secon…"]);
+        assert!(
+            !any_error
+                .context
+                .extensions()
+                .contains_key(MESSAGE_TRUNCATED_EXTENSION)
+        );
+        let extensions = any_error.root_cause().context.extensions();
+        assert_eq!(extensions[MESSAGE_TRUNCATED_EXTENSION], true);
+        assert_eq!(extensions[TRUNCATED_EXTENSION], "depth");
+    }
+
+    #[test]
+    fn test_max_source_depth() {
+        static THIRD: LinkedError = LinkedError {
+            name: "third",
+            next: None,
+        };
+        static SECOND: LinkedError = LinkedError {
+            name: "second",
+            next: Some(&THIRD),
+        };
+        let config = Config {
+            max_source_depth: Some(1),
+            ..Default::default()
+        };
+
+        let first = LinkedError {
+            name: "first",
+            next: Some(&SECOND),
+        };
+        let any_error = AnyError::from_error("Linked".to_string(), &first, &config);
+        assert_eq!(any_error.chain_messages(), ["first", "second"]);
+        assert_eq!(
+            any_error.root_cause().context.extensions()[TRUNCATED_EXTENSION],
+            "depth"
+        );
+
+        let config = Config {
+            max_source_depth: Some(0),
+            ..config
+        };
+        let any_error = AnyError::from_error("Linked".to_string(), &first, &config);
+        assert_eq!(any_error.depth(), 1);
+        assert_eq!(
+            any_error.extension(TRUNCATED_EXTENSION),
+            Some(&"depth".into())
+        );
+
+        let any_error = AnyError::from(first);
+        assert_eq!(any_error.depth(), 3);
+        assert_eq!(any_error.extension(TRUNCATED_EXTENSION), None);
+    }
+
+    #[test]
+    fn test_copy_on_write_inner() {
+        let level1 = SimpleError {
+            message: "Level 1 error".to_string(),
+        };
+        let level2 = NestedError {
+            message: "Level 2 error".to_string(),
+            source: level1,
+        };
+        let level3 = DeepNestedError {
+            message: "Level 3 error".to_string(),
+            source: level2,
+        };
+
+        let original = AnyError::from(level3);
+        let mut shared = original.clone();
+
+        shared
+            .make_context_mut()
+            .inner_mut()
+            .expect("inner error")
+            .make_context_mut()
+            .message = "Edited".to_string();
+
+        let original_inner = original.context.inner_error.as_ref().unwrap();
+        let shared_inner = shared.context.inner_error.as_ref().unwrap();
+
+        assert_eq!(original_inner.context.message, "Level 2 error");
+        assert_eq!(shared_inner.context.message, "Edited");
+        assert!(!Arc::ptr_eq(original_inner, shared_inner));
+        assert!(Arc::ptr_eq(
+            original_inner.context.inner_error.as_ref().unwrap(),
+            shared_inner.context.inner_error.as_ref().unwrap()
+        ));
+    }
+
+    #[test]
+    fn test_with_builders() {
+        let inner = AnyError::from(SimpleError {
+            message: "Inner".to_string(),
+        });
+
+        let any_error = AnyError::from(SimpleError {
+            message: "Outer".to_string(),
+        })
+        .with_type("CustomError")
+        .with_message("Replaced")
+        .with_inner(inner);
+
+        assert_eq!(any_error.r#type, "CustomError");
+        assert_eq!(any_error.context.message, "Replaced");
+        assert_eq!(
+            any_error
+                .context
+                .inner_error
+                .as_ref()
+                .unwrap()
+                .context
+                .message,
+            "Inner"
+        );
+    }
+
+    #[test]
+    fn test_accessors() {
+        let nested = NestedError {
+            message: "Outer".to_string(),
+            source: SimpleError {
+                message: "Inner".to_string(),
+            },
+        };
+
+        let any_error = AnyError::from(nested);
+
+        assert_eq!(any_error.message(), "Outer");
+        assert_eq!(any_error.inner().map(AnyError::message), Some("Inner"));
+        assert!(any_error.inner().unwrap().inner().is_none());
+
+        assert_eq!(
+            any_error.chain_types(),
+            vec!["liberror.tests.NestedError", "&dyn Error"]
+        );
+        assert_eq!(any_error.chain_messages(), vec!["Outer", "Inner"]);
+
+        let (message, inner) = any_error.context.into_parts();
+        assert_eq!(message, "Outer");
+        assert_eq!(inner.unwrap().context.message(), "Inner");
+    }
+
+    #[test]
+    fn test_msg() {
+        let error = AnyError::msg(format_args!("user {} not found", 42));
+        assert_eq!(error.r#type, MESSAGE_TYPE);
+        assert_eq!(error.message(), "user 42 not found");
+
+        let error = AnyError::msg_as("billing.QuotaExceeded", "monthly quota exceeded");
+        assert_eq!(error.r#type, "billing.QuotaExceeded");
+        assert_eq!(error.message(), "monthly quota exceeded");
+        assert_eq!(error.location().unwrap().file, file!());
+    }
+
+    #[test]
+    fn test_from_parts() {
+        let error = AnyError::from_parts(
+            "app.Save",
+            "save failed",
+            Some(AnyError::from_parts("io.Error", "disk full", None)),
+        );
+        assert_eq!(error.chain_types(), vec!["app.Save", "io.Error"]);
+        assert_eq!(error.chain_messages(), vec!["save failed", "disk full"]);
+
+        let chain = AnyError::from_chain(vec![
+            ("app.Request", "request failed"),
+            ("app.Save", "save failed"),
+            ("io.Error", "disk full"),
+        ])
+        .unwrap();
+        assert_eq!(
+            chain.chain_types(),
+            vec!["app.Request", "app.Save", "io.Error"]
+        );
+        assert_eq!(chain.inner().unwrap().message(), "save failed");
+        assert!(AnyError::from_chain(Vec::<(String, String)>::new()).is_none());
+    }
+
+    #[test]
+    fn test_in_place_mutators() {
+        let mut any_error = AnyError::from(SimpleError {
+            message: "Original".to_string(),
+        });
+
+        any_error.set_message("Updated");
+        assert_eq!(any_error.message(), "Updated");
+
+        let previous = any_error.set_inner(Some(AnyError::from(SimpleError {
+            message: "Cause".to_string(),
+        })));
+        assert!(previous.is_none());
+        assert_eq!(any_error.inner().unwrap().message(), "Cause");
+
+        let previous = any_error.set_inner(None);
+        assert_eq!(previous.unwrap().message(), "Cause");
+        assert!(any_error.inner().is_none());
+    }
+
+    #[test]
+    fn test_wrap_with() {
+        let mut any_error = AnyError::from(SimpleError {
+            message: "Connection reset".to_string(),
+        });
+        let original_type = any_error.r#type.clone();
+
+        any_error.wrap_with("while syncing user 42");
+
+        assert_eq!(any_error.r#type, CONTEXT_TYPE);
+        assert_eq!(any_error.message(), "while syncing user 42");
+
+        let inner = any_error.inner().unwrap();
+        assert_eq!(inner.r#type, original_type);
+        assert_eq!(inner.message(), "Connection reset");
+    }
+
+    #[test]
+    fn test_wrap_and_wrap_in() {
+        let error = AnyError::wrap(
+            "while migrating schema v3",
+            SimpleError {
+                message: "Connection reset".to_string(),
+            },
+        );
+        assert_eq!(error.r#type, CONTEXT_TYPE);
+        assert_eq!(
+            error.chain_messages(),
+            ["while migrating schema v3", "Connection reset"]
+        );
+
+        let error = error.wrap_in("app.migration", "migration failed");
+        assert_eq!(error.chain_types()[..2], ["app.migration", CONTEXT_TYPE]);
+        assert_eq!(error.message(), "migration failed");
+
+        let error = error.wrap_msg("startup failed");
+        assert_eq!(error.r#type, CONTEXT_TYPE);
+        assert_eq!(error.depth(), 4);
+        assert_eq!(error.location().unwrap().line, line!() - 3);
+    }
+
+    #[test]
+    fn test_push_cause() {
+        let mut any_error = AnyError::from(NestedError {
+            message: "Outer".to_string(),
+            source: SimpleError {
+                message: "Middle".to_string(),
+            },
+        });
+
+        any_error.push_cause(AnyError::from(SimpleError {
+            message: "Root".to_string(),
+        }));
+
+        let middle = any_error.inner().unwrap();
+        assert_eq!(middle.message(), "Middle");
+        assert_eq!(middle.inner().unwrap().message(), "Root");
+    }
+
+    #[derive(Debug, PartialEq, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct RequestInfo {
+        request_id: String,
+    }
+
+    #[test]
+    fn test_attachments() {
+        let inner = AnyError::from(SimpleError {
+            message: "Inner".to_string(),
+        })
+        .attach(RequestInfo {
+            request_id: "req-1".to_string(),
+        });
+
+        let any_error = AnyError::from(SimpleError {
+            message: "Outer".to_string(),
+        })
+        .attach(42u32)
+        .with_inner(inner);
+
+        assert_eq!(any_error.attachment::<u32>(), Some(&42));
+        assert_eq!(
+            any_error.attachment::<RequestInfo>(),
+            Some(&RequestInfo {
+                request_id: "req-1".to_string()
+            })
+        );
+        assert!(any_error.attachment::<String>().is_none());
+
+        let json = serde_json::to_value(&any_error).expect("Serialization failed");
+        assert_eq!(json["context"]["attachments"][0]["$type"], "u32");
+        assert_eq!(json["context"]["attachments"][0]["value"], 42);
+        assert_eq!(
+            json["context"]["innerError"]["context"]["attachments"][0]["value"]["requestId"],
+            "req-1"
+        );
+    }
+
+    #[test]
+    fn test_attachments_omitted_when_empty() {
+        let any_error = AnyError::from(SimpleError {
+            message: "Plain error".to_string(),
+        });
+
+        let json = serde_json::to_string(&any_error).expect("Serialization failed");
+        assert!(!json.contains("attachments"));
+    }
+
+    #[test]
+    fn test_help() {
+        let inner = AnyError::from(SimpleError {
+            message: "Inner".to_string(),
+        })
+        .with_help("check the inner thing");
+
+        let any_error = AnyError::from(SimpleError {
+            message: "Outer".to_string(),
+        })
+        .with_help("retry later")
+        .with_inner(inner);
+
+        assert_eq!(any_error.help(), Some("retry later"));
+        assert!(!format!("{any_error}").contains("help:"));
+        assert!(
+            format!("{any_error:#}").ends_with("\nhelp: retry later\nhelp: check the inner thing")
+        );
+
+        let json = serde_json::to_value(&any_error).unwrap();
+        assert_eq!(json["context"]["help"], "retry later");
+    }
+
+    #[test]
+    fn test_from_display_and_debug() {
+        struct Status(u16);
+
+        impl Display for Status {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "status {}", self.0)
+            }
+        }
+
+        #[derive(Debug)]
+        #[allow(dead_code)]
+        enum DriverError {
+            Busy { retries: u8 },
+        }
+
+        let error = AnyError::from_display(Status(503));
+        assert!(error.r#type.ends_with(".Status"));
+        assert_eq!(error.message(), "status 503");
+
+        let error = AnyError::from_debug(DriverError::Busy { retries: 3 });
+        assert!(error.r#type.ends_with(".DriverError"));
+        assert_eq!(error.message(), "Busy { retries: 3 }");
+    }
+
+    #[test]
+    fn test_extensions() {
+        let inner = AnyError::msg("bad id").with_extension("key", "user_id");
+        let any_error = AnyError::msg("rejected")
+            .with_extension("status", 400)
+            .with_inner(inner);
+
+        assert_eq!(any_error.extension("status"), Some(&serde_json::json!(400)));
+        assert_eq!(
+            any_error.extension("key"),
+            Some(&serde_json::json!("user_id"))
+        );
+        assert_eq!(any_error.extension("missing"), None);
+        assert_eq!(any_error.context.extensions().len(), 1);
+
+        let json = serde_json::to_value(&any_error).unwrap();
+        assert_eq!(json["context"]["extensions"]["status"], 400);
+    }
+
+    #[test]
+    fn test_data() {
+        let mut error = AnyError::msg("query failed").with_data("query", "SELECT 1");
+        error.wrap_with("loading user");
+        let error = error
+            .with_data("requestId", "req-7")
+            .with_data("userId", 42);
+
+        assert_eq!(error.data("userId"), Some(&serde_json::json!(42)));
+        assert_eq!(error.data("query"), Some(&serde_json::json!("SELECT 1")));
+        assert_eq!(error.data("missing"), None);
+        assert_eq!(error.context.data().len(), 2);
+        assert!(error.context.extensions().is_empty());
+
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["context"]["data"]["requestId"], "req-7");
+        let decoded: AnyError = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded.data("query"), Some(&serde_json::json!("SELECT 1")));
+    }
+
+    #[test]
+    fn test_value_round_trip() {
+        let error = AnyError::msg("card declined")
+            .with_inner(AnyError::msg("insufficient funds"))
+            .with_extension("amount", 1200);
+
+        let mut value = error.to_value();
+        assert_eq!(value["context"], error.context_value());
+        assert_eq!(error.context_value()["extensions"]["amount"], 1200);
+
+        value["context"]["extensions"]["amount"] = serde_json::json!("[redacted]");
+        let patched = AnyError::from_value(value).unwrap();
+        assert_eq!(
+            patched.extension("amount"),
+            Some(&serde_json::json!("[redacted]"))
+        );
+        assert_eq!(patched.inner().unwrap().message(), "insufficient funds");
+
+        assert!(AnyError::from_value(serde_json::json!({ "message": 1 })).is_err());
+    }
+
     #[test]
     fn test_valuable_trait() {
         let simple_error = SimpleError {
@@ -0,0 +1,79 @@
+//! Capture of errors that aren't `Send`, such as ones holding an `Rc` or a `RefCell` borrow.
+//! Everything is copied out on the current thread, so the resulting [`AnyError`] can be sent
+//! and stored like any other.
+
+use std::error::Error;
+
+use crate::{AnyError, type_name::standardized_type_name_of};
+
+/// The `{:#?}` text of an error captured with [`AnyError::capture_local`].
+pub const DEBUG_EXTENSION: &str = "debug";
+
+impl AnyError {
+    /// Captures `error` by reference: its type, message, `Debug` text and the whole source
+    /// chain. The error can be dropped or kept on its thread afterwards.
+    #[track_caller]
+    pub fn capture_local<E: Error + ?Sized>(error: &E) -> Self {
+        let mut captured = AnyError::capture(standardized_type_name_of(error), error)
+            .with_extension(DEBUG_EXTENSION, format!("{error:#?}"));
+        captured.finish_conversion();
+        captured
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, fmt, rc::Rc};
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct RenderError {
+        node: Rc<RefCell<String>>,
+        source: std::fmt::Error,
+    }
+
+    impl fmt::Display for RenderError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "failed to render {}", self.node.borrow())
+        }
+    }
+
+    impl Error for RenderError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.source)
+        }
+    }
+
+    fn assert_send<T: Send>(value: T) -> T {
+        value
+    }
+
+    #[test]
+    fn test_capture_local() {
+        let error = RenderError {
+            node: Rc::new(RefCell::new("header".to_string())),
+            source: std::fmt::Error,
+        };
+
+        let captured = assert_send(AnyError::capture_local(&error));
+        drop(error);
+
+        assert!(captured.r#type.ends_with(".RenderError"));
+        assert_eq!(
+            captured.chain_messages(),
+            [
+                "failed to render header",
+                "an error occurred when formatting an argument"
+            ]
+        );
+        assert!(
+            captured
+                .extension(DEBUG_EXTENSION)
+                .unwrap()
+                .as_str()
+                .unwrap()
+                .contains("\"header\"")
+        );
+    }
+}
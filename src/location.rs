@@ -0,0 +1,150 @@
+use std::{cell::Cell, fmt::Display, panic::Location};
+
+use serde::{Deserialize, Serialize};
+
+use crate::AnyError;
+
+/// Where in the source an error was created or wrapped, recorded through `#[track_caller]`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, valuable::Valuable)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceLocation {
+    pub file: String,
+    pub line: u32,
+    pub column: u32,
+}
+
+impl SourceLocation {
+    #[track_caller]
+    pub fn caller() -> Self {
+        Location::caller().into()
+    }
+}
+
+impl From<&Location<'_>> for SourceLocation {
+    fn from(location: &Location<'_>) -> Self {
+        Self {
+            file: location.file().to_string(),
+            line: location.line(),
+            column: location.column(),
+        }
+    }
+}
+
+impl Display for SourceLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+thread_local! {
+    /// Where layers created on this thread are located instead of their own caller, set by
+    /// [`located_at`].
+    static CALLER: Cell<Option<&'static Location<'static>>> = const { Cell::new(None) };
+}
+
+/// Runs `f` with the layers it creates located at `caller`, for helpers that convert errors
+/// in closures or futures, which `#[track_caller]` doesn't reach.
+pub(crate) fn located_at<R>(caller: &'static Location<'static>, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<&'static Location<'static>>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            CALLER.set(self.0);
+        }
+    }
+
+    let _restore = Restore(CALLER.replace(Some(caller)));
+    f()
+}
+
+/// The location set by [`located_at`], if any.
+pub(crate) fn located_caller() -> Option<&'static Location<'static>> {
+    CALLER.get()
+}
+
+impl AnyError {
+    pub fn with_location(mut self, location: SourceLocation) -> Self {
+        self.context.details.location = Some(location);
+        self
+    }
+
+    /// The location of the outermost layer that has one.
+    pub fn location(&self) -> Option<&SourceLocation> {
+        let mut node = Some(self);
+        while let Some(error) = node {
+            if let Some(location) = error.context.location() {
+                return Some(location);
+            }
+            node = error.inner();
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::ParseIntError;
+
+    use super::*;
+    use crate::{IntoAnyError, compat::anyhow::Context, option::OptionExt};
+
+    #[test]
+    fn test_msg() {
+        let line = line!() + 1;
+        let error = AnyError::msg("boom");
+
+        let location = error.location().unwrap();
+        assert_eq!(location.file, file!());
+        assert_eq!(location.line, line);
+        assert_eq!(location.column, 21);
+    }
+
+    #[test]
+    fn test_question_mark() {
+        fn parse() -> Result<u8, AnyError> {
+            Ok("x".parse::<u8>()?)
+        }
+
+        let error = parse().unwrap_err();
+        assert_eq!(error.location().unwrap().file, file!());
+        assert_eq!(error.location().unwrap().line, line!() - 5);
+    }
+
+    #[test]
+    fn test_wrap_records_each_layer() {
+        let mut error = "x".parse::<u8>().unwrap_err().into_any_error();
+        let inner_line = line!() - 1;
+        error.wrap_with("reading port");
+        let outer_line = line!() - 1;
+
+        assert_eq!(error.location().unwrap().line, outer_line);
+        assert_eq!(error.inner().unwrap().location().unwrap().line, inner_line);
+    }
+
+    #[test]
+    fn test_extension_methods() {
+        let result: Result<u8, ParseIntError> = "x".parse();
+        let error = result.context("reading port").unwrap_err();
+        assert_eq!(error.location().unwrap().line, line!() - 1);
+        assert_eq!(error.location().unwrap().file, file!());
+
+        let error = None::<u8>.ok_or_any("missing port").unwrap_err();
+        assert_eq!(error.location().unwrap().line, line!() - 1);
+    }
+
+    #[test]
+    fn test_serialize() {
+        let error = AnyError::msg("boom").with_location(SourceLocation {
+            file: "src/main.rs".to_string(),
+            line: 3,
+            column: 5,
+        });
+
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(
+            json["context"]["location"],
+            serde_json::json!({ "file": "src/main.rs", "line": 3, "column": 5 })
+        );
+        assert_eq!(error.location().unwrap().to_string(), "src/main.rs:3:5");
+    }
+}
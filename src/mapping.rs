@@ -0,0 +1,281 @@
+use std::{
+    collections::BTreeMap,
+    sync::{LazyLock, RwLock},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{AnyError, category::Category};
+
+/// The canonical gRPC status codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[repr(i32)]
+pub enum GrpcCode {
+    Ok = 0,
+    Cancelled = 1,
+    Unknown = 2,
+    InvalidArgument = 3,
+    DeadlineExceeded = 4,
+    NotFound = 5,
+    AlreadyExists = 6,
+    PermissionDenied = 7,
+    ResourceExhausted = 8,
+    FailedPrecondition = 9,
+    Aborted = 10,
+    OutOfRange = 11,
+    Unimplemented = 12,
+    Internal = 13,
+    Unavailable = 14,
+    DataLoss = 15,
+    Unauthenticated = 16,
+}
+
+/// How one error tag is reported over each protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtocolCodes {
+    pub exit_code: u8,
+    pub http_status: u16,
+    pub grpc_code: GrpcCode,
+}
+
+impl Default for ProtocolCodes {
+    fn default() -> Self {
+        Self {
+            exit_code: 1,
+            http_status: 500,
+            grpc_code: GrpcCode::Unknown,
+        }
+    }
+}
+
+impl ProtocolCodes {
+    pub fn new(exit_code: u8, http_status: u16, grpc_code: GrpcCode) -> Self {
+        Self {
+            exit_code,
+            http_status,
+            grpc_code,
+        }
+    }
+}
+
+/// Resolves the protocol codes for an error. Integrations (process exit, HTTP responses, gRPC
+/// statuses) all go through this trait so one declaration per tag controls every protocol.
+pub trait CodeMapping {
+    /// The codes declared for a single `$type` tag.
+    fn codes_for(&self, tag: &str) -> Option<ProtocolCodes>;
+
+    /// The codes of the outermost layer of `error` with a declaration.
+    fn declared(&self, error: &AnyError) -> Option<ProtocolCodes> {
+        let mut node = Some(error);
+        while let Some(error) = node {
+            if let Some(codes) = self.codes_for(&error.r#type) {
+                return Some(codes);
+            }
+            node = error.inner();
+        }
+        None
+    }
+
+    /// The codes of the outermost layer of `error` with a declaration, or the defaults.
+    fn resolve(&self, error: &AnyError) -> ProtocolCodes {
+        self.declared(error).unwrap_or_default()
+    }
+
+    fn exit_code(&self, error: &AnyError) -> u8 {
+        self.resolve(error).exit_code
+    }
+
+    fn http_status(&self, error: &AnyError) -> u16 {
+        self.resolve(error).http_status
+    }
+
+    fn grpc_code(&self, error: &AnyError) -> GrpcCode {
+        self.resolve(error).grpc_code
+    }
+}
+
+/// A table-driven [`CodeMapping`] keyed by `$type` tag.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeTable {
+    codes: BTreeMap<String, ProtocolCodes>,
+}
+
+impl CodeTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declares the codes for `tag`, returning the previous declaration.
+    pub fn insert(
+        &mut self,
+        tag: impl Into<String>,
+        codes: ProtocolCodes,
+    ) -> Option<ProtocolCodes> {
+        self.codes.insert(tag.into(), codes)
+    }
+
+    pub fn with(mut self, tag: impl Into<String>, codes: ProtocolCodes) -> Self {
+        self.insert(tag, codes);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.codes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.codes.is_empty()
+    }
+}
+
+impl CodeMapping for CodeTable {
+    fn codes_for(&self, tag: &str) -> Option<ProtocolCodes> {
+        self.codes.get(tag).copied()
+    }
+}
+
+/// The process-wide table, used by [`AnyError::exit_code`], [`AnyError::http_status`] and
+/// [`AnyError::grpc_code`].
+pub struct GlobalCodes;
+
+static CODES: LazyLock<RwLock<CodeTable>> = LazyLock::new(Default::default);
+
+impl CodeMapping for GlobalCodes {
+    fn codes_for(&self, tag: &str) -> Option<ProtocolCodes> {
+        CODES
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .codes_for(tag)
+    }
+}
+
+pub fn register(tag: impl Into<String>, codes: ProtocolCodes) -> Option<ProtocolCodes> {
+    CODES
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(tag, codes)
+}
+
+pub fn snapshot() -> CodeTable {
+    CODES.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Extension through which an error can pin its own process exit code, taking precedence over
+/// the global table in [`AnyError::exit_code`].
+pub const EXIT_CODE_EXTENSION: &str = "exitCode";
+/// Extension through which an error can pin the HTTP status it is answered with, taking
+/// precedence over the global table in [`AnyError::http_status`].
+pub const HTTP_STATUS_EXTENSION: &str = "httpStatus";
+
+impl AnyError {
+    pub fn exit_code(&self) -> u8 {
+        self.extension(EXIT_CODE_EXTENSION)
+            .and_then(serde_json::Value::as_u64)
+            .and_then(|code| u8::try_from(code).ok())
+            .unwrap_or_else(|| GlobalCodes.exit_code(self))
+    }
+
+    /// The [`HTTP_STATUS_EXTENSION`], else the status declared in the global table, else the
+    /// status of the error's [category](AnyError::category), else 500.
+    pub fn http_status(&self) -> u16 {
+        self.extension(HTTP_STATUS_EXTENSION)
+            .and_then(serde_json::Value::as_u64)
+            .and_then(|status| u16::try_from(status).ok())
+            .or_else(|| GlobalCodes.declared(self).map(|codes| codes.http_status))
+            .or_else(|| self.category().map(Category::http_status))
+            .unwrap_or(ProtocolCodes::default().http_status)
+    }
+
+    pub fn grpc_code(&self) -> GrpcCode {
+        GlobalCodes.grpc_code(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> CodeTable {
+        CodeTable::new()
+            .with(
+                "app.user.not_found",
+                ProtocolCodes::new(3, 404, GrpcCode::NotFound),
+            )
+            .with(
+                "app.db.unavailable",
+                ProtocolCodes::new(69, 503, GrpcCode::Unavailable),
+            )
+    }
+
+    #[test]
+    fn test_resolves_outermost_declared_layer() {
+        let root = AnyError::new("app.db.unavailable".to_string(), "down".to_string(), None);
+        let mut error = AnyError::new(
+            "app.user.not_found".to_string(),
+            "no user".to_string(),
+            Some(root),
+        );
+        error.wrap_with("while loading profile");
+
+        let table = table();
+        assert_eq!(table.http_status(&error), 404);
+        assert_eq!(table.exit_code(&error), 3);
+        assert_eq!(table.grpc_code(&error), GrpcCode::NotFound);
+    }
+
+    #[test]
+    fn test_defaults_for_undeclared() {
+        let error = AnyError::msg("boom");
+
+        assert_eq!(table().resolve(&error), ProtocolCodes::default());
+        assert_eq!(table().http_status(&error), 500);
+    }
+
+    #[test]
+    fn test_global_codes() {
+        register(
+            "app.mapping.test_only",
+            ProtocolCodes::new(4, 409, GrpcCode::AlreadyExists),
+        );
+        let error = AnyError::msg("exists").with_type("app.mapping.test_only");
+
+        assert_eq!(error.http_status(), 409);
+        assert_eq!(error.exit_code(), 4);
+        assert_eq!(error.grpc_code(), GrpcCode::AlreadyExists);
+        assert!(snapshot().codes_for("app.mapping.test_only").is_some());
+
+        let pinned = error.with_extension(EXIT_CODE_EXTENSION, 0);
+        assert_eq!(pinned.exit_code(), 0);
+        assert_eq!(pinned.http_status(), 409);
+
+        let pinned = pinned.with_extension(HTTP_STATUS_EXTENSION, 422);
+        assert_eq!(pinned.http_status(), 422);
+    }
+
+    #[test]
+    fn test_http_status_from_category() {
+        let mut error = AnyError::msg("bad email").with_category(Category::Validation);
+        error.wrap_with("creating user");
+        assert_eq!(error.http_status(), 400);
+
+        register(
+            "app.mapping.category_test_only",
+            ProtocolCodes::new(4, 409, GrpcCode::AlreadyExists),
+        );
+        let declared = error.wrap_in("app.mapping.category_test_only", "exists");
+        assert_eq!(declared.http_status(), 409);
+        assert_eq!(AnyError::msg("boom").http_status(), 500);
+    }
+
+    #[test]
+    fn test_grpc_code_values() {
+        assert_eq!(GrpcCode::Unauthenticated as i32, 16);
+        assert_eq!(
+            serde_json::to_value(GrpcCode::InvalidArgument).unwrap(),
+            "INVALID_ARGUMENT"
+        );
+    }
+}
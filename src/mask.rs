@@ -0,0 +1,155 @@
+//! Hiding internal error types from external clients. A layer whose `$type` the policy
+//! doesn't expose is replaced, with everything below it, by a [`MASKED_TYPE`] placeholder,
+//! so e.g. a `sqlx::postgres::PgDatabaseError` and its message never reach an API consumer.
+//! The error itself keeps the full detail for internal logging.
+
+use std::{fmt, sync::Arc};
+
+use serde::{Serialize, Serializer};
+
+use crate::{AnyError, config};
+
+pub const MASKED_TYPE: &str = "Internal";
+pub const MASKED_MESSAGE: &str = "internal error";
+
+/// Which `$type`s are exposed. In lists, an entry ending in `.` matches every type under it,
+/// e.g. `app.` for `app.UserNotFound`.
+#[derive(Clone)]
+pub enum MaskPolicy {
+    /// Only the listed types are exposed.
+    Allow(Vec<String>),
+    /// Every type except the listed ones is exposed.
+    Deny(Vec<String>),
+    /// Types for which the callback returns `true` are exposed.
+    Custom(Arc<dyn Fn(&str) -> bool + Send + Sync>),
+}
+
+fn matches(entries: &[String], r#type: &str) -> bool {
+    entries.iter().any(|entry| {
+        entry == r#type || (entry.ends_with('.') && r#type.starts_with(entry.as_str()))
+    })
+}
+
+impl MaskPolicy {
+    pub fn allow<S: Into<String>>(types: impl IntoIterator<Item = S>) -> Self {
+        Self::Allow(types.into_iter().map(Into::into).collect())
+    }
+
+    pub fn deny<S: Into<String>>(types: impl IntoIterator<Item = S>) -> Self {
+        Self::Deny(types.into_iter().map(Into::into).collect())
+    }
+
+    pub fn custom(is_exposed: impl Fn(&str) -> bool + Send + Sync + 'static) -> Self {
+        Self::Custom(Arc::new(is_exposed))
+    }
+
+    pub fn is_exposed(&self, r#type: &str) -> bool {
+        match self {
+            Self::Allow(types) => matches(types, r#type),
+            Self::Deny(types) => !matches(types, r#type),
+            Self::Custom(is_exposed) => is_exposed(r#type),
+        }
+    }
+}
+
+impl fmt::Debug for MaskPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Allow(types) => f.debug_tuple("Allow").field(types).finish(),
+            Self::Deny(types) => f.debug_tuple("Deny").field(types).finish(),
+            Self::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+impl AnyError {
+    /// A copy of this error with every layer `policy` doesn't expose masked, including
+    /// [related](AnyError::with_related) errors.
+    pub fn masked(&self, policy: &MaskPolicy) -> AnyError {
+        let mut masked = self.clone();
+        let mut stack = vec![&mut masked];
+        while let Some(error) = stack.pop() {
+            if !policy.is_exposed(&error.r#type) {
+                *error = AnyError::from_parts(MASKED_TYPE, MASKED_MESSAGE, None);
+                continue;
+            }
+            let context = error.make_context_mut();
+            let details = &mut *context.details;
+            stack.extend(
+                context
+                    .inner_error
+                    .iter_mut()
+                    .chain(&mut details.inner_errors)
+                    .map(Arc::make_mut)
+                    .chain(&mut details.related),
+            );
+        }
+        masked
+    }
+}
+
+/// Serializes an `AnyError` field masked by the configured
+/// [`mask_policy`](crate::config::Config::mask_policy), unchanged if there is none.
+pub fn serialize<S: Serializer>(error: &AnyError, serializer: S) -> Result<S::Ok, S::Error> {
+    match &config::current().mask_policy {
+        Some(policy) => error.masked(policy).serialize(serializer),
+        None => error.serialize(serializer),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query_failed() -> AnyError {
+        AnyError::msg("duplicate key value violates unique constraint \"users_email_key\"")
+            .with_type("sqlx.postgres.PgDatabaseError")
+            .wrap_in("app.UserExists", "a user with this email already exists")
+    }
+
+    #[test]
+    fn test_allow() {
+        let policy = MaskPolicy::allow(["app.", crate::CONTEXT_TYPE]);
+        let masked = query_failed().wrap_msg("creating account").masked(&policy);
+
+        assert_eq!(
+            masked.chain_types(),
+            [crate::CONTEXT_TYPE, "app.UserExists", MASKED_TYPE]
+        );
+        assert_eq!(masked.root_cause().message(), MASKED_MESSAGE);
+        assert!(masked.root_cause().location().is_none());
+
+        let masked = query_failed()
+            .with_type("application.Error")
+            .masked(&policy);
+        assert_eq!(masked.chain_types(), [MASKED_TYPE]);
+    }
+
+    #[test]
+    fn test_deny_and_custom() {
+        let error = query_failed();
+        let masked = error.masked(&MaskPolicy::deny(["sqlx."]));
+        assert_eq!(masked.chain_types(), ["app.UserExists", MASKED_TYPE]);
+        assert_eq!(error.depth(), 2);
+        assert_eq!(error.root_cause().r#type, "sqlx.postgres.PgDatabaseError");
+
+        let policy = MaskPolicy::custom(|r#type| !r#type.contains("postgres"));
+        assert_eq!(error.masked(&policy), masked);
+    }
+
+    #[test]
+    fn test_related() {
+        let rollback = AnyError::msg("relation \"orders\" does not exist")
+            .with_type("sqlx.postgres.PgDatabaseError")
+            .wrap_in("app.RollbackFailed", "rollback failed");
+        let error = query_failed().with_related(rollback);
+
+        let masked = error.masked(&MaskPolicy::allow(["app."]));
+        let related = masked.all_related();
+        assert_eq!(
+            related[0].chain_types(),
+            ["app.RollbackFailed", MASKED_TYPE]
+        );
+        assert!(!serde_json::to_string(&masked).unwrap().contains("orders"));
+    }
+}
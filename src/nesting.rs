@@ -0,0 +1,135 @@
+//! `Debug` and `Serialize` for chains too deep to recurse through. `Debug` lists the layers
+//! flat, as [`AnyError::to_flat`] does, and serialization stops following causes past
+//! [`Config::max_source_depth`](crate::config::Config::max_source_depth), marking the last
+//! layer written with [`TRUNCATED_EXTENSION`].
+
+use std::{cell::Cell, fmt};
+
+use serde::{Serialize, Serializer};
+
+use crate::{AnyError, AnyErrorContext, ContextDetails, TRUNCATED_EXTENSION, config};
+
+impl fmt::Debug for AnyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AnyError").field(&self.to_flat()).finish()
+    }
+}
+
+thread_local! {
+    /// How many contexts are being serialized on this thread, and the depth past which their
+    /// causes are cut, read from the config by the outermost one.
+    static NESTING: Cell<(usize, usize)> = const { Cell::new((0, 0)) };
+}
+
+/// Marks a context being serialized with its causes until dropped.
+struct Nested;
+
+impl Nested {
+    /// `None` if the contexts already open reach the max depth.
+    fn enter() -> Option<Self> {
+        NESTING.with(|nesting| {
+            let (depth, mut max) = nesting.get();
+            if depth == 0 {
+                max = config::current().max_source_depth();
+            }
+            if depth >= max {
+                return None;
+            }
+            nesting.set((depth + 1, max));
+            Some(Nested)
+        })
+    }
+}
+
+impl Drop for Nested {
+    fn drop(&mut self) {
+        NESTING.with(|nesting| {
+            let (depth, max) = nesting.get();
+            nesting.set((depth - 1, max));
+        });
+    }
+}
+
+/// The serialized shape of [`AnyErrorContext`], matching its `Deserialize`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ContextRepr<'a> {
+    message: &'a str,
+    inner_error: Option<&'a AnyError>,
+    #[serde(flatten)]
+    details: &'a ContextDetails,
+}
+
+impl Serialize for AnyErrorContext {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let has_causes = self.inner_error.is_some() || !self.details.inner_errors.is_empty();
+        let nested = has_causes.then(Nested::enter);
+        if let Some(None) = nested {
+            let mut details = ContextDetails::clone(&self.details);
+            details.inner_errors.clear();
+            details
+                .extensions
+                .insert(TRUNCATED_EXTENSION.to_string(), "depth".into());
+            return ContextRepr {
+                message: &self.message,
+                inner_error: None,
+                details: &details,
+            }
+            .serialize(serializer);
+        }
+
+        ContextRepr {
+            message: &self.message,
+            inner_error: self.inner_error.as_deref(),
+            details: &self.details,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DEFAULT_MAX_SOURCE_DEPTH;
+
+    #[test]
+    fn test_deep_chain_on_small_stack() {
+        let mut error = AnyError::from_parts("Root", "root", None);
+        for index in 0..10_000 {
+            error = AnyError::from_parts("Retry", format!("attempt {index}"), Some(error));
+        }
+
+        // The default stack size of tokio's worker threads.
+        std::thread::Builder::new()
+            .stack_size(2 * 1024 * 1024)
+            .spawn(move || {
+                let debug = format!("{error:?}");
+                assert!(debug.starts_with("AnyError([FlatFrame { type: \"Retry\""));
+                assert!(debug.contains("message: \"root\""));
+
+                let json = serde_json::to_string(&error).unwrap();
+                assert_eq!(
+                    json.matches("\"$type\"").count(),
+                    DEFAULT_MAX_SOURCE_DEPTH + 1
+                );
+                assert_eq!(json.matches("\"truncated\":\"depth\"").count(), 1);
+                assert!(!json.contains("attempt 9870"));
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_shallow_chain_unchanged() {
+        let error = AnyError::from_parts("Outer", "outer", Some(AnyError::msg("inner")));
+        let json = serde_json::to_value(&error).unwrap();
+
+        assert_eq!(json["context"]["innerError"]["context"]["message"], "inner");
+        assert!(json["context"].get("extensions").is_none());
+        assert_eq!(
+            serde_json::to_value(AnyError::from_value(json.clone()).unwrap()).unwrap(),
+            json
+        );
+    }
+}
@@ -0,0 +1,72 @@
+//! Converting `None` into an error, since it doesn't carry one of its own.
+
+use std::fmt::Display;
+
+use crate::{AnyError, AnyResult};
+
+/// `$type` of errors made from `None` unless another is given.
+pub const MISSING_TYPE: &str = "Missing";
+
+pub trait OptionExt<T> {
+    /// `Err` with `message` if `None`, e.g. `users.get(id).ok_or_any("user not found")?`.
+    #[track_caller]
+    fn ok_or_any(self, message: impl Display) -> AnyResult<T>;
+
+    /// Like [`OptionExt::ok_or_any`], only building the message if `None`.
+    #[track_caller]
+    fn ok_or_any_with<M: Display>(self, message: impl FnOnce() -> M) -> AnyResult<T>;
+
+    /// Like [`OptionExt::ok_or_any`], with `r#type` instead of [`MISSING_TYPE`].
+    #[track_caller]
+    fn ok_or_any_as(self, r#type: impl Into<String>, message: impl Display) -> AnyResult<T>;
+}
+
+impl<T> OptionExt<T> for Option<T> {
+    fn ok_or_any(self, message: impl Display) -> AnyResult<T> {
+        self.ok_or_any_as(MISSING_TYPE, message)
+    }
+
+    fn ok_or_any_with<M: Display>(self, message: impl FnOnce() -> M) -> AnyResult<T> {
+        match self {
+            Some(value) => Ok(value),
+            None => self.ok_or_any_as(MISSING_TYPE, message()),
+        }
+    }
+
+    fn ok_or_any_as(self, r#type: impl Into<String>, message: impl Display) -> AnyResult<T> {
+        match self {
+            Some(value) => Ok(value),
+            None => Err(AnyError::msg_as(r#type, message)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ok_or_any() {
+        assert_eq!(Some(1).ok_or_any("missing").unwrap(), 1);
+
+        let error = None::<u8>.ok_or_any("user not found").unwrap_err();
+        assert_eq!(error.r#type, MISSING_TYPE);
+        assert_eq!(error.message(), "user not found");
+
+        let error = None::<u8>
+            .ok_or_any_with(|| format!("user {} not found", 42))
+            .unwrap_err();
+        assert_eq!(error.message(), "user 42 not found");
+
+        let error = None::<u8>
+            .ok_or_any_as("app.user.not_found", "user not found")
+            .unwrap_err();
+        assert_eq!(error.r#type, "app.user.not_found");
+    }
+
+    #[test]
+    fn test_message_built_lazily() {
+        let result = Some(1).ok_or_any_with(|| -> String { unreachable!() });
+        assert!(result.is_ok());
+    }
+}
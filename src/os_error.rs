@@ -0,0 +1,375 @@
+//! Structured OS error codes. Converted `io::Error`s carrying a raw OS error get the code and
+//! its symbolic name as extensions, since the number in the message alone is hard to triage.
+//!
+//! On Unix the errno is recorded with its name and the English `strerror` text, regardless of
+//! the process locale. On Windows the code is recorded both as the Win32 code returned by
+//! `GetLastError` and as the equivalent HRESULT.
+
+use std::{error::Error, io};
+
+use crate::AnyError;
+
+pub const ERRNO_EXTENSION: &str = "errno";
+/// E.g. `ECONNRESET`.
+pub const ERRNO_NAME_EXTENSION: &str = "errnoName";
+/// E.g. `Connection reset by peer`.
+pub const ERRNO_TEXT_EXTENSION: &str = "errnoText";
+
+pub const WIN32_CODE_EXTENSION: &str = "win32Code";
+/// The HRESULT as a hex string, e.g. `0x80070005`.
+pub const HRESULT_EXTENSION: &str = "hresult";
+/// The symbolic name of the code when known, e.g. `ERROR_ACCESS_DENIED` or `E_NOTIMPL`.
+pub const WIN32_NAME_EXTENSION: &str = "win32Name";
+
+const FACILITY_WIN32: u32 = 7;
+
+/// Symbolic names of common Win32 and Winsock error codes.
+const WIN32_NAMES: &[(u32, &str)] = &[
+    (0, "ERROR_SUCCESS"),
+    (1, "ERROR_INVALID_FUNCTION"),
+    (2, "ERROR_FILE_NOT_FOUND"),
+    (3, "ERROR_PATH_NOT_FOUND"),
+    (4, "ERROR_TOO_MANY_OPEN_FILES"),
+    (5, "ERROR_ACCESS_DENIED"),
+    (6, "ERROR_INVALID_HANDLE"),
+    (8, "ERROR_NOT_ENOUGH_MEMORY"),
+    (14, "ERROR_OUTOFMEMORY"),
+    (15, "ERROR_INVALID_DRIVE"),
+    (19, "ERROR_WRITE_PROTECT"),
+    (21, "ERROR_NOT_READY"),
+    (32, "ERROR_SHARING_VIOLATION"),
+    (33, "ERROR_LOCK_VIOLATION"),
+    (38, "ERROR_HANDLE_EOF"),
+    (39, "ERROR_HANDLE_DISK_FULL"),
+    (50, "ERROR_NOT_SUPPORTED"),
+    (53, "ERROR_BAD_NETPATH"),
+    (64, "ERROR_NETNAME_DELETED"),
+    (67, "ERROR_BAD_NET_NAME"),
+    (80, "ERROR_FILE_EXISTS"),
+    (87, "ERROR_INVALID_PARAMETER"),
+    (109, "ERROR_BROKEN_PIPE"),
+    (112, "ERROR_DISK_FULL"),
+    (122, "ERROR_INSUFFICIENT_BUFFER"),
+    (123, "ERROR_INVALID_NAME"),
+    (145, "ERROR_DIR_NOT_EMPTY"),
+    (183, "ERROR_ALREADY_EXISTS"),
+    (206, "ERROR_FILENAME_EXCED_RANGE"),
+    (231, "ERROR_PIPE_BUSY"),
+    (232, "ERROR_NO_DATA"),
+    (233, "ERROR_PIPE_NOT_CONNECTED"),
+    (258, "WAIT_TIMEOUT"),
+    (267, "ERROR_DIRECTORY"),
+    (995, "ERROR_OPERATION_ABORTED"),
+    (997, "ERROR_IO_PENDING"),
+    (1060, "ERROR_SERVICE_DOES_NOT_EXIST"),
+    (1223, "ERROR_CANCELLED"),
+    (1326, "ERROR_LOGON_FAILURE"),
+    (1460, "ERROR_TIMEOUT"),
+    (10004, "WSAEINTR"),
+    (10013, "WSAEACCES"),
+    (10014, "WSAEFAULT"),
+    (10022, "WSAEINVAL"),
+    (10024, "WSAEMFILE"),
+    (10035, "WSAEWOULDBLOCK"),
+    (10036, "WSAEINPROGRESS"),
+    (10048, "WSAEADDRINUSE"),
+    (10049, "WSAEADDRNOTAVAIL"),
+    (10050, "WSAENETDOWN"),
+    (10051, "WSAENETUNREACH"),
+    (10053, "WSAECONNABORTED"),
+    (10054, "WSAECONNRESET"),
+    (10055, "WSAENOBUFS"),
+    (10056, "WSAEISCONN"),
+    (10057, "WSAENOTCONN"),
+    (10060, "WSAETIMEDOUT"),
+    (10061, "WSAECONNREFUSED"),
+    (10065, "WSAEHOSTUNREACH"),
+    (11001, "WSAHOST_NOT_FOUND"),
+];
+
+/// HRESULTs that have their own name rather than wrapping a Win32 code.
+const HRESULT_NAMES: &[(u32, &str)] = &[
+    (0x8000_4001, "E_NOTIMPL"),
+    (0x8000_4002, "E_NOINTERFACE"),
+    (0x8000_4003, "E_POINTER"),
+    (0x8000_4004, "E_ABORT"),
+    (0x8000_4005, "E_FAIL"),
+    (0x8000_FFFF, "E_UNEXPECTED"),
+    (0x8007_0005, "E_ACCESSDENIED"),
+    (0x8007_0006, "E_HANDLE"),
+    (0x8007_000E, "E_OUTOFMEMORY"),
+    (0x8007_0057, "E_INVALIDARG"),
+];
+
+/// The symbolic name and English description of `errno` on this platform.
+#[cfg(unix)]
+pub fn errno_info(errno: i32) -> Option<(&'static str, &'static str)> {
+    Some(match errno {
+        libc::EPERM => ("EPERM", "Operation not permitted"),
+        libc::ENOENT => ("ENOENT", "No such file or directory"),
+        libc::ESRCH => ("ESRCH", "No such process"),
+        libc::EINTR => ("EINTR", "Interrupted system call"),
+        libc::EIO => ("EIO", "Input/output error"),
+        libc::ENXIO => ("ENXIO", "No such device or address"),
+        libc::E2BIG => ("E2BIG", "Argument list too long"),
+        libc::ENOEXEC => ("ENOEXEC", "Exec format error"),
+        libc::EBADF => ("EBADF", "Bad file descriptor"),
+        libc::ECHILD => ("ECHILD", "No child processes"),
+        libc::EAGAIN => ("EAGAIN", "Resource temporarily unavailable"),
+        libc::ENOMEM => ("ENOMEM", "Cannot allocate memory"),
+        libc::EACCES => ("EACCES", "Permission denied"),
+        libc::EFAULT => ("EFAULT", "Bad address"),
+        libc::EBUSY => ("EBUSY", "Device or resource busy"),
+        libc::EEXIST => ("EEXIST", "File exists"),
+        libc::EXDEV => ("EXDEV", "Invalid cross-device link"),
+        libc::ENODEV => ("ENODEV", "No such device"),
+        libc::ENOTDIR => ("ENOTDIR", "Not a directory"),
+        libc::EISDIR => ("EISDIR", "Is a directory"),
+        libc::EINVAL => ("EINVAL", "Invalid argument"),
+        libc::ENFILE => ("ENFILE", "Too many open files in system"),
+        libc::EMFILE => ("EMFILE", "Too many open files"),
+        libc::ENOTTY => ("ENOTTY", "Inappropriate ioctl for device"),
+        libc::ETXTBSY => ("ETXTBSY", "Text file busy"),
+        libc::EFBIG => ("EFBIG", "File too large"),
+        libc::ENOSPC => ("ENOSPC", "No space left on device"),
+        libc::ESPIPE => ("ESPIPE", "Illegal seek"),
+        libc::EROFS => ("EROFS", "Read-only file system"),
+        libc::EMLINK => ("EMLINK", "Too many links"),
+        libc::EPIPE => ("EPIPE", "Broken pipe"),
+        libc::EDOM => ("EDOM", "Numerical argument out of domain"),
+        libc::ERANGE => ("ERANGE", "Numerical result out of range"),
+        libc::EDEADLK => ("EDEADLK", "Resource deadlock avoided"),
+        libc::ENAMETOOLONG => ("ENAMETOOLONG", "File name too long"),
+        libc::ENOLCK => ("ENOLCK", "No locks available"),
+        libc::ENOSYS => ("ENOSYS", "Function not implemented"),
+        libc::ENOTEMPTY => ("ENOTEMPTY", "Directory not empty"),
+        libc::ELOOP => ("ELOOP", "Too many levels of symbolic links"),
+        libc::ENOTSOCK => ("ENOTSOCK", "Socket operation on non-socket"),
+        libc::EDESTADDRREQ => ("EDESTADDRREQ", "Destination address required"),
+        libc::EMSGSIZE => ("EMSGSIZE", "Message too long"),
+        libc::EPROTOTYPE => ("EPROTOTYPE", "Protocol wrong type for socket"),
+        libc::ENOPROTOOPT => ("ENOPROTOOPT", "Protocol not available"),
+        libc::EPROTONOSUPPORT => ("EPROTONOSUPPORT", "Protocol not supported"),
+        libc::EOPNOTSUPP => ("EOPNOTSUPP", "Operation not supported"),
+        libc::EAFNOSUPPORT => ("EAFNOSUPPORT", "Address family not supported by protocol"),
+        libc::EADDRINUSE => ("EADDRINUSE", "Address already in use"),
+        libc::EADDRNOTAVAIL => ("EADDRNOTAVAIL", "Cannot assign requested address"),
+        libc::ENETDOWN => ("ENETDOWN", "Network is down"),
+        libc::ENETUNREACH => ("ENETUNREACH", "Network is unreachable"),
+        libc::ENETRESET => ("ENETRESET", "Network dropped connection on reset"),
+        libc::ECONNABORTED => ("ECONNABORTED", "Software caused connection abort"),
+        libc::ECONNRESET => ("ECONNRESET", "Connection reset by peer"),
+        libc::ENOBUFS => ("ENOBUFS", "No buffer space available"),
+        libc::EISCONN => ("EISCONN", "Transport endpoint is already connected"),
+        libc::ENOTCONN => ("ENOTCONN", "Transport endpoint is not connected"),
+        libc::ETIMEDOUT => ("ETIMEDOUT", "Connection timed out"),
+        libc::ECONNREFUSED => ("ECONNREFUSED", "Connection refused"),
+        libc::EHOSTDOWN => ("EHOSTDOWN", "Host is down"),
+        libc::EHOSTUNREACH => ("EHOSTUNREACH", "No route to host"),
+        libc::EALREADY => ("EALREADY", "Operation already in progress"),
+        libc::EINPROGRESS => ("EINPROGRESS", "Operation now in progress"),
+        libc::ESTALE => ("ESTALE", "Stale file handle"),
+        libc::EDQUOT => ("EDQUOT", "Disk quota exceeded"),
+        libc::ECANCELED => ("ECANCELED", "Operation canceled"),
+        _ => return None,
+    })
+}
+
+pub fn win32_error_name(code: u32) -> Option<&'static str> {
+    lookup(WIN32_NAMES, code)
+}
+
+/// `HRESULT_FROM_WIN32`.
+pub fn hresult_from_win32(code: u32) -> u32 {
+    match code {
+        0 => 0,
+        code if code & 0x8000_0000 != 0 => code,
+        code => 0x8000_0000 | (FACILITY_WIN32 << 16) | (code & 0xFFFF),
+    }
+}
+
+/// The `E_*` name of `hresult`, or the name of the Win32 code it wraps.
+pub fn hresult_name(hresult: u32) -> Option<&'static str> {
+    lookup(HRESULT_NAMES, hresult).or_else(|| win32_error_name(win32_from_hresult(hresult)?))
+}
+
+fn win32_from_hresult(hresult: u32) -> Option<u32> {
+    (hresult >> 16 & 0x1FFF == FACILITY_WIN32).then_some(hresult & 0xFFFF)
+}
+
+fn lookup(table: &[(u32, &'static str)], code: u32) -> Option<&'static str> {
+    table
+        .iter()
+        .find_map(|(known, name)| (*known == code).then_some(*name))
+}
+
+impl AnyError {
+    /// Records an errno, e.g. from a raw syscall or `nix::Error`, with its name and text.
+    /// Done automatically for `io::Error`s on Unix.
+    #[cfg(unix)]
+    pub fn with_errno(mut self, errno: i32) -> Self {
+        record_errno(errno, &mut self);
+        self
+    }
+
+    /// Records a Win32 code from `GetLastError`, or an HRESULT when negative, with its name.
+    /// Done automatically for `io::Error`s on Windows.
+    pub fn with_win32_code(mut self, code: i32) -> Self {
+        record_win32(code, &mut self);
+        self
+    }
+}
+
+#[cfg(unix)]
+fn record_errno(errno: i32, error: &mut AnyError) {
+    let extensions = &mut error.context.details.extensions;
+    extensions.insert(ERRNO_EXTENSION.to_string(), errno.into());
+    if let Some((name, text)) = errno_info(errno) {
+        extensions.insert(ERRNO_NAME_EXTENSION.to_string(), name.into());
+        extensions.insert(ERRNO_TEXT_EXTENSION.to_string(), text.into());
+    }
+}
+
+fn record_win32(code: i32, error: &mut AnyError) {
+    let hresult = hresult_from_win32(code as u32);
+    let (win32, name) = match code {
+        0.. => (Some(code as u32), win32_error_name(code as u32)),
+        _ => (win32_from_hresult(hresult), hresult_name(hresult)),
+    };
+
+    let extensions = &mut error.context.details.extensions;
+    extensions.insert(
+        HRESULT_EXTENSION.to_string(),
+        format!("{hresult:#010X}").into(),
+    );
+    if let Some(win32) = win32 {
+        extensions.insert(WIN32_CODE_EXTENSION.to_string(), win32.into());
+    }
+    if let Some(name) = name {
+        extensions.insert(WIN32_NAME_EXTENSION.to_string(), name.into());
+    }
+}
+
+/// Records the OS error code of a converted source error.
+pub(crate) fn apply(source: &(dyn Error + 'static), error: &mut AnyError) {
+    if let Some(source) = source.downcast_ref::<io::Error>() {
+        record(source.raw_os_error(), error);
+    }
+}
+
+/// Records `io::Error::raw_os_error` on the layer converted from that error.
+pub(crate) fn record(raw_os_error: Option<i32>, error: &mut AnyError) {
+    let Some(code) = raw_os_error else {
+        return;
+    };
+    #[cfg(windows)]
+    record_win32(code, error);
+    #[cfg(unix)]
+    record_errno(code, error);
+    #[cfg(not(any(unix, windows)))]
+    let _ = (code, error);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hresult_from_win32() {
+        assert_eq!(hresult_from_win32(5), 0x8007_0005);
+        assert_eq!(hresult_from_win32(0), 0);
+        assert_eq!(hresult_from_win32(0x8000_4001), 0x8000_4001);
+    }
+
+    #[test]
+    fn test_names() {
+        assert_eq!(win32_error_name(5), Some("ERROR_ACCESS_DENIED"));
+        assert_eq!(win32_error_name(10054), Some("WSAECONNRESET"));
+        assert_eq!(hresult_name(0x8000_4001), Some("E_NOTIMPL"));
+        assert_eq!(hresult_name(0x8007_0020), Some("ERROR_SHARING_VIOLATION"));
+        assert_eq!(hresult_name(0x8876_0001), None);
+    }
+
+    #[test]
+    fn test_with_win32_code() {
+        let error = AnyError::msg("Access is denied. (os error 5)").with_win32_code(5);
+        assert_eq!(
+            error.extension(WIN32_CODE_EXTENSION),
+            Some(&serde_json::json!(5))
+        );
+        assert_eq!(
+            error.extension(HRESULT_EXTENSION),
+            Some(&serde_json::json!("0x80070005"))
+        );
+        assert_eq!(
+            error.extension(WIN32_NAME_EXTENSION),
+            Some(&serde_json::json!("ERROR_ACCESS_DENIED"))
+        );
+
+        let error = AnyError::msg("not implemented").with_win32_code(0x8000_4001_u32 as i32);
+        assert_eq!(error.extension(WIN32_CODE_EXTENSION), None);
+        assert_eq!(
+            error.extension(WIN32_NAME_EXTENSION),
+            Some(&serde_json::json!("E_NOTIMPL"))
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_errno() {
+        use crate::IntoAnyError;
+
+        assert_eq!(
+            errno_info(libc::ECONNRESET),
+            Some(("ECONNRESET", "Connection reset by peer"))
+        );
+        assert_eq!(errno_info(-1), None);
+
+        let error = io::Error::from_raw_os_error(libc::EACCES).into_any_error();
+        assert_eq!(
+            error.extension(ERRNO_EXTENSION),
+            Some(&serde_json::json!(libc::EACCES))
+        );
+        assert_eq!(
+            error.extension(ERRNO_NAME_EXTENSION),
+            Some(&serde_json::json!("EACCES"))
+        );
+        assert_eq!(
+            error.extension(ERRNO_TEXT_EXTENSION),
+            Some(&serde_json::json!("Permission denied"))
+        );
+
+        #[derive(Debug)]
+        struct OpenError(io::Error);
+
+        impl std::fmt::Display for OpenError {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "open failed")
+            }
+        }
+
+        impl Error for OpenError {
+            fn source(&self) -> Option<&(dyn Error + 'static)> {
+                Some(&self.0)
+            }
+        }
+
+        let error = AnyError::from(OpenError(io::Error::from_raw_os_error(libc::ENOENT)));
+        assert_eq!(
+            error.inner().unwrap().extension(ERRNO_NAME_EXTENSION),
+            Some(&serde_json::json!("ENOENT"))
+        );
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_io_error() {
+        use crate::IntoAnyError;
+
+        let error = io::Error::from_raw_os_error(2).into_any_error();
+        assert_eq!(
+            error.extension(WIN32_NAME_EXTENSION),
+            Some(&serde_json::json!("ERROR_FILE_NOT_FOUND"))
+        );
+    }
+}
@@ -0,0 +1,210 @@
+//! Panics as errors, and a friendly crash experience for CLI and desktop applications: instead
+//! of a raw panic message, users see a short note pointing at a report file they can send in.
+//!
+//! ```no_run
+//! liberror::panic::CrashReporter::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))
+//!     .with_support_url("https://github.com/acme/tool/issues")
+//!     .install();
+//! ```
+
+use std::{
+    any::Any,
+    fs,
+    io::{self, Write},
+    panic::{Location, PanicHookInfo},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::Serialize;
+
+use crate::{
+    AnyError,
+    backtrace::{Backtrace, BacktracePolicy},
+};
+
+/// `$type` of errors converted from panics.
+pub const PANIC_TYPE: &str = "Panic";
+/// `file:line:column` of the panic.
+pub const LOCATION_EXTENSION: &str = "location";
+
+impl AnyError {
+    /// Converts a panic seen by a panic hook, capturing a backtrace as configured.
+    pub fn from_panic(info: &PanicHookInfo<'_>) -> Self {
+        Self::from_panic_payload(info.payload(), info.location())
+    }
+
+    /// Converts the payload of a panic, e.g. the `Err` of `std::panic::catch_unwind`.
+    pub fn from_panic_payload(payload: &(dyn Any + Send), location: Option<&Location<'_>>) -> Self {
+        let message = match payload.downcast_ref::<&str>() {
+            Some(message) => message.to_string(),
+            None => match payload.downcast_ref::<String>() {
+                Some(message) => message.clone(),
+                None => "Box<dyn Any>".to_string(),
+            },
+        };
+
+        let mut error = AnyError::new(PANIC_TYPE.to_string(), message, None);
+        error.context.details.backtrace = Backtrace::capture();
+        match location {
+            Some(location) => error.with_extension(LOCATION_EXTENSION, location.to_string()),
+            None => error,
+        }
+    }
+}
+
+/// The contents of a crash report file.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport<'a> {
+    pub app_name: &'a str,
+    pub version: &'a str,
+    pub os: &'static str,
+    pub arch: &'static str,
+    pub error: &'a AnyError,
+}
+
+/// Writes a report and prints a short note to stderr when the application panics.
+#[derive(Debug, Clone)]
+pub struct CrashReporter {
+    pub app_name: String,
+    pub version: String,
+    /// Where users are asked to send the report.
+    pub support_url: Option<String>,
+    /// The temporary directory by default.
+    pub report_dir: PathBuf,
+}
+
+impl CrashReporter {
+    pub fn new(app_name: impl Into<String>, version: impl Into<String>) -> Self {
+        Self {
+            app_name: app_name.into(),
+            version: version.into(),
+            support_url: None,
+            report_dir: std::env::temp_dir(),
+        }
+    }
+
+    pub fn with_support_url(mut self, url: impl Into<String>) -> Self {
+        self.support_url = Some(url.into());
+        self
+    }
+
+    pub fn with_report_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.report_dir = dir.into();
+        self
+    }
+
+    /// Replaces the panic hook. Panics are reported by [`CrashReporter::report`], always with a
+    /// backtrace, and the process then unwinds or aborts as usual.
+    pub fn install(self) {
+        std::panic::set_hook(Box::new(move |info| {
+            let mut error = AnyError::from_panic(info);
+            if error.context.details.backtrace.is_none() {
+                error.context.details.backtrace =
+                    Backtrace::capture_with(BacktracePolicy::Captured);
+            }
+            self.report(&error, &mut io::stderr());
+        }));
+    }
+
+    /// Writes the report file and the note for users to `out`. When the report can't be
+    /// written, the error is printed in full instead.
+    pub fn report(&self, error: &AnyError, out: &mut impl Write) {
+        let _ = match self.write_report(error) {
+            Ok(path) => self.write_note(&path, out),
+            Err(write_error) => writeln!(
+                out,
+                "{} crashed, and the crash report could not be written ({write_error}):\n\n{}",
+                self.app_name,
+                error.render_report()
+            ),
+        };
+    }
+
+    /// Writes the report as JSON into [`CrashReporter::report_dir`], returning its path.
+    pub fn write_report(&self, error: &AnyError) -> io::Result<PathBuf> {
+        let report = CrashReport {
+            app_name: &self.app_name,
+            version: &self.version,
+            os: std::env::consts::OS,
+            arch: std::env::consts::ARCH,
+            error,
+        };
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let path = self.report_dir.join(format!(
+            "{}-crash-{millis}-{}.json",
+            self.app_name,
+            std::process::id()
+        ));
+        fs::write(&path, serde_json::to_vec_pretty(&report)?)?;
+        Ok(path)
+    }
+
+    fn write_note(&self, path: &Path, out: &mut impl Write) -> io::Result<()> {
+        writeln!(
+            out,
+            "{} {} had a problem and crashed.",
+            self.app_name, self.version
+        )?;
+        writeln!(out)?;
+        writeln!(out, "A report has been written to:")?;
+        writeln!(out)?;
+        writeln!(out, "    {}", path.display())?;
+        writeln!(out)?;
+        match &self.support_url {
+            Some(url) => writeln!(out, "Please attach it to a report at {url}."),
+            None => writeln!(out, "Please include it when reporting the problem."),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_panic_payload() {
+        let payload = std::panic::catch_unwind(|| panic!("index {} out of range", 4)).unwrap_err();
+        let error = AnyError::from_panic_payload(payload.as_ref(), Some(Location::caller()));
+
+        assert_eq!(error.r#type, PANIC_TYPE);
+        assert_eq!(error.message(), "index 4 out of range");
+        assert!(
+            error
+                .extension(LOCATION_EXTENSION)
+                .unwrap()
+                .as_str()
+                .unwrap()
+                .starts_with("src/panic.rs:")
+        );
+
+        let error = AnyError::from_panic_payload(&42, None);
+        assert_eq!(error.message(), "Box<dyn Any>");
+    }
+
+    #[test]
+    fn test_report() {
+        let dir = std::env::temp_dir().join(format!("liberror-crash-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let reporter = CrashReporter::new("tool", "1.2.3")
+            .with_support_url("https://example.com/issues")
+            .with_report_dir(&dir);
+
+        let mut out = Vec::new();
+        reporter.report(&AnyError::msg("boom").with_type(PANIC_TYPE), &mut out);
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("tool 1.2.3 had a problem and crashed."));
+        assert!(out.ends_with("Please attach it to a report at https://example.com/issues.\n"));
+
+        let path = fs::read_dir(&dir).unwrap().next().unwrap().unwrap().path();
+        let report: serde_json::Value = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(report["appName"], "tool");
+        assert_eq!(report["os"], std::env::consts::OS);
+        assert_eq!(report["error"]["context"]["message"], "boom");
+    }
+}
@@ -0,0 +1,102 @@
+//! Lock poisoning. A `PoisonError` only says that some other thread panicked while holding the
+//! lock, so the conversion records which type the lock guards and marks the error as a
+//! secondary failure: the panic that poisoned the lock is the one worth investigating.
+
+use std::{
+    ops::Deref,
+    sync::{LockResult, PoisonError, TryLockError, TryLockResult},
+};
+
+use crate::{AnyError, IntoAnyError, category::Category, type_name::standardized_type_name_of};
+
+pub const POISONED_TYPE: &str = "PoisonError";
+pub const GUARDED_TYPE_EXTENSION: &str = "guardedType";
+/// Set to `true` on errors caused by an earlier failure elsewhere.
+pub const SECONDARY_EXTENSION: &str = "secondary";
+
+impl<G: Deref> IntoAnyError for PoisonError<G> {
+    fn into_any_error(self) -> AnyError {
+        let guarded = standardized_type_name_of(&**self.get_ref());
+        AnyError::msg(format!(
+            "lock guarding {guarded} was poisoned by a thread that panicked while holding it"
+        ))
+        .with_type(POISONED_TYPE)
+        .with_category(Category::Internal)
+        .with_extension(GUARDED_TYPE_EXTENSION, guarded)
+        .with_extension(SECONDARY_EXTENSION, true)
+    }
+}
+
+/// Converts poisoned lock results, e.g. `mutex.lock().or_poisoned()?`.
+pub trait LockResultExt<G> {
+    fn or_poisoned(self) -> Result<G, AnyError>;
+}
+
+impl<G: Deref> LockResultExt<G> for LockResult<G> {
+    fn or_poisoned(self) -> Result<G, AnyError> {
+        self.map_err(IntoAnyError::into_any_error)
+    }
+}
+
+/// Converts `try_lock` results. `WouldBlock` is `Ok(None)`, as it isn't a failure.
+pub trait TryLockResultExt<G> {
+    fn or_poisoned(self) -> Result<Option<G>, AnyError>;
+}
+
+impl<G: Deref> TryLockResultExt<G> for TryLockResult<G> {
+    fn or_poisoned(self) -> Result<Option<G>, AnyError> {
+        match self {
+            Ok(guard) => Ok(Some(guard)),
+            Err(TryLockError::WouldBlock) => Ok(None),
+            Err(TryLockError::Poisoned(error)) => Err(error.into_any_error()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex, RwLock};
+
+    use super::*;
+
+    fn poison<T: Send + 'static>(lock: Arc<Mutex<T>>) {
+        let _ = std::thread::spawn(move || {
+            let _guard = lock.lock().unwrap();
+            panic!("poisoning");
+        })
+        .join();
+    }
+
+    #[test]
+    fn test_poisoned_mutex() {
+        let lock = Arc::new(Mutex::new(vec![1u32]));
+        poison(lock.clone());
+
+        let error = lock.lock().or_poisoned().unwrap_err();
+        assert_eq!(error.r#type, POISONED_TYPE);
+        assert_eq!(
+            error.extension(GUARDED_TYPE_EXTENSION),
+            Some(&serde_json::json!("Vec<u32>"))
+        );
+        assert_eq!(
+            error.extension(SECONDARY_EXTENSION),
+            Some(&serde_json::json!(true))
+        );
+
+        let error = lock.try_lock().or_poisoned().unwrap_err();
+        assert_eq!(
+            error.extension(GUARDED_TYPE_EXTENSION),
+            Some(&serde_json::json!("Vec<u32>"))
+        );
+    }
+
+    #[test]
+    fn test_healthy_locks() {
+        let lock = RwLock::new(String::from("ok"));
+        assert_eq!(*lock.read().or_poisoned().unwrap(), "ok");
+
+        let mutex = Mutex::new(1);
+        let _guard = mutex.lock().unwrap();
+        assert!(mutex.try_lock().or_poisoned().unwrap().is_none());
+    }
+}
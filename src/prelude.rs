@@ -0,0 +1,18 @@
+//! The types, extension traits and macros most code needs, in one import:
+//!
+//! ```
+//! use liberror::prelude::*;
+//!
+//! fn port(value: &str) -> AnyResult<u16> {
+//!     ensure!(!value.is_empty(), "no port given");
+//!     value.parse::<u16>().context("invalid port")
+//! }
+//!
+//! assert_eq!(port("8080").unwrap(), 8080);
+//! ```
+
+pub use crate::{
+    AnyError, AnyResult, IntoAnyError, aggregate::ResultIteratorExt, anyhow, bail,
+    compat::anyhow::Context, context::ContextExt, context_scope, ensure, option::OptionExt,
+    poison::LockResultExt, text::ParseExt,
+};
@@ -0,0 +1,112 @@
+//! Keeping the original error next to its serializable form, for callers that still need to
+//! inspect it, e.g. to match on an `io::ErrorKind`.
+
+use std::{error::Error, sync::Arc};
+
+use crate::{AnyError, type_name::standardized_type_name_of};
+
+impl AnyError {
+    /// Converts `value` like `AnyError::from`, also keeping `value` itself for
+    /// [`AnyError::downcast_ref`]. The original is shared between clones and is not serialized,
+    /// so it is gone once the error has crossed a process boundary.
+    #[track_caller]
+    pub fn from_preserving<E: Error + Send + Sync + 'static>(value: E) -> Self {
+        let mut error = AnyError::capture(standardized_type_name_of(&value), &value);
+        error.context.details.original = Some(Arc::new(value));
+        error.finish_conversion();
+        error
+    }
+
+    /// The original error of the outermost layer that kept one.
+    pub fn original(&self) -> Option<&(dyn Error + Send + Sync + 'static)> {
+        let mut node = Some(self);
+        while let Some(error) = node {
+            if let Some(original) = error.context.details.original.as_deref() {
+                return Some(original);
+            }
+            node = error.inner();
+        }
+        None
+    }
+
+    /// Finds a `T` among the kept originals and their sources, outermost first.
+    pub fn downcast_ref<T: Error + 'static>(&self) -> Option<&T> {
+        let mut node = Some(self);
+        while let Some(error) = node {
+            let original = error
+                .context
+                .details
+                .original
+                .as_deref()
+                .map(|original| original as &(dyn Error + 'static));
+            let found = std::iter::successors(original, |&error| error.source())
+                .find_map(|error| error.downcast_ref::<T>());
+            if found.is_some() {
+                return found;
+            }
+            node = error.inner();
+        }
+        None
+    }
+
+    pub fn is<T: Error + 'static>(&self) -> bool {
+        self.downcast_ref::<T>().is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fmt, io};
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct ReadError(io::Error);
+
+    impl fmt::Display for ReadError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "failed to read config")
+        }
+    }
+
+    impl Error for ReadError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    #[test]
+    fn test_downcast() {
+        let io = io::Error::new(io::ErrorKind::PermissionDenied, "denied");
+        let mut error = AnyError::from_preserving(ReadError(io));
+        error.wrap_with("starting server");
+
+        assert_eq!(
+            error.downcast_ref::<io::Error>().map(io::Error::kind),
+            Some(io::ErrorKind::PermissionDenied)
+        );
+        assert!(error.is::<ReadError>());
+        assert!(!error.is::<fmt::Error>());
+        assert_eq!(
+            error.original().unwrap().to_string(),
+            "failed to read config"
+        );
+        assert_eq!(
+            error.chain_messages(),
+            ["starting server", "failed to read config", "denied"]
+        );
+    }
+
+    #[test]
+    fn test_not_kept_by_default_or_serialized() {
+        assert!(AnyError::from(fmt::Error).original().is_none());
+
+        let error = AnyError::from_preserving(fmt::Error);
+        assert!(error.clone().is::<fmt::Error>());
+
+        let json = serde_json::to_value(&error).unwrap();
+        assert!(json["context"].get("original").is_none());
+        let decoded: AnyError = serde_json::from_value(json).unwrap();
+        assert!(decoded.original().is_none());
+    }
+}
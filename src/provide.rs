@@ -0,0 +1,75 @@
+use std::{
+    backtrace::{Backtrace as StdBacktrace, BacktraceStatus},
+    error::{Error, request_ref, request_value},
+};
+
+use crate::{attachment::Attachment, backtrace::Backtrace, config};
+
+/// Pulls a captured `Backtrace` and an [`Attachment`] out of an error through
+/// `Error::provide`. Errors opt in to the latter with `request.provide_value(Attachment::new(..))`
+/// or `provide_ref`.
+pub(crate) fn extract<E: Error + ?Sized>(error: &E) -> (Option<Backtrace>, Vec<Attachment>) {
+    let backtrace = request_ref::<StdBacktrace>(error)
+        .filter(|backtrace| backtrace.status() == BacktraceStatus::Captured)
+        .map(|backtrace| Backtrace::from(backtrace).filter(&config::current().frame_filter));
+
+    let attachment = request_ref::<Attachment>(error)
+        .cloned()
+        .or_else(|| request_value::<Attachment>(error));
+
+    (backtrace, attachment.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{error::Request, fmt};
+
+    use super::*;
+    use crate::AnyError;
+
+    #[derive(Debug)]
+    struct ProvidingError {
+        backtrace: StdBacktrace,
+    }
+
+    impl fmt::Display for ProvidingError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "providing error")
+        }
+    }
+
+    impl Error for ProvidingError {
+        fn provide<'a>(&'a self, request: &mut Request<'a>) {
+            request
+                .provide_ref::<StdBacktrace>(&self.backtrace)
+                .provide_value(Attachment::new(17u16));
+        }
+    }
+
+    #[test]
+    fn test_extracts_provided_values() {
+        let any_error = AnyError::from(ProvidingError {
+            backtrace: StdBacktrace::force_capture(),
+        });
+
+        assert!(any_error.context.details.backtrace.is_some());
+    }
+
+    #[test]
+    fn test_extracts_provided_attachment() {
+        let any_error = AnyError::from(ProvidingError {
+            backtrace: StdBacktrace::disabled(),
+        });
+
+        assert_eq!(any_error.attachment::<u16>(), Some(&17));
+    }
+
+    #[test]
+    fn test_ignores_disabled_backtrace() {
+        let (backtrace, _) = extract(&ProvidingError {
+            backtrace: StdBacktrace::disabled(),
+        });
+
+        assert!(backtrace.is_none());
+    }
+}
@@ -0,0 +1,214 @@
+//! A fixed-size buffer of the most recently reported errors, for debug endpoints and dumps
+//! after an incident. Register it as a [sink](crate::sink::register) through an `Arc` to keep
+//! a handle for reading it back.
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::Mutex,
+    time::SystemTime,
+};
+
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::{AnyError, sink::ErrorSink};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentError {
+    #[serde(rename = "reportedAtMs", with = "epoch_millis")]
+    pub reported_at: SystemTime,
+    pub error: AnyError,
+}
+
+/// Counts over a [`RecentErrors`] buffer, see [`RecentErrors::stats`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorStats {
+    /// Every error pushed, including evicted ones.
+    pub total: u64,
+    pub stored: usize,
+    pub capacity: usize,
+    /// Stored errors per outermost `$type`.
+    pub by_type: BTreeMap<String, usize>,
+}
+
+#[derive(Debug)]
+pub struct RecentErrors {
+    capacity: usize,
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// Oldest first.
+    entries: VecDeque<RecentError>,
+    total: u64,
+}
+
+impl RecentErrors {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            state: Mutex::new(State {
+                entries: VecDeque::with_capacity(capacity),
+                total: 0,
+            }),
+        }
+    }
+
+    /// Stores `error`, evicting the oldest one if the buffer is full.
+    pub fn push(&self, error: AnyError) {
+        let entry = RecentError {
+            reported_at: SystemTime::now(),
+            error,
+        };
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.total += 1;
+        if self.capacity == 0 {
+            return;
+        }
+        if state.entries.len() == self.capacity {
+            state.entries.pop_front();
+        }
+        state.entries.push_back(entry);
+    }
+
+    /// The stored errors, newest first.
+    pub fn snapshot(&self) -> Vec<RecentError> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.entries.iter().rev().cloned().collect()
+    }
+
+    /// The stored errors reported at or after `time`, newest first.
+    pub fn since(&self, time: SystemTime) -> Vec<RecentError> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state
+            .entries
+            .iter()
+            .rev()
+            .take_while(|entry| entry.reported_at >= time)
+            .cloned()
+            .collect()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entries
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Every error pushed so far, including evicted ones.
+    pub fn total(&self) -> u64 {
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).total
+    }
+
+    pub fn stats(&self) -> ErrorStats {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let mut by_type = BTreeMap::new();
+        for entry in &state.entries {
+            *by_type.entry(entry.error.r#type.clone()).or_default() += 1;
+        }
+        ErrorStats {
+            total: state.total,
+            stored: state.entries.len(),
+            capacity: self.capacity,
+            by_type,
+        }
+    }
+
+    pub fn clear(&self) {
+        self.state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entries
+            .clear();
+    }
+}
+
+impl ErrorSink for RecentErrors {
+    fn report(&self, error: &AnyError) {
+        self.push(error.clone());
+    }
+}
+
+/// Serializes as the [`RecentErrors::snapshot`].
+impl Serialize for RecentErrors {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.snapshot().serialize(serializer)
+    }
+}
+
+/// Serializes a time as whole milliseconds since the Unix epoch.
+mod epoch_millis {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &SystemTime, serializer: S) -> Result<S::Ok, S::Error> {
+        let millis = value
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        serializer.serialize_u64(millis as u64)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<SystemTime, D::Error> {
+        Ok(UNIX_EPOCH + Duration::from_millis(u64::deserialize(deserializer)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::UNIX_EPOCH};
+
+    use super::*;
+
+    #[test]
+    fn test_keeps_newest() {
+        let recent = RecentErrors::new(2);
+        for message in ["first", "second", "third"] {
+            recent.report(&AnyError::msg(message));
+        }
+
+        let messages = recent
+            .snapshot()
+            .into_iter()
+            .map(|entry| entry.error.message().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(messages, ["third", "second"]);
+        assert_eq!(recent.total(), 3);
+        assert_eq!(recent.len(), 2);
+
+        let stats = recent.stats();
+        assert_eq!((stats.total, stats.stored), (3, 2));
+        assert_eq!(stats.by_type[crate::MESSAGE_TYPE], 2);
+    }
+
+    #[test]
+    fn test_since_and_serialize() {
+        let recent = Arc::new(RecentErrors::new(8));
+        recent.report(&AnyError::msg("old"));
+        let cutoff = SystemTime::now();
+        recent.report(&AnyError::msg("new"));
+
+        assert_eq!(recent.since(cutoff).len(), 1);
+        assert_eq!(recent.since(UNIX_EPOCH).len(), 2);
+
+        let json = serde_json::to_value(&*recent).unwrap();
+        assert_eq!(json[0]["error"]["context"]["message"], "new");
+        assert!(json[0]["reportedAtMs"].as_u64().unwrap() > 0);
+
+        let entries: Vec<RecentError> = serde_json::from_value(json).unwrap();
+        assert_eq!(entries.len(), 2);
+    }
+}
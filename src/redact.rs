@@ -0,0 +1,349 @@
+//! Scrubbing personal data and secrets out of errors before they leave the process, replacing
+//! them with [`REDACTED`].
+//!
+//! Set [`Config::redactor`](crate::config::Config::redactor) to scrub every error as it is
+//! converted, or scrub when serializing with [`AnyError::redacted`] or
+//! `#[serde(serialize_with = "liberror::redact::serialize")]`. Messages written with
+//! [`AnyError::msg`] or [`AnyError::wrap_with`] are only scrubbed when serializing.
+
+use std::{borrow::Cow, sync::LazyLock};
+
+use regex::{Captures, Regex};
+use serde::{Serialize, Serializer};
+
+use crate::{AnyError, config};
+
+pub const REDACTED: &str = "[REDACTED]";
+
+/// A check a pattern's match must pass to be replaced, e.g. a checksum.
+type MatchCheck = fn(&str) -> bool;
+
+/// The rules applied to messages, help texts, metadata values, attachments and snippets.
+#[derive(Debug, Clone, Default)]
+pub struct Redactor {
+    patterns: Vec<(Regex, Option<MatchCheck>)>,
+    keywords: Vec<Regex>,
+    keys: Vec<String>,
+}
+
+impl Redactor {
+    /// A redactor without any rules.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Emails, card numbers, bearer tokens and JWTs, and the values of `password`, `secret`,
+    /// `token`, `api_key` and `authorization`.
+    pub fn common() -> Self {
+        let pattern = |pattern| Regex::new(pattern).expect("built-in patterns are valid");
+        [
+            "password",
+            "passwd",
+            "secret",
+            "token",
+            "api_key",
+            "apikey",
+            "authorization",
+        ]
+        .into_iter()
+        .fold(Self::new(), Self::keyword)
+        .pattern(pattern(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}"))
+        .checked_pattern(pattern(r"\b(?:\d[ -]?){12,18}\d\b"), passes_luhn)
+        .pattern(pattern(r"(?i)\bbearer\s+[A-Za-z0-9._~+/-]+=*"))
+        .pattern(pattern(
+            r"\beyJ[A-Za-z0-9_-]+\.[A-Za-z0-9_-]+\.[A-Za-z0-9_-]+",
+        ))
+    }
+
+    /// Replaces every match of `pattern`.
+    pub fn pattern(mut self, pattern: Regex) -> Self {
+        self.patterns.push((pattern, None));
+        self
+    }
+
+    fn checked_pattern(mut self, pattern: Regex, check: MatchCheck) -> Self {
+        self.patterns.push((pattern, Some(check)));
+        self
+    }
+
+    /// Replaces the value of `keyword`, in text as `keyword=value` or `keyword: value` and in
+    /// metadata as the value of a `keyword` key, ignoring case.
+    pub fn keyword(mut self, keyword: &str) -> Self {
+        let pattern = format!(
+            r#"(?i)\b({})(\s*[:=]\s*["']?)[^\s,;&)"']+"#,
+            regex::escape(keyword)
+        );
+        self.keywords
+            .push(Regex::new(&pattern).expect("escaped keywords are valid patterns"));
+        self.keys.push(keyword.to_lowercase());
+        self
+    }
+
+    pub fn redact_str<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut text = Cow::Borrowed(text);
+        for (pattern, check) in &self.patterns {
+            let redacted = match check {
+                None => pattern.replace_all(&text, REDACTED),
+                Some(check) => pattern.replace_all(&text, |captures: &Captures| {
+                    let matched = &captures[0];
+                    if check(matched) { REDACTED } else { matched }.to_string()
+                }),
+            };
+            if let Cow::Owned(redacted) = redacted {
+                text = Cow::Owned(redacted);
+            }
+        }
+        for keyword in &self.keywords {
+            if let Cow::Owned(redacted) =
+                keyword.replace_all(&text, format!("${{1}}${{2}}{REDACTED}"))
+            {
+                text = Cow::Owned(redacted);
+            }
+        }
+        text
+    }
+
+    /// Redacts the strings in `value`, and the whole value of keys matching a keyword.
+    pub fn redact_value(&self, value: &mut serde_json::Value) {
+        let mut stack = vec![value];
+        while let Some(value) = stack.pop() {
+            match value {
+                serde_json::Value::String(text) => {
+                    if let Cow::Owned(redacted) = self.redact_str(text) {
+                        *text = redacted;
+                    }
+                }
+                serde_json::Value::Array(values) => stack.extend(values),
+                serde_json::Value::Object(map) => {
+                    for (key, value) in map {
+                        if self.is_secret_key(key) {
+                            *value = REDACTED.into();
+                        } else {
+                            stack.push(value);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn is_secret_key(&self, key: &str) -> bool {
+        self.keys.contains(&key.to_lowercase())
+    }
+
+    fn redact_string(&self, text: &mut String) {
+        if let Cow::Owned(redacted) = self.redact_str(text) {
+            *text = redacted;
+        }
+    }
+
+    /// Redacts every layer, cause and related error of `error`: messages, help texts,
+    /// extensions, data, attachments and snippets.
+    pub fn redact(&self, error: &mut AnyError) {
+        let mut stack = vec![error];
+        while let Some(error) = stack.pop() {
+            let context = error.make_context_mut();
+            self.redact_string(&mut context.message);
+            let details = &mut *context.details;
+            if let Some(help) = &mut details.help {
+                self.redact_string(help);
+            }
+            for (key, value) in details.extensions.iter_mut().chain(&mut details.data) {
+                if self.is_secret_key(key) {
+                    *value = REDACTED.into();
+                } else {
+                    self.redact_value(value);
+                }
+            }
+            for attachment in &mut details.attachments {
+                let mut value = attachment.value.clone();
+                self.redact_value(&mut value);
+                if value != attachment.value {
+                    attachment.replace_value(value);
+                }
+            }
+            if let Some(snippet) = &mut details.snippet {
+                self.redact_string(&mut snippet.text);
+                if let Some(label) = &mut snippet.label {
+                    self.redact_string(label);
+                }
+                for label in &mut snippet.labels {
+                    self.redact_string(&mut label.message);
+                }
+            }
+            stack.extend(
+                context
+                    .inner_error
+                    .iter_mut()
+                    .chain(&mut details.inner_errors)
+                    .map(std::sync::Arc::make_mut)
+                    .chain(&mut details.related),
+            );
+        }
+    }
+}
+
+/// The Luhn checksum of card numbers, ignoring separators.
+fn passes_luhn(number: &str) -> bool {
+    let sum = number
+        .bytes()
+        .filter(u8::is_ascii_digit)
+        .rev()
+        .enumerate()
+        .map(|(index, digit)| {
+            let digit = u32::from(digit - b'0');
+            match index % 2 {
+                0 => digit,
+                _ if digit > 4 => digit * 2 - 9,
+                _ => digit * 2,
+            }
+        })
+        .sum::<u32>();
+    sum % 10 == 0
+}
+
+pub(crate) static COMMON: LazyLock<Redactor> = LazyLock::new(Redactor::common);
+
+/// Scrubs a freshly converted error with the configured redactor, if any.
+pub(crate) fn apply(error: &mut AnyError) {
+    if let Some(redactor) = config::current().redactor.as_deref() {
+        redactor.redact(error);
+    }
+}
+
+impl AnyError {
+    /// A copy of this error scrubbed by `redactor`, e.g. to serialize for a log vendor.
+    pub fn redacted(&self, redactor: &Redactor) -> AnyError {
+        let mut error = self.clone();
+        redactor.redact(&mut error);
+        error
+    }
+}
+
+/// Serializes an `AnyError` field scrubbed by the configured redactor, or by
+/// [`Redactor::common`] if none is configured.
+pub fn serialize<S: Serializer>(error: &AnyError, serializer: S) -> Result<S::Ok, S::Error> {
+    let redacted = match config::current().redactor.as_deref() {
+        Some(redactor) => error.redacted(redactor),
+        None => error.redacted(&COMMON),
+    };
+    redacted.serialize(serializer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_common() {
+        let redactor = Redactor::common();
+
+        assert_eq!(
+            redactor.redact_str("no account for jane.doe@example.com"),
+            "no account for [REDACTED]"
+        );
+        assert_eq!(
+            redactor.redact_str("card 4111 1111 1111 1111 declined"),
+            "card [REDACTED] declined"
+        );
+        assert_eq!(
+            redactor.redact_str("GET /login?user=jane&password=hunter2 failed"),
+            "GET /login?user=jane&password=[REDACTED] failed"
+        );
+        assert_eq!(
+            redactor.redact_str("Authorization: Bearer abc.def-123"),
+            "Authorization: [REDACTED]"
+        );
+        assert!(matches!(
+            redactor.redact_str("retry 3 of 5 after 250ms"),
+            Cow::Borrowed(_)
+        ));
+        assert_eq!(
+            redactor.redact_str("card 4111 1111 1111 1112 declined at 1709164800123"),
+            "card 4111 1111 1111 1112 declined at 1709164800123"
+        );
+    }
+
+    #[test]
+    fn test_redact_chain() {
+        let mut error = AnyError::msg("no account for jane.doe@example.com")
+            .with_data(
+                "user",
+                serde_json::json!({ "email": "jane@example.com", "id": 7 }),
+            )
+            .with_extension("Token", "abc123");
+        error.wrap_with("login failed for jane.doe@example.com");
+
+        let redacted = error.redacted(&Redactor::common());
+        assert_eq!(
+            redacted.chain_messages(),
+            ["login failed for [REDACTED]", "no account for [REDACTED]"]
+        );
+        let inner = redacted.inner().unwrap();
+        assert_eq!(
+            inner.data("user"),
+            Some(&serde_json::json!({ "email": REDACTED, "id": 7 }))
+        );
+        assert_eq!(inner.extension("Token"), Some(&REDACTED.into()));
+        assert_eq!(
+            error.inner().unwrap().message(),
+            "no account for jane.doe@example.com"
+        );
+    }
+
+    #[test]
+    fn test_custom_rules() {
+        let redactor = Redactor::new()
+            .pattern(Regex::new(r"\bcus_[A-Za-z0-9]+").unwrap())
+            .keyword("session");
+
+        assert_eq!(
+            redactor.redact_str("customer cus_9f2A not found (session=s3cr3t)"),
+            "customer [REDACTED] not found (session=[REDACTED])"
+        );
+        assert_eq!(
+            redactor.redact_str("jane.doe@example.com"),
+            "jane.doe@example.com"
+        );
+    }
+
+    #[test]
+    fn test_serialize_with() {
+        #[derive(Serialize)]
+        struct Event {
+            #[serde(serialize_with = "crate::redact::serialize")]
+            error: AnyError,
+        }
+
+        let error = AnyError::msg("password: hunter2 rejected");
+        let json = serde_json::to_value(Event { error }).unwrap();
+        assert_eq!(
+            json["error"]["context"]["message"],
+            "password: [REDACTED] rejected"
+        );
+    }
+
+    #[test]
+    fn test_redact_related_attachments_and_snippets() {
+        use crate::snippet::Snippet;
+
+        let source = "[db]\nurl = \"postgres://app@db\"\npassword = \"hunter2\"\n";
+        let snippet =
+            Snippet::new("app.toml", source, 42..51).with_primary_label("contact ops@example.com");
+        let error = AnyError::msg("invalid config")
+            .with_snippet(snippet)
+            .with_related(AnyError::msg("notified ops@example.com"));
+        let error = error.attach(serde_json::json!({ "apiKey": "k", "by": "jane@example.com" }));
+
+        let redacted = error.redacted(&Redactor::common());
+        let snippet = redacted.snippet().unwrap();
+        assert_eq!(snippet.text, "password = \"[REDACTED]\"");
+        assert_eq!(snippet.label.as_deref(), Some("contact [REDACTED]"));
+        assert_eq!(redacted.all_related()[0].message(), "notified [REDACTED]");
+        let attachment = &redacted.context.attachments()[0];
+        assert_eq!(attachment.value["by"], REDACTED);
+        assert_eq!(attachment.value["apiKey"], REDACTED);
+        assert!(attachment.downcast_ref::<serde_json::Value>().is_none());
+    }
+}
@@ -0,0 +1,66 @@
+use crate::AnyError;
+
+impl AnyError {
+    /// Attaches an error that happened while handling this one but didn't cause it, such as
+    /// a failed rollback, so it isn't lost when only the primary error is returned.
+    pub fn with_related(mut self, related: impl Into<AnyError>) -> Self {
+        self.add_related(related.into());
+        self
+    }
+
+    pub fn add_related(&mut self, related: AnyError) {
+        self.context.details.related.push(related);
+    }
+
+    /// The related errors of every layer, outermost first.
+    pub fn all_related(&self) -> Vec<&AnyError> {
+        let mut related = Vec::new();
+        let mut node = Some(self);
+        while let Some(error) = node {
+            related.extend(error.context.related());
+            node = error.inner();
+        }
+        related
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_related() {
+        let mut error = AnyError::msg("insert failed")
+            .with_related(AnyError::msg("rollback failed: connection closed"));
+        error.wrap_with("saving order");
+        error.add_related(AnyError::msg("failed to release lock"));
+
+        assert_eq!(
+            error
+                .all_related()
+                .into_iter()
+                .map(AnyError::message)
+                .collect::<Vec<_>>(),
+            vec![
+                "failed to release lock",
+                "rollback failed: connection closed"
+            ]
+        );
+        assert!(
+            error
+                .causes()
+                .all(|cause| cause.message() == "insert failed")
+        );
+
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(
+            json["context"]["related"][0]["context"]["message"],
+            "failed to release lock"
+        );
+        assert!(
+            serde_json::to_value(AnyError::msg("plain")).unwrap()["context"]
+                .get("related")
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,293 @@
+use crate::{AnyError, retry};
+
+/// `$type` of errors read by [`AnyError::from_http_body`] from bodies without a type of their
+/// own.
+pub const HTTP_ERROR_TYPE: &str = "HttpError";
+pub const STATUS_EXTENSION: &str = "status";
+pub const URL_EXTENSION: &str = "url";
+/// The `title` of a problem details body that also had a `detail`, which became the message.
+pub const TITLE_EXTENSION: &str = "title";
+/// `$type` of errors read by [`AnyError::from_grpc_status`].
+pub const GRPC_ERROR_TYPE: &str = "GrpcError";
+/// The numeric status code of an error read by [`AnyError::from_grpc_status`].
+pub const GRPC_STATUS_EXTENSION: &str = "grpcStatus";
+
+impl AnyError {
+    /// Appends an error received from an upstream service below the innermost local cause,
+    /// marking each of its layers as remote.
+    pub fn caused_by_remote(mut self, mut remote: AnyError) -> Self {
+        mark_remote(&mut remote);
+        self.push_cause(remote);
+        self
+    }
+
+    /// Reads an upstream error serialized as this crate's JSON, e.g. from a response body or
+    /// RPC status details. `None` if `bytes` aren't a serialized `AnyError`.
+    pub fn from_remote_json(bytes: &[u8]) -> Option<AnyError> {
+        serde_json::from_slice(bytes).ok()
+    }
+
+    /// Reads a failed HTTP response body, trying in turn RFC 9457 problem details, this
+    /// crate's JSON and plain text. The result is marked remote and carries the status and,
+    /// if given, the request URL as extensions.
+    pub fn from_http_body(
+        status: u16,
+        content_type: Option<&str>,
+        body: &[u8],
+        url: Option<&str>,
+    ) -> AnyError {
+        let media_type = content_type
+            .and_then(|content_type| content_type.split(';').next())
+            .map(|media_type| media_type.trim().to_ascii_lowercase());
+        let json = media_type
+            .as_deref()
+            .is_none_or(|media_type| media_type.ends_with("json"))
+            .then(|| serde_json::from_slice::<serde_json::Value>(body).ok())
+            .flatten();
+
+        let mut error = match json {
+            Some(serde_json::Value::Object(object)) if object.contains_key("$type") => {
+                Self::from_remote_json(body)
+            }
+            Some(serde_json::Value::Object(object))
+                if media_type.as_deref() == Some("application/problem+json")
+                    || object.contains_key("title")
+                    || object.contains_key("detail") =>
+            {
+                Some(from_problem(object))
+            }
+            _ => None,
+        }
+        .unwrap_or_else(|| {
+            let text = String::from_utf8_lossy(body);
+            let message = match text.trim() {
+                "" => format!("HTTP {status}"),
+                text => text.to_string(),
+            };
+            AnyError::msg(message).with_type(HTTP_ERROR_TYPE)
+        });
+
+        mark_remote(&mut error);
+        let mut error = error.with_extension(STATUS_EXTENSION, status);
+        if let Some(url) = url {
+            error = error.with_extension(URL_EXTENSION, url);
+        }
+        error
+    }
+
+    /// Reads a failed gRPC call from its status code, message and serialized
+    /// `google.rpc.Status` details: the (base64 decoded) `grpc-status-details-bin` trailer or
+    /// tonic's `Status::details()`, empty if there are none. The result is marked remote,
+    /// carries the code as an extension and takes the delay of a `google.rpc.RetryInfo` detail
+    /// as its [`retry_after`](AnyError::retry_after). `None` for the `OK` status.
+    pub fn from_grpc_status(code: i32, message: &str, details: &[u8]) -> Option<AnyError> {
+        if code == 0 {
+            return None;
+        }
+        let message = match message.trim() {
+            "" => format!("gRPC status {code}"),
+            message => message.to_string(),
+        };
+        let mut error = AnyError::msg(message)
+            .with_type(GRPC_ERROR_TYPE)
+            .with_extension(GRPC_STATUS_EXTENSION, code);
+        if let Some(retry_after) = retry::parse_grpc_retry_info(details) {
+            error = error.with_retry_after(retry_after);
+        }
+        mark_remote(&mut error);
+        Some(error)
+    }
+}
+
+/// The problem `type` URI identifies the kind of problem, so it becomes the `$type`, and the
+/// docs URL when it can be opened. `title` is the same for every occurrence of a type and
+/// `detail` specific to this one, so the message is the `detail`, with the `title` kept as an
+/// extension, or the `title` alone.
+fn from_problem(mut object: serde_json::Map<String, serde_json::Value>) -> AnyError {
+    let mut take = |key| match object.remove(key) {
+        Some(serde_json::Value::String(value)) => Some(value),
+        _ => None,
+    };
+    let r#type = take("type").filter(|r#type| !r#type.is_empty() && r#type != "about:blank");
+    let title = take("title");
+    let detail = take("detail");
+    object.remove("status");
+
+    let docs_url = r#type
+        .as_deref()
+        .filter(|r#type| r#type.starts_with("https://") || r#type.starts_with("http://"))
+        .map(str::to_string);
+    let mut error = match (detail, title) {
+        (Some(detail), Some(title)) => AnyError::msg(detail).with_extension(TITLE_EXTENSION, title),
+        (detail, title) => AnyError::msg(detail.or(title).unwrap_or_default()),
+    }
+    .with_type(r#type.unwrap_or_else(|| HTTP_ERROR_TYPE.to_string()));
+    if let Some(docs_url) = docs_url {
+        error = error.with_docs_url(docs_url);
+    }
+    object.into_iter().fold(error, |error, (key, value)| {
+        error.with_extension(key, value)
+    })
+}
+
+fn mark_remote(error: &mut AnyError) {
+    let mut node = Some(error);
+    while let Some(error) = node {
+        error.context.details.remote = true;
+        node = error.context.inner_mut();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_caused_by_remote() {
+        let mut upstream = AnyError::msg("row locked").with_type("db.lock");
+        upstream.wrap_with("updating balance");
+        let body = serde_json::to_vec(&upstream).unwrap();
+
+        let mut local = AnyError::msg("upstream returned 503").with_type("http.status");
+        local.wrap_with("charging card");
+        let error = local.caused_by_remote(AnyError::from_remote_json(&body).unwrap());
+
+        assert_eq!(
+            error.chain_messages(),
+            vec![
+                "charging card",
+                "upstream returned 503",
+                "updating balance",
+                "row locked"
+            ]
+        );
+        let mut remote = Vec::new();
+        let mut node = Some(&error);
+        while let Some(error) = node {
+            remote.push(error.context.is_remote());
+            node = error.inner();
+        }
+        assert_eq!(remote, vec![false, false, true, true]);
+
+        let json = serde_json::to_value(&error).unwrap();
+        assert!(json["context"].get("remote").is_none());
+        assert_eq!(
+            json["context"]["innerError"]["context"]["innerError"]["context"]["remote"],
+            true
+        );
+    }
+
+    #[test]
+    fn test_from_remote_json_rejects_other_bodies() {
+        assert!(AnyError::from_remote_json(b"<html>Bad Gateway</html>").is_none());
+        assert!(AnyError::from_remote_json(br#"{"error": "nope"}"#).is_none());
+    }
+
+    #[test]
+    fn test_from_http_body_problem() {
+        let body = br#"{
+            "type": "https://docs.example.com/out-of-credit",
+            "title": "You do not have enough credit.",
+            "status": 403,
+            "detail": "Your balance is 30, but that costs 50.",
+            "balance": 30
+        }"#;
+        let error = AnyError::from_http_body(
+            403,
+            Some("application/problem+json; charset=utf-8"),
+            body,
+            Some("https://api.example.com/charge"),
+        );
+
+        assert_eq!(error.r#type, "https://docs.example.com/out-of-credit");
+        assert_eq!(error.message(), "Your balance is 30, but that costs 50.");
+        assert_eq!(
+            error.extension(TITLE_EXTENSION),
+            Some(&serde_json::json!("You do not have enough credit."))
+        );
+        assert_eq!(
+            error.docs_url(),
+            Some("https://docs.example.com/out-of-credit")
+        );
+        assert_eq!(error.extension("balance"), Some(&serde_json::json!(30)));
+        assert_eq!(error.extension("status"), Some(&serde_json::json!(403)));
+        assert_eq!(
+            error.extension("url"),
+            Some(&serde_json::json!("https://api.example.com/charge"))
+        );
+        assert!(error.context.is_remote());
+    }
+
+    #[test]
+    fn test_from_http_body_untyped_problem() {
+        let body = br#"{"type": "about:blank", "title": "Service Unavailable", "status": 503}"#;
+        let error = AnyError::from_http_body(503, Some("application/problem+json"), body, None);
+
+        assert_eq!(error.r#type, HTTP_ERROR_TYPE);
+        assert_eq!(error.message(), "Service Unavailable");
+        assert_eq!(error.extension(TITLE_EXTENSION), None);
+        assert_eq!(error.docs_url(), None);
+
+        let body = br#"{"type": "urn:acme:quota", "title": "Quota exceeded"}"#;
+        let error = AnyError::from_http_body(429, None, body, None);
+        assert_eq!(error.r#type, "urn:acme:quota");
+        assert_eq!(error.message(), "Quota exceeded");
+        assert_eq!(error.docs_url(), None);
+    }
+
+    #[test]
+    fn test_from_http_body_any_error() {
+        let upstream = AnyError::msg("no such user").with_type("app.not_found");
+        let body = serde_json::to_vec(&upstream).unwrap();
+        let error = AnyError::from_http_body(404, Some("application/json"), &body, None);
+
+        assert_eq!(error.r#type, "app.not_found");
+        assert_eq!(error.extension("status"), Some(&serde_json::json!(404)));
+        assert_eq!(error.extension("url"), None);
+    }
+
+    #[test]
+    fn test_from_http_body_text() {
+        let error = AnyError::from_http_body(502, Some("text/html"), b" Bad Gateway\n", None);
+        assert_eq!(error.r#type, HTTP_ERROR_TYPE);
+        assert_eq!(error.message(), "Bad Gateway");
+
+        let error = AnyError::from_http_body(500, Some("application/json"), b"{\"ok\"", None);
+        assert_eq!(error.message(), "{\"ok\"");
+
+        assert_eq!(
+            AnyError::from_http_body(503, None, b"", None).message(),
+            "HTTP 503"
+        );
+    }
+
+    #[test]
+    fn test_from_grpc_status() {
+        // google.rpc.Status { details: [Any(RetryInfo { retry_delay: 7s })] }
+        let field =
+            |number: u8, value: &[u8]| [&[number << 3 | 2, value.len() as u8], value].concat();
+        let retry_info = field(1, &[0x08, 0x07]);
+        let any = [
+            field(1, b"type.googleapis.com/google.rpc.RetryInfo"),
+            field(2, &retry_info),
+        ]
+        .concat();
+        let details = field(3, &any);
+
+        let error = AnyError::from_grpc_status(14, "backend overloaded", &details).unwrap();
+        assert_eq!(error.r#type, GRPC_ERROR_TYPE);
+        assert_eq!(error.message(), "backend overloaded");
+        assert_eq!(
+            error.extension(GRPC_STATUS_EXTENSION),
+            Some(&serde_json::json!(14))
+        );
+        assert_eq!(error.retry_after(), Some(std::time::Duration::from_secs(7)));
+        assert!(error.context.is_remote());
+
+        let error = AnyError::from_grpc_status(5, "", &[]).unwrap();
+        assert_eq!(error.message(), "gRPC status 5");
+        assert_eq!(error.retry_after(), None);
+
+        assert!(AnyError::from_grpc_status(0, "", &details).is_none());
+    }
+}
@@ -0,0 +1,327 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    AnyError, backtrace::Backtrace, location::SourceLocation, snippet::Snippet,
+    span_trace::SpanTrace,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportEntry {
+    #[serde(rename = "$type")]
+    pub r#type: String,
+    pub message: String,
+    /// Where the layer was created, if it was recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<SourceLocation>,
+}
+
+impl ReportEntry {
+    fn new(error: &AnyError) -> Self {
+        Self {
+            r#type: error.r#type.clone(),
+            message: error.context.message.clone(),
+            location: error.context.location().cloned(),
+        }
+    }
+
+    fn write_location(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.location {
+            Some(location) => writeln!(f, "      at {location}"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The message chain, span trace and backtrace of an error gathered into one block, see
+/// [`AnyError::render_report`]. `Display` renders it for terminals, `Serialize` for logs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderedReport {
+    /// Every layer and cause, outermost first and causes depth first.
+    pub chain: Vec<ReportEntry>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub help: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<Snippet>,
+    /// Errors that happened alongside the chain without causing it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub related: Vec<ReportEntry>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub span_trace: Option<SpanTrace>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backtrace: Option<Backtrace>,
+}
+
+impl AnyError {
+    /// Builds a report from the chain, using the outermost span trace and backtrace found. An
+    /// error with several [causes](AnyError::causes) lists them depth first, each layer
+    /// followed by its own causes, the same order `Display` writes them in.
+    pub fn render_report(&self) -> RenderedReport {
+        let mut chain = Vec::new();
+        let mut help = Vec::new();
+        let mut related = Vec::new();
+        let mut span_trace = None;
+        let mut backtrace = None;
+
+        let mut stack = vec![self];
+        while let Some(error) = stack.pop() {
+            chain.push(ReportEntry::new(error));
+            help.extend(error.context.details.help.clone());
+            related.extend(error.context.related().iter().map(ReportEntry::new));
+            span_trace = span_trace.or_else(|| error.context.details.span_trace.clone());
+            backtrace = backtrace.or_else(|| error.context.details.backtrace.clone());
+            let len = stack.len();
+            stack.extend(error.causes());
+            stack[len..].reverse();
+        }
+
+        RenderedReport {
+            chain,
+            help,
+            snippet: self.snippet().cloned(),
+            related,
+            span_trace,
+            backtrace,
+        }
+    }
+}
+
+/// One line: the outermost `$type` and the first line of its message, see
+/// [`AnyError::display_short`].
+pub struct DisplayShort<'a>(&'a AnyError);
+
+impl Display for DisplayShort<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = self.0.message().lines().next().unwrap_or_default();
+        write!(f, "{}: {message}", self.0.r#type)
+    }
+}
+
+/// The whole chain with help, span trace and backtrace, see [`AnyError::display_full`].
+pub struct DisplayFull<'a>(&'a AnyError);
+
+impl Display for DisplayFull<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.render_report().fmt(f)
+    }
+}
+
+impl AnyError {
+    /// For log lines and metrics labels where only the kind of failure matters.
+    pub fn display_short(&self) -> DisplayShort<'_> {
+        DisplayShort(self)
+    }
+
+    /// For crash reports and debugging, renders [`AnyError::render_report`].
+    pub fn display_full(&self) -> DisplayFull<'_> {
+        DisplayFull(self)
+    }
+}
+
+impl Display for RenderedReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut entries = self.chain.iter();
+        if let Some(outermost) = entries.next() {
+            writeln!(f, "Error: {}: {}", outermost.r#type, outermost.message)?;
+            outermost.write_location(f)?;
+        }
+
+        if let Some(snippet) = &self.snippet {
+            write!(f, "{snippet}")?;
+        }
+
+        let causes = entries.collect::<Vec<_>>();
+        if !causes.is_empty() {
+            writeln!(f, "\nCaused by:")?;
+            for (index, cause) in causes.iter().enumerate() {
+                writeln!(f, "{index:>4}: {}: {}", cause.r#type, cause.message)?;
+                cause.write_location(f)?;
+            }
+        }
+
+        if !self.related.is_empty() {
+            writeln!(f, "\nRelated:")?;
+            for related in &self.related {
+                writeln!(f, "      {}: {}", related.r#type, related.message)?;
+                related.write_location(f)?;
+            }
+        }
+
+        if !self.help.is_empty() {
+            writeln!(f)?;
+            for help in &self.help {
+                writeln!(f, "help: {help}")?;
+            }
+        }
+
+        if let Some(span_trace) = &self.span_trace {
+            write!(f, "\nSpan trace:\n{span_trace}")?;
+        }
+
+        if let Some(backtrace) = &self.backtrace {
+            write!(f, "\nBacktrace:\n{backtrace}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{backtrace::BacktraceFrame, span_trace::SpanTraceFrame};
+
+    fn sample_error() -> AnyError {
+        let mut root = AnyError::new("DiskError".to_string(), "disk full".to_string(), None);
+        root.context.details.backtrace = Some(Backtrace::new(vec![BacktraceFrame {
+            function: "app::write".to_string(),
+            file: Some("src/write.rs".to_string()),
+            line: Some(3),
+        }]));
+        root.context.details.location = Some(SourceLocation {
+            file: "src/disk.rs".to_string(),
+            line: 40,
+            column: 17,
+        });
+        root.context.details.span_trace = Some(SpanTrace::new(vec![SpanTraceFrame {
+            target: "app".to_string(),
+            name: "save".to_string(),
+            fields: String::new(),
+            file: None,
+            line: None,
+        }]));
+
+        AnyError::new(
+            "SaveError".to_string(),
+            "save failed".to_string(),
+            Some(root),
+        )
+        .with_help("free up disk space")
+    }
+
+    #[test]
+    fn test_render_text() {
+        let rendered = sample_error().render_report().to_string();
+
+        assert_eq!(
+            rendered,
+            "Error: SaveError: save failed\n\
+             \n\
+             Caused by:\n   0: DiskError: disk full\n      at src/disk.rs:40:17\n\
+             \n\
+             help: free up disk space\n\
+             \n\
+             Span trace:\n   0: app::save\n\
+             \n\
+             Backtrace:\n   0: app::write\n             at src/write.rs:3\n"
+        );
+    }
+
+    #[test]
+    fn test_display_modes() {
+        let error = sample_error().with_message("save failed\nwhile writing row 7");
+
+        assert_eq!(error.display_short().to_string(), "SaveError: save failed");
+        assert_eq!(
+            error.display_full().to_string(),
+            error.render_report().to_string()
+        );
+        assert_eq!(
+            error.to_string(),
+            "SaveError: save failed\nwhile writing row 7(DiskError: disk full)"
+        );
+    }
+
+    #[test]
+    fn test_render_aggregate() {
+        let error = AnyError::new("BatchError".to_string(), "2 of 2 failed".to_string(), None)
+            .with_causes([
+                sample_error(),
+                AnyError::new("TimeoutError".to_string(), "timed out".to_string(), None)
+                    .with_help("raise the timeout"),
+            ]);
+        let report = error.render_report();
+
+        assert_eq!(
+            report
+                .chain
+                .iter()
+                .map(|entry| entry.r#type.as_str())
+                .collect::<Vec<_>>(),
+            ["BatchError", "SaveError", "DiskError", "TimeoutError"]
+        );
+        assert_eq!(report.help, ["free up disk space", "raise the timeout"]);
+        assert!(
+            error
+                .display_full()
+                .to_string()
+                .contains("   2: TimeoutError: timed out\n")
+        );
+    }
+
+    #[test]
+    fn test_render_related() {
+        let error = AnyError::msg("insert failed")
+            .with_related(AnyError::msg("rollback failed").with_type("DbError"));
+        let outer = error.context.location().unwrap();
+        let related = error.all_related()[0].context.location().unwrap();
+
+        assert_eq!(outer.file, file!());
+        assert_eq!(
+            error.render_report().to_string(),
+            format!(
+                "Error: Message: insert failed\n      at {outer}\n\n\
+                 Related:\n      DbError: rollback failed\n      at {related}\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_render_snippet() {
+        let error = AnyError::msg("invalid port").with_snippet(Snippet::new(
+            "app.toml",
+            "port = \"eighty\"\n",
+            7..15,
+        ));
+
+        let location = error.context.location().unwrap();
+
+        assert_eq!(
+            error.render_report().to_string(),
+            format!(
+                "Error: Message: invalid port\n      at {location}\n \
+                 --> app.toml:1:8\n  |\n1 | port = \"eighty\"\n  |        ^^^^^^^^\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_render_json() {
+        let json = serde_json::to_value(sample_error().render_report()).unwrap();
+
+        assert_eq!(json["chain"][0]["$type"], "SaveError");
+        assert_eq!(json["chain"][1]["message"], "disk full");
+        assert_eq!(json["chain"][1]["location"]["line"], 40);
+        assert!(json["chain"][0].get("location").is_none());
+        assert_eq!(json["help"][0], "free up disk space");
+        assert_eq!(json["spanTrace"][0]["name"], "save");
+        assert_eq!(json["backtrace"][0]["function"], "app::write");
+    }
+
+    #[test]
+    fn test_render_without_traces() {
+        let error = AnyError::new("PlainError".to_string(), "plain".to_string(), None);
+        let report = error.render_report();
+
+        assert_eq!(report.to_string(), "Error: PlainError: plain\n");
+        assert!(
+            serde_json::to_value(&report)
+                .unwrap()
+                .get("backtrace")
+                .is_none()
+        );
+    }
+}
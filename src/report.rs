@@ -0,0 +1,188 @@
+//! An `std::error::Error` view of an [`AnyError`]. `AnyError` can't implement `Error` itself
+//! without overlapping its blanket `From<E: Error>`, so [`Report`] carries it into APIs that
+//! expect `Box<dyn Error>` or another `Error` type.
+
+use std::{error::Error, fmt};
+
+use crate::{AnyError, IntoAnyError};
+
+/// One layer of an [`AnyError`] chain as an `Error`: `Display` is the layer's message and
+/// `source()` is the next layer, so error reporters walk the same chain. `Debug` renders the
+/// whole chain, which is what `main` prints when it returns an error.
+///
+/// Convert back with [`IntoAnyError::into_any_error`], which is lossless. `AnyError::from`
+/// treats a `Report` like any other error and only keeps the outermost layer's message, though
+/// reports found among the sources of other errors are restored in full.
+pub struct Report {
+    error: AnyError,
+    source: Option<Box<Report>>,
+}
+
+impl Report {
+    /// Builds the reports from the innermost layer out, so deep chains don't recurse.
+    pub fn new(error: AnyError) -> Self {
+        let inner = error.chain().skip(1).cloned().collect::<Vec<_>>();
+        let source = inner.into_iter().rev().fold(None, |source, error| {
+            Some(Box::new(Report { error, source }))
+        });
+        Self { error, source }
+    }
+
+    pub fn as_any_error(&self) -> &AnyError {
+        &self.error
+    }
+}
+
+impl From<AnyError> for Report {
+    fn from(error: AnyError) -> Self {
+        Report::new(error)
+    }
+}
+
+impl IntoAnyError for Report {
+    fn into_any_error(mut self) -> AnyError {
+        std::mem::replace(
+            &mut self.error,
+            AnyError::new(String::new(), String::new(), None),
+        )
+    }
+}
+
+impl Drop for Report {
+    /// Unlinks the sources one at a time, so dropping a deep chain doesn't recurse.
+    fn drop(&mut self) {
+        let mut next = self.source.take();
+        while let Some(mut report) = next {
+            next = report.source.take();
+        }
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.error.message())
+    }
+}
+
+impl fmt::Debug for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.error, f)
+    }
+}
+
+impl Error for Report {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source
+            .as_deref()
+            .map(|source| source as &(dyn Error + 'static))
+    }
+}
+
+impl From<AnyError> for Box<dyn Error + Send + Sync> {
+    fn from(error: AnyError) -> Self {
+        Box::new(Report::new(error))
+    }
+}
+
+impl From<AnyError> for Box<dyn Error> {
+    fn from(error: AnyError) -> Self {
+        Box::new(Report::new(error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain() -> AnyError {
+        let mut error = AnyError::msg("connection refused").with_help("is the server up?");
+        error.wrap_with("loading user 42");
+        error
+    }
+
+    #[test]
+    fn test_source_chain() {
+        let report = Report::new(chain());
+
+        assert_eq!(report.to_string(), "loading user 42");
+        let source = report.source().unwrap();
+        assert_eq!(source.to_string(), "connection refused");
+        assert!(source.source().is_none());
+        assert_eq!(format!("{report:?}"), chain().to_string());
+    }
+
+    #[test]
+    fn test_question_mark_into_box_dyn_error() {
+        fn load() -> Result<(), Box<dyn Error + Send + Sync>> {
+            Err(chain())?;
+            Ok(())
+        }
+
+        let error = load().unwrap_err();
+        let report = error.downcast_ref::<Report>().unwrap();
+        assert_eq!(
+            report.as_any_error().chain_messages(),
+            chain().chain_messages()
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let error = Report::from(chain()).into_any_error();
+        assert_eq!(error.chain_messages(), chain().chain_messages());
+        assert_eq!(error.help(), None);
+        assert_eq!(error.inner().unwrap().help(), Some("is the server up?"));
+    }
+
+    #[test]
+    fn test_deep_chain_without_recursion() {
+        const DEPTH: usize = 10_000;
+
+        let mut error = AnyError::from_parts("Root", "attempt 0", None);
+        for index in 1..DEPTH {
+            error = AnyError::from_parts("Retry", format!("attempt {index}"), Some(error));
+        }
+
+        // Far too small a stack for any per-layer recursion.
+        std::thread::Builder::new()
+            .stack_size(256 * 1024)
+            .spawn(move || {
+                let report = Report::new(error);
+                let sources = std::iter::successors(report.source(), |&source| source.source());
+                assert_eq!(sources.count(), DEPTH - 1);
+                assert_eq!(report.into_any_error().depth(), DEPTH);
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_nested_report_restored() {
+        #[derive(Debug)]
+        struct LoadError(Report);
+
+        impl fmt::Display for LoadError {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "load failed")
+            }
+        }
+
+        impl Error for LoadError {
+            fn source(&self) -> Option<&(dyn Error + 'static)> {
+                Some(&self.0)
+            }
+        }
+
+        let error = AnyError::from(LoadError(Report::new(chain())));
+        assert_eq!(
+            error.chain_messages(),
+            ["load failed", "loading user 42", "connection refused"]
+        );
+        assert_eq!(error.inner().unwrap().r#type, crate::CONTEXT_TYPE);
+        assert_eq!(
+            error.inner().unwrap().inner().unwrap().help(),
+            Some("is the server up?")
+        );
+    }
+}
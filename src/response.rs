@@ -0,0 +1,207 @@
+use serde::{Deserialize, Serialize};
+
+use std::time::SystemTime;
+
+use crate::{AnyError, retry};
+
+pub const JSON_CONTENT_TYPE: &str = "application/json";
+pub const PROBLEM_JSON_CONTENT_TYPE: &str = "application/problem+json";
+
+/// The body written by [`AnyError::to_http_response_with`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BodyFormat {
+    /// The serialized `AnyError`.
+    #[default]
+    Json,
+    /// An RFC 9457 problem details document.
+    ProblemJson,
+}
+
+/// RFC 9457 problem details for an error, see [`AnyError::to_problem_details`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProblemDetails {
+    /// The docs URL of the error, or `about:blank`.
+    pub r#type: String,
+    /// The `$type` tag of the error.
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+}
+
+impl AnyError {
+    pub fn to_problem_details(&self) -> ProblemDetails {
+        ProblemDetails {
+            r#type: self.docs_url().unwrap_or("about:blank").to_string(),
+            title: self.r#type.clone(),
+            status: self.http_status(),
+            detail: self.message().to_string(),
+        }
+    }
+
+    /// A JSON response with the status from the global [`CodeMapping`](crate::mapping::CodeMapping).
+    pub fn to_http_response(&self) -> http::Response<Vec<u8>> {
+        self.to_http_response_with(BodyFormat::Json)
+    }
+
+    /// The body is built from [`AnyError::to_public`], so traces, locations, metadata and
+    /// masked types don't reach the client.
+    pub fn to_http_response_with(&self, format: BodyFormat) -> http::Response<Vec<u8>> {
+        let status = http::StatusCode::from_u16(self.http_status())
+            .unwrap_or(http::StatusCode::INTERNAL_SERVER_ERROR);
+        let public = self.to_public();
+        let (content_type, body) = match format {
+            BodyFormat::Json => (JSON_CONTENT_TYPE, serde_json::to_vec(&public)),
+            BodyFormat::ProblemJson => (
+                PROBLEM_JSON_CONTENT_TYPE,
+                serde_json::to_vec(&public.to_problem_details()),
+            ),
+        };
+
+        let mut response = http::Response::new(body.unwrap_or_default());
+        *response.status_mut() = status;
+        response.headers_mut().insert(
+            http::header::CONTENT_TYPE,
+            http::HeaderValue::from_static(content_type),
+        );
+        response
+    }
+
+    /// Reads the error body of an upstream response with [`AnyError::from_http_body`], for
+    /// stitching with [`AnyError::caused_by_remote`]. `None` for successful responses.
+    pub fn from_remote_response<B: AsRef<[u8]>>(response: &http::Response<B>) -> Option<Self> {
+        if response.status().is_success() {
+            return None;
+        }
+        let content_type = response
+            .headers()
+            .get(http::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok());
+        let error = Self::from_http_body(
+            response.status().as_u16(),
+            content_type,
+            response.body().as_ref(),
+            None,
+        );
+        let retry_after = response
+            .headers()
+            .get(http::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| retry::parse_retry_after(value, SystemTime::now()));
+        Some(match retry_after {
+            Some(retry_after) => error.with_retry_after(retry_after),
+            None => error,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::{self, GrpcCode, ProtocolCodes};
+    use crate::mask::{MASKED_MESSAGE, MASKED_TYPE};
+
+    #[test]
+    fn test_json_response() {
+        mapping::register(
+            "app.response.not_found",
+            ProtocolCodes::new(1, 404, GrpcCode::NotFound),
+        );
+        let error = AnyError::msg("no such user").with_type("app.response.not_found");
+        let response = error.to_http_response();
+
+        assert_eq!(response.status(), http::StatusCode::NOT_FOUND);
+        assert_eq!(
+            response.headers()[http::header::CONTENT_TYPE],
+            JSON_CONTENT_TYPE
+        );
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(body["$type"], "app.response.not_found");
+        assert_eq!(body["context"]["message"], "no such user");
+    }
+
+    #[test]
+    fn test_response_body_is_public() {
+        let mut error = AnyError::msg("no such user")
+            .with_data("userId", 42)
+            .with_extension("query", "SELECT * FROM users");
+        error.wrap_with("loading profile");
+        let response = error.to_http_response();
+
+        let body = String::from_utf8(response.body().clone()).unwrap();
+        for internal in [
+            "backtrace",
+            "location",
+            "data",
+            "userId",
+            "SELECT",
+            "no such user",
+            "loading profile",
+        ] {
+            assert!(!body.contains(internal), "{internal} leaked into {body}");
+        }
+        assert!(body.contains(MASKED_MESSAGE));
+    }
+
+    #[test]
+    fn test_masks_internal_types_by_default() {
+        let error = AnyError::msg("relation \"users\" does not exist")
+            .with_type("sqlx.postgres.PgDatabaseError");
+        let body = String::from_utf8(error.to_http_response().into_body()).unwrap();
+
+        assert!(!body.contains("sqlx"), "{body}");
+        assert!(!body.contains("users"), "{body}");
+    }
+
+    #[test]
+    fn test_from_remote_response() {
+        let upstream = AnyError::msg("no such account");
+        let response = upstream.to_http_response();
+
+        let error = AnyError::msg("lookup failed")
+            .caused_by_remote(AnyError::from_remote_response(&response).unwrap());
+        assert!(error.inner().unwrap().context.is_remote());
+        assert_eq!(error.inner().unwrap().message(), MASKED_MESSAGE);
+        assert_eq!(
+            error.extension("status"),
+            Some(&serde_json::json!(upstream.http_status()))
+        );
+
+        assert_eq!(error.retry_after(), None);
+
+        let mut throttled = AnyError::msg("slow down").to_http_response();
+        *throttled.status_mut() = http::StatusCode::TOO_MANY_REQUESTS;
+        throttled.headers_mut().insert(
+            http::header::RETRY_AFTER,
+            http::HeaderValue::from_static("7"),
+        );
+        let error = AnyError::from_remote_response(&throttled).unwrap();
+        assert_eq!(error.retry_after(), Some(std::time::Duration::from_secs(7)));
+        assert_eq!(error.retryable(), Some(true));
+
+        let ok = http::Response::new(serde_json::to_vec(&upstream).unwrap());
+        assert!(AnyError::from_remote_response(&ok).is_none());
+    }
+
+    #[test]
+    fn test_problem_json_response() {
+        let error = AnyError::msg("boom").with_docs_url("https://docs.example.com/boom");
+        let response = error.to_http_response_with(BodyFormat::ProblemJson);
+
+        assert_eq!(response.status(), http::StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(
+            response.headers()[http::header::CONTENT_TYPE],
+            PROBLEM_JSON_CONTENT_TYPE
+        );
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "type": "about:blank",
+                "title": MASKED_TYPE,
+                "status": 500,
+                "detail": MASKED_MESSAGE,
+            })
+        );
+    }
+}
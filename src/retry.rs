@@ -0,0 +1,407 @@
+use std::time::{Duration, SystemTime};
+
+use crate::AnyError;
+
+impl AnyError {
+    /// Marks whether retrying the failed operation may succeed.
+    pub fn with_retryable(mut self, retryable: bool) -> Self {
+        self.context.details.retryable = Some(retryable);
+        self
+    }
+
+    /// Records how long the server asked clients to wait before retrying.
+    pub fn with_retry_after(mut self, retry_after: Duration) -> Self {
+        self.context.details.retry_after = Some(retry_after);
+        self
+    }
+
+    /// Whether the failed operation may be retried, taken from the outermost layer that says
+    /// so. A layer with a [`retry_after`](AnyError::retry_after) hint counts as retryable.
+    /// `None` means no layer classified the error.
+    pub fn retryable(&self) -> Option<bool> {
+        let mut node = Some(self);
+        while let Some(error) = node {
+            if let Some(retryable) = error.context.details.retryable {
+                return Some(retryable);
+            }
+            if error.context.details.retry_after.is_some() {
+                return Some(true);
+            }
+            node = error.inner();
+        }
+        None
+    }
+
+    /// The server's backoff hint, taken from the outermost layer that has one.
+    pub fn retry_after(&self) -> Option<Duration> {
+        let mut node = Some(self);
+        while let Some(error) = node {
+            if let Some(retry_after) = error.context.details.retry_after {
+                return Some(retry_after);
+            }
+            node = error.inner();
+        }
+        None
+    }
+}
+
+/// The longest delay taken from an upstream's hint, longer ones are cut to it. Hints come from
+/// untrusted servers, and a client shouldn't wait a day to retry anyway.
+pub const MAX_RETRY_AFTER: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Parses a `Retry-After` header value, either delay seconds or an HTTP date. Dates in the
+/// past give a zero delay, delays over [`MAX_RETRY_AFTER`] are cut to it.
+pub fn parse_retry_after(value: &str, now: SystemTime) -> Option<Duration> {
+    let value = value.trim();
+    let delay = match value.parse::<u64>() {
+        Ok(seconds) => Duration::from_secs(seconds),
+        Err(_) => parse_http_date(value)?
+            .duration_since(now)
+            .unwrap_or_default(),
+    };
+    Some(delay.min(MAX_RETRY_AFTER))
+}
+
+/// Parses the IMF-fixdate form of an HTTP date, `Sun, 06 Nov 1994 08:49:37 GMT`. Headers come
+/// from untrusted upstreams, so every field is range checked and nothing can overflow.
+fn parse_http_date(value: &str) -> Option<SystemTime> {
+    let (_, date) = value.split_once(", ")?;
+    let mut parts = date.split(' ');
+    let day = parts.next()?.parse::<u64>().ok()?;
+    let month = match parts.next()? {
+        "Jan" => 1,
+        "Feb" => 2,
+        "Mar" => 3,
+        "Apr" => 4,
+        "May" => 5,
+        "Jun" => 6,
+        "Jul" => 7,
+        "Aug" => 8,
+        "Sep" => 9,
+        "Oct" => 10,
+        "Nov" => 11,
+        "Dec" => 12,
+        _ => return None,
+    };
+    let year = parts.next()?.parse::<u64>().ok()?;
+    let mut time = parts
+        .next()?
+        .split(':')
+        .map(|part| part.parse::<u64>().ok());
+    let (hour, minute, second) = (time.next()??, time.next()??, time.next()??);
+    if parts.next()? != "GMT" || time.next().is_some() || parts.next().is_some() {
+        return None;
+    }
+    if !(1970..=9999).contains(&year)
+        || !(1..=days_in_month(year, month)).contains(&day)
+        || hour >= 24
+        || minute >= 60
+        || second >= 61
+    {
+        return None;
+    }
+
+    // Days since the epoch of a proleptic Gregorian date, shifted to start years in March.
+    let (year, month) = if month <= 2 {
+        (year - 1, month + 9)
+    } else {
+        (year, month - 3)
+    };
+    let era = year / 400;
+    let year_of_era = year % 400;
+    let day_of_year = (153 * month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era
+        .checked_mul(365)?
+        .checked_add(year_of_era / 4)?
+        .checked_sub(year_of_era / 100)?
+        .checked_add(day_of_year)?;
+    let days = era
+        .checked_mul(146_097)?
+        .checked_add(day_of_era)?
+        .checked_sub(719_468)?;
+
+    let seconds = days
+        .checked_mul(86_400)?
+        .checked_add(hour * 3_600 + minute * 60 + second)?;
+    SystemTime::UNIX_EPOCH.checked_add(Duration::from_secs(seconds))
+}
+
+fn days_in_month(year: u64, month: u64) -> u64 {
+    match month {
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => {
+            29
+        }
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// The `type_url` of a `google.rpc.RetryInfo` status detail.
+const RETRY_INFO_TYPE_URL: &str = "type.googleapis.com/google.rpc.RetryInfo";
+
+/// Reads the delay of a `google.rpc.RetryInfo` detail out of a serialized `google.rpc.Status`,
+/// the (base64 decoded) `grpc-status-details-bin` trailer or tonic's `Status::details()`.
+/// `None` if there is no such detail or the bytes are malformed. Delays over
+/// [`MAX_RETRY_AFTER`] are cut to it. [`AnyError::from_grpc_status`] applies it.
+pub fn parse_grpc_retry_info(status_details: &[u8]) -> Option<Duration> {
+    use protobuf::{Field, bytes_field, fields};
+
+    // google.rpc.Status { repeated google.protobuf.Any details = 3; }
+    // google.protobuf.Any { string type_url = 1; bytes value = 2; }
+    let retry_info = fields(status_details)?
+        .into_iter()
+        .filter_map(|(number, field)| match field {
+            Field::Bytes(any) if number == 3 => fields(any),
+            _ => None,
+        })
+        .find(|any| bytes_field(any, 1) == Some(RETRY_INFO_TYPE_URL.as_bytes()))?;
+
+    // google.rpc.RetryInfo { google.protobuf.Duration retry_delay = 1; }
+    let retry_info = fields(bytes_field(&retry_info, 2)?)?;
+    let delay = bytes_field(&retry_info, 1)?;
+
+    // google.protobuf.Duration { int64 seconds = 1; int32 nanos = 2; }
+    let (mut seconds, mut nanos) = (0, 0);
+    for (number, field) in fields(delay)? {
+        match (number, field) {
+            (1, Field::Varint(value)) => seconds = value,
+            (2, Field::Varint(value)) => nanos = value,
+            _ => {}
+        }
+    }
+    // Negative values are encoded as large varints.
+    if i64::try_from(seconds).is_err() || nanos >= 1_000_000_000 {
+        return None;
+    }
+    Some(Duration::new(seconds, nanos as u32).min(MAX_RETRY_AFTER))
+}
+
+/// Just enough of the protobuf wire format to read gRPC status details.
+mod protobuf {
+    pub enum Field<'a> {
+        Varint(u64),
+        Bytes(&'a [u8]),
+    }
+
+    fn varint(bytes: &mut &[u8]) -> Option<u64> {
+        let mut value = 0;
+        for shift in (0..64).step_by(7) {
+            let (&byte, rest) = bytes.split_first()?;
+            *bytes = rest;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    fn skip<'a>(bytes: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+        let (skipped, rest) = bytes.split_at_checked(len)?;
+        *bytes = rest;
+        Some(skipped)
+    }
+
+    /// The first length-delimited field `number`.
+    pub fn bytes_field<'a>(fields: &[(u64, Field<'a>)], number: u64) -> Option<&'a [u8]> {
+        fields.iter().find_map(|(field_number, field)| match field {
+            Field::Bytes(value) if *field_number == number => Some(*value),
+            _ => None,
+        })
+    }
+
+    /// The `(number, value)` pairs of a message. Fixed-width fields are skipped.
+    pub fn fields(mut bytes: &[u8]) -> Option<Vec<(u64, Field<'_>)>> {
+        let mut fields = Vec::new();
+        while !bytes.is_empty() {
+            let key = varint(&mut bytes)?;
+            let field = match key & 7 {
+                0 => Field::Varint(varint(&mut bytes)?),
+                1 => {
+                    skip(&mut bytes, 8)?;
+                    continue;
+                }
+                2 => {
+                    let len = usize::try_from(varint(&mut bytes)?).ok()?;
+                    Field::Bytes(skip(&mut bytes, len)?)
+                }
+                5 => {
+                    skip(&mut bytes, 4)?;
+                    continue;
+                }
+                _ => return None,
+            };
+            fields.push((key >> 3, field));
+        }
+        Some(fields)
+    }
+}
+
+/// Serializes an optional duration as whole milliseconds.
+pub(crate) mod millis {
+    use std::time::Duration;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        value: &Option<Duration>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(duration) => {
+                serializer.serialize_some(&u64::try_from(duration.as_millis()).unwrap_or(u64::MAX))
+            }
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Duration>, D::Error> {
+        Ok(Option::<u64>::deserialize(deserializer)?.map(Duration::from_millis))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retryable_from_chain() {
+        let mut error = AnyError::msg("connection reset").with_retryable(true);
+        error.wrap_with("while saving");
+
+        assert_eq!(error.retryable(), Some(true));
+        assert_eq!(AnyError::msg("plain").retryable(), None);
+        assert_eq!(
+            serde_json::to_value(&error).unwrap()["context"]["innerError"]["context"]["retryable"],
+            true
+        );
+    }
+
+    #[test]
+    fn test_retry_after() {
+        let mut error = AnyError::msg("slow down").with_retry_after(Duration::from_millis(1500));
+        error.wrap_with("calling billing");
+
+        assert_eq!(error.retry_after(), Some(Duration::from_millis(1500)));
+        assert_eq!(error.retryable(), Some(true));
+        assert_eq!(
+            serde_json::to_value(&error).unwrap()["context"]["innerError"]["context"]["retryAfterMs"],
+            1500
+        );
+        assert_eq!(
+            AnyError::msg("quota exhausted")
+                .with_retry_after(Duration::from_secs(60))
+                .with_retryable(false)
+                .retryable(),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(784_111_747);
+
+        assert_eq!(
+            parse_retry_after("120", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after("Sun, 06 Nov 1994 08:49:37 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            parse_retry_after("Sat, 05 Nov 1994 08:49:37 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(
+            parse_http_date("Thu, 29 Feb 2024 00:00:00 GMT"),
+            Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_709_164_800))
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+        assert_eq!(parse_retry_after("-5", now), None);
+    }
+
+    #[test]
+    fn test_parse_http_date_hostile() {
+        let now = SystemTime::UNIX_EPOCH;
+        for value in [
+            "Sun, 06 Nov 1994 99999999999999999:00:00 GMT",
+            "Sun, 06 Nov 1994 24:00:00 GMT",
+            "Sun, 06 Nov 1994 08:60:00 GMT",
+            "Sun, 06 Nov 1994 08:00:61 GMT",
+            "Sun, 06 Nov 500000000000 08:49:37 GMT",
+            "Sun, 06 Nov 18446744073709551615 08:49:37 GMT",
+            "Sun, 31 Apr 2024 08:49:37 GMT",
+            "Sun, 29 Feb 2023 08:49:37 GMT",
+            "Sun, 00 Nov 1994 08:49:37 GMT",
+            "Sun, 99999999999999999999 Nov 1994 08:49:37 GMT",
+            "Sun, 06 Nov 1994 08:49:37:00 GMT",
+            "Sun, 06 Nov 1994 08:49:37 GMT extra",
+        ] {
+            assert_eq!(parse_retry_after(value, now), None, "{value}");
+        }
+        assert!(parse_http_date("Fri, 31 Dec 9999 23:59:60 GMT").is_some());
+        assert_eq!(
+            parse_retry_after("18446744073709551615", now),
+            Some(MAX_RETRY_AFTER)
+        );
+        assert_eq!(
+            parse_retry_after("Fri, 31 Dec 9999 23:59:59 GMT", now),
+            Some(MAX_RETRY_AFTER)
+        );
+    }
+
+    #[test]
+    fn test_serialize_huge_delay() {
+        let error = AnyError::msg("later").with_retry_after(Duration::MAX);
+        assert_eq!(
+            serde_json::to_value(&error).unwrap()["context"]["retryAfterMs"],
+            u64::MAX
+        );
+    }
+
+    /// A length-delimited field, for values shorter than 128 bytes.
+    fn field(number: u8, value: &[u8]) -> Vec<u8> {
+        [&[number << 3 | 2, value.len() as u8], value].concat()
+    }
+
+    fn any(type_url: &str, value: &[u8]) -> Vec<u8> {
+        [field(1, type_url.as_bytes()), field(2, value)].concat()
+    }
+
+    #[test]
+    fn test_parse_grpc_retry_info() {
+        // Duration { seconds: 3, nanos: 500_000_000 }
+        let delay = [0x08, 0x03, 0x10, 0x80, 0xca, 0xb5, 0xee, 0x01];
+        let status = [
+            vec![0x08, 0x08],
+            field(2, b"slow down"),
+            field(
+                3,
+                &any(
+                    "type.googleapis.com/google.rpc.ErrorInfo",
+                    &field(1, b"RATE_LIMITED"),
+                ),
+            ),
+            field(3, &any(RETRY_INFO_TYPE_URL, &field(1, &delay))),
+        ]
+        .concat();
+
+        assert_eq!(
+            parse_grpc_retry_info(&status),
+            Some(Duration::from_millis(3500))
+        );
+        assert_eq!(parse_grpc_retry_info(&status[..status.len() - 1]), None);
+        assert_eq!(parse_grpc_retry_info(&[0x08, 0x08]), None);
+        assert_eq!(parse_grpc_retry_info(&[0xff; 16]), None);
+
+        let negative = [
+            0x08, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01,
+        ];
+        let status = field(3, &any(RETRY_INFO_TYPE_URL, &field(1, &negative)));
+        assert_eq!(parse_grpc_retry_info(&status), None);
+    }
+}
@@ -0,0 +1,196 @@
+use std::{
+    cell::{Cell, RefCell},
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::AnyError;
+
+#[cfg(feature = "tokio")]
+tokio::task_local! {
+    static TASK_SCOPES: Vec<String>;
+}
+
+thread_local! {
+    static SCOPES: RefCell<Vec<(u64, String)>> = const { RefCell::new(Vec::new()) };
+    static NEXT_ID: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Keeps a context message active on the current thread, see [`context_scope`].
+#[must_use = "the scope ends when the guard is dropped"]
+pub struct ContextScope {
+    id: u64,
+    // Scopes live in a thread-local, so the guard must stay on the thread that created it.
+    _not_send: PhantomData<*const ()>,
+}
+
+/// Until the returned guard is dropped, every `AnyError` converted on this thread through
+/// `From` gains `message` as an extra context layer. Nested scopes stack, the outermost scope
+/// becoming the outermost layer.
+///
+/// Thread-locals don't follow a future across `.await` points on a multi-threaded runtime; use
+/// [`scoped`] for async code.
+pub fn context_scope(message: impl Into<String>) -> ContextScope {
+    let id = NEXT_ID.with(|next| {
+        let id = next.get();
+        next.set(id + 1);
+        id
+    });
+    SCOPES.with(|scopes| scopes.borrow_mut().push((id, message.into())));
+    ContextScope {
+        id,
+        _not_send: PhantomData,
+    }
+}
+
+impl Drop for ContextScope {
+    fn drop(&mut self) {
+        SCOPES.with(|scopes| scopes.borrow_mut().retain(|(id, _)| *id != self.id));
+    }
+}
+
+/// Runs `future` with `message` as a context scope, entered for each poll so it applies on
+/// whichever thread the future is polled.
+pub fn scoped<F: Future>(message: impl Into<String>, future: F) -> Scoped<F> {
+    Scoped {
+        message: message.into(),
+        future: Box::pin(future),
+    }
+}
+
+/// Future returned by [`scoped`].
+pub struct Scoped<F> {
+    message: String,
+    future: Pin<Box<F>>,
+}
+
+impl<F: Future> Future for Scoped<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let _scope = context_scope(self.message.clone());
+        self.future.as_mut().poll(cx)
+    }
+}
+
+/// Runs `future` with `message` stored in a tokio task-local, so every `AnyError` converted
+/// inside it gains `message` as a context layer, across `.await` points and worker threads.
+/// Nested calls stack like [`context_scope`], task scopes enclosing thread scopes.
+#[cfg(feature = "tokio")]
+pub fn with_error_context<F: Future>(
+    message: impl Into<String>,
+    future: F,
+) -> impl Future<Output = F::Output> {
+    let mut scopes = TASK_SCOPES.try_with(Clone::clone).unwrap_or_default();
+    scopes.push(message.into());
+    TASK_SCOPES.scope(scopes, future)
+}
+
+/// Wraps a freshly converted error in the active scopes.
+pub(crate) fn apply(error: &mut AnyError) {
+    SCOPES.with(|scopes| {
+        for (_, message) in scopes.borrow().iter().rev() {
+            error.wrap_with(message.clone());
+        }
+    });
+
+    #[cfg(feature = "tokio")]
+    let _ = TASK_SCOPES.try_with(|scopes| {
+        for message in scopes.iter().rev() {
+            error.wrap_with(message.clone());
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fmt, io, task::Waker};
+
+    use super::*;
+
+    fn chain(error: &AnyError) -> Vec<String> {
+        let mut messages = Vec::new();
+        let mut node = Some(error);
+        while let Some(error) = node {
+            messages.push(error.message().to_string());
+            node = error.inner();
+        }
+        messages
+    }
+
+    #[test]
+    fn test_nested_scopes() {
+        let error = {
+            let _tenant = context_scope("syncing tenant 42");
+            let _user = context_scope("loading user 7");
+            AnyError::from(io::Error::other("timed out"))
+        };
+
+        assert_eq!(
+            chain(&error),
+            vec!["syncing tenant 42", "loading user 7", "timed out"]
+        );
+        assert_eq!(
+            chain(&AnyError::from(io::Error::other("after"))),
+            vec!["after"]
+        );
+    }
+
+    #[test]
+    fn test_out_of_order_drop() {
+        let outer = context_scope("outer");
+        let inner = context_scope("inner");
+        drop(outer);
+
+        let error = AnyError::from(io::Error::other("boom"));
+        drop(inner);
+
+        assert_eq!(chain(&error), vec!["inner", "boom"]);
+    }
+
+    #[derive(Debug)]
+    struct Noop;
+
+    impl fmt::Display for Noop {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "noop")
+        }
+    }
+
+    impl std::error::Error for Noop {}
+
+    #[test]
+    fn test_scoped_future() {
+        let mut future = scoped("handling request", async { AnyError::from(Noop) });
+
+        let Poll::Ready(error) =
+            Pin::new(&mut future).poll(&mut Context::from_waker(Waker::noop()))
+        else {
+            panic!("future should complete in one poll");
+        };
+        assert_eq!(chain(&error), vec!["handling request", "noop"]);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_task_context_survives_await() {
+        let error = with_error_context("syncing tenant 42", async {
+            with_error_context("page 3", async {
+                tokio::task::yield_now().await;
+                tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+                let _scope = context_scope("row 17");
+                AnyError::from(Noop)
+            })
+            .await
+        })
+        .await;
+
+        assert_eq!(
+            chain(&error),
+            vec!["syncing tenant 42", "page 3", "row 17", "noop"]
+        );
+        assert_eq!(chain(&AnyError::from(Noop)), vec!["noop"]);
+    }
+}
@@ -0,0 +1,132 @@
+use std::error::Error;
+
+use serde::{Deserialize, Serialize};
+
+use crate::AnyError;
+
+/// How serious an error is, for log pipelines to route and alert on. Ordered from least to
+/// most severe.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    valuable::Valuable,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum Severity {
+    Debug,
+    Info,
+    Warning,
+    Error,
+    /// Needs someone's attention now.
+    Critical,
+}
+
+impl Severity {
+    /// The RFC 5424 severity, also used as the journald `PRIORITY`.
+    pub fn syslog_severity(self) -> u8 {
+        match self {
+            Severity::Debug => 7,
+            Severity::Info => 6,
+            Severity::Warning => 4,
+            Severity::Error => 3,
+            Severity::Critical => 2,
+        }
+    }
+}
+
+/// Implemented by error types that know how serious each of their values is, see
+/// [`AnyError::from_with_severity`].
+pub trait ErrorSeverity {
+    fn severity(&self) -> Severity;
+}
+
+impl AnyError {
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.context.details.severity = Some(severity);
+        self
+    }
+
+    /// The severity of the outermost layer that has one.
+    pub fn severity(&self) -> Option<Severity> {
+        let mut node = Some(self);
+        while let Some(error) = node {
+            if let Some(severity) = error.context.details.severity {
+                return Some(severity);
+            }
+            node = error.inner();
+        }
+        None
+    }
+
+    /// Converts `value` like `AnyError::from`, recording its [`ErrorSeverity`].
+    #[track_caller]
+    pub fn from_with_severity<E: Error + ErrorSeverity>(value: E) -> Self {
+        let severity = value.severity();
+        AnyError::from(value).with_severity(severity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt;
+
+    use super::*;
+
+    #[derive(Debug)]
+    enum CacheError {
+        Miss,
+        Corrupted,
+    }
+
+    impl fmt::Display for CacheError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                CacheError::Miss => write!(f, "cache miss"),
+                CacheError::Corrupted => write!(f, "cache corrupted"),
+            }
+        }
+    }
+
+    impl Error for CacheError {}
+
+    impl ErrorSeverity for CacheError {
+        fn severity(&self) -> Severity {
+            match self {
+                CacheError::Miss => Severity::Debug,
+                CacheError::Corrupted => Severity::Critical,
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_with_severity() {
+        assert_eq!(
+            AnyError::from_with_severity(CacheError::Miss).severity(),
+            Some(Severity::Debug)
+        );
+
+        let mut error = AnyError::from_with_severity(CacheError::Corrupted);
+        error.wrap_with("loading session");
+        assert_eq!(error.severity(), Some(Severity::Critical));
+        assert_eq!(AnyError::msg("plain").severity(), None);
+    }
+
+    #[test]
+    fn test_serialization_and_order() {
+        let error = AnyError::msg("disk nearly full").with_severity(Severity::Warning);
+        let json = serde_json::to_value(&error).unwrap();
+
+        assert_eq!(json["context"]["severity"], "warning");
+        let round_trip: AnyError = serde_json::from_value(json).unwrap();
+        assert_eq!(round_trip.severity(), Some(Severity::Warning));
+        assert!(Severity::Warning < Severity::Error && Severity::Error < Severity::Critical);
+    }
+}
@@ -0,0 +1,146 @@
+//! Destinations for reported errors. Register sinks once at startup with [`register`], then
+//! call [`AnyError::report`] wherever an error is handled rather than propagated.
+
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    path::Path,
+    sync::{Arc, LazyLock, Mutex, RwLock},
+};
+
+use crate::AnyError;
+
+/// Receives reported errors. Implementations shouldn't block for long, since reporting runs on
+/// the caller's thread; queue the error instead if delivery is slow.
+pub trait ErrorSink: Send + Sync {
+    fn report(&self, error: &AnyError);
+}
+
+impl<S: ErrorSink + ?Sized> ErrorSink for Arc<S> {
+    fn report(&self, error: &AnyError) {
+        (**self).report(error)
+    }
+}
+
+impl<S: ErrorSink + ?Sized> ErrorSink for Box<S> {
+    fn report(&self, error: &AnyError) {
+        (**self).report(error)
+    }
+}
+
+/// Prints errors as `Error: {:#}`, like [`Exit`](crate::exit::Exit).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StderrSink;
+
+impl ErrorSink for StderrSink {
+    fn report(&self, error: &AnyError) {
+        eprintln!("Error: {error:#}");
+    }
+}
+
+/// Appends each error to a file as one line of JSON.
+#[derive(Debug)]
+pub struct FileSink {
+    file: Mutex<File>,
+}
+
+impl FileSink {
+    /// Opens `path` for appending, creating it if needed.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+}
+
+impl ErrorSink for FileSink {
+    fn report(&self, error: &AnyError) {
+        let Ok(mut line) = serde_json::to_vec(error) else {
+            return;
+        };
+        line.push(b'\n');
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        let _ = file.write_all(&line);
+    }
+}
+
+/// Calls a closure with each error.
+pub struct CallbackSink<F>(F);
+
+impl<F: Fn(&AnyError) + Send + Sync> CallbackSink<F> {
+    pub fn new(callback: F) -> Self {
+        Self(callback)
+    }
+}
+
+impl<F: Fn(&AnyError) + Send + Sync> ErrorSink for CallbackSink<F> {
+    fn report(&self, error: &AnyError) {
+        (self.0)(error)
+    }
+}
+
+static SINKS: LazyLock<RwLock<Vec<Arc<dyn ErrorSink>>>> = LazyLock::new(Default::default);
+
+/// Adds a sink that every [`AnyError::report`] call dispatches to.
+pub fn register(sink: impl ErrorSink + 'static) {
+    SINKS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(Arc::new(sink));
+}
+
+/// Removes every registered sink.
+pub fn clear() {
+    SINKS.write().unwrap_or_else(|e| e.into_inner()).clear();
+}
+
+impl AnyError {
+    /// Sends the error to every registered sink.
+    pub fn report(&self) {
+        let sinks = SINKS.read().unwrap_or_else(|e| e.into_inner()).clone();
+        for sink in sinks {
+            sink.report(self);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[test]
+    fn test_report_dispatches_to_registered_sinks() {
+        static REPORTED: AtomicUsize = AtomicUsize::new(0);
+        register(CallbackSink::new(|error: &AnyError| {
+            if error.r#type == "sink.test" {
+                REPORTED.fetch_add(1, Ordering::SeqCst);
+            }
+        }));
+
+        let error = AnyError::msg("boom").with_type("sink.test");
+        error.report();
+        error.report();
+
+        assert_eq!(REPORTED.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_file_sink() {
+        let path = std::env::temp_dir().join(format!("liberror-sink-{}.jsonl", std::process::id()));
+        let sink = FileSink::open(&path).unwrap();
+        sink.report(&AnyError::msg("first"));
+        sink.report(&AnyError::msg("second"));
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let messages = contents
+            .lines()
+            .map(|line| serde_json::from_str::<AnyError>(line).unwrap())
+            .map(|error| error.message().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(messages, ["first", "second"]);
+    }
+}
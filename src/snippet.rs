@@ -0,0 +1,356 @@
+use std::{fmt::Display, ops::Range};
+
+use serde::{Deserialize, Serialize};
+
+use crate::AnyError;
+
+/// A byte range in a source, `start..start + len`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, valuable::Valuable)]
+#[serde(rename_all = "camelCase")]
+pub struct Span {
+    pub start: usize,
+    pub len: usize,
+}
+
+impl Span {
+    pub fn end(&self) -> usize {
+        self.start.saturating_add(self.len)
+    }
+}
+
+/// A secondary span pointing at related source, e.g. "defined here".
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, valuable::Valuable)]
+#[serde(rename_all = "camelCase")]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+}
+
+/// The lines of a source around a span, for parser and config errors. `{}` renders them with
+/// the span underlined by `^` and any labels by `-`. Snippets may come from another process,
+/// so spans outside `text` or off its character boundaries are rendered without underline.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, valuable::Valuable)]
+#[serde(rename_all = "camelCase")]
+pub struct Snippet {
+    /// A file name or other label for the source.
+    pub source_name: String,
+    /// Offsets into the whole source.
+    pub span: Span,
+    /// The full lines the span touches.
+    pub text: String,
+    /// Offset of `text` in the source.
+    pub text_offset: usize,
+    /// 1-based line number of the first line of `text`.
+    pub line: usize,
+    /// Shown next to the primary span's underline, e.g. "expected a number".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<Label>,
+}
+
+impl Snippet {
+    /// Cuts the lines containing `span` out of `source`. The span is clamped to the source
+    /// and to character boundaries.
+    pub fn new(source_name: impl Into<String>, source: &str, span: Range<usize>) -> Self {
+        let span = clamp(source, span);
+        let (text_offset, text_end) = line_bounds(source, span.start, span.end());
+
+        Self {
+            source_name: source_name.into(),
+            span,
+            text: source[text_offset..text_end].to_string(),
+            text_offset,
+            line: source[..text_offset].matches('\n').count() + 1,
+            label: None,
+            labels: Vec::new(),
+        }
+    }
+
+    pub fn with_primary_label(mut self, message: impl Into<String>) -> Self {
+        self.label = Some(message.into());
+        self
+    }
+
+    /// Adds a secondary span of the same `source`, widening the snippet to the lines it is on.
+    pub fn with_label(
+        mut self,
+        source: &str,
+        span: Range<usize>,
+        message: impl Into<String>,
+    ) -> Self {
+        let span = clamp(source, span);
+        let (start, end) = line_bounds(source, span.start, span.end());
+        let text_offset = start.min(self.text_offset);
+        let text_end = end.max(self.text_offset + self.text.len());
+
+        self.text = source[text_offset..text_end].to_string();
+        self.line = source[..text_offset].matches('\n').count() + 1;
+        self.text_offset = text_offset;
+        self.labels.push(Label {
+            span,
+            message: message.into(),
+        });
+        self
+    }
+
+    /// A one-character span at a 1-based line and column, as reported by most parsers.
+    pub fn at_line_column(
+        source_name: impl Into<String>,
+        source: &str,
+        line: usize,
+        column: usize,
+    ) -> Self {
+        let line_start = source
+            .split_inclusive('\n')
+            .take(line.saturating_sub(1))
+            .map(str::len)
+            .sum::<usize>();
+        let start = line_start + column.saturating_sub(1);
+        let len = source
+            .get(start..)
+            .and_then(|rest| rest.chars().next())
+            .map_or(0, char::len_utf8);
+        Self::new(source_name, source, start..start + len)
+    }
+
+    /// 1-based column of the span start, counted in characters. 1 if the span isn't in
+    /// `text`.
+    pub fn column(&self) -> usize {
+        let before = self.text_before(self.span.start).unwrap_or_default();
+        let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+        before[line_start..].chars().count() + 1
+    }
+
+    /// The part of `text` before the source offset `offset`, `None` if `offset` isn't a
+    /// character boundary of `text`.
+    fn text_before(&self, offset: usize) -> Option<&str> {
+        self.text.get(..offset.checked_sub(self.text_offset)?)
+    }
+}
+
+fn clamp(source: &str, span: Range<usize>) -> Span {
+    let start = floor_char_boundary(source, span.start.min(source.len()));
+    let end = floor_char_boundary(source, span.end.clamp(start, source.len()));
+    Span {
+        start,
+        len: end - start,
+    }
+}
+
+/// The start of the line containing `start` and the end of the line containing `end`.
+fn line_bounds(source: &str, start: usize, end: usize) -> (usize, usize) {
+    let line_start = source[..start].rfind('\n').map_or(0, |index| index + 1);
+    let line_end = source[end..]
+        .find('\n')
+        .map_or(source.len(), |index| end + index);
+    (line_start, line_end)
+}
+
+fn floor_char_boundary(source: &str, mut index: usize) -> usize {
+    while !source.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+impl Display for Snippet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let last_line = self.line + self.text.matches('\n').count();
+        let width = last_line.to_string().len();
+        writeln!(
+            f,
+            "{:width$}--> {}:{}:{}",
+            "",
+            self.source_name,
+            self.line
+                + self
+                    .text_before(self.span.start)
+                    .map_or(0, |before| before.matches('\n').count()),
+            self.column()
+        )?;
+        writeln!(f, "{:width$} |", "")?;
+
+        let marks = std::iter::once(('^', &self.span, self.label.as_deref())).chain(
+            self.labels
+                .iter()
+                .map(|label| ('-', &label.span, Some(label.message.as_str()))),
+        );
+        let marks = marks.collect::<Vec<_>>();
+
+        let mut offset = self.text_offset;
+        for (index, line) in self.text.split('\n').enumerate() {
+            writeln!(f, "{:>width$} | {line}", self.line + index)?;
+
+            let line_end = offset + line.len();
+            for (mark, span, label) in &marks {
+                let start = span.start.max(offset);
+                let end = span.end().min(line_end);
+                let underlined = match span.len {
+                    0 => (offset..=line_end).contains(&span.start),
+                    _ => start < end,
+                };
+                if !underlined {
+                    continue;
+                }
+
+                let (Some(before), Some(marked)) = (
+                    line.get(..start - offset),
+                    line.get(start - offset..end.max(start) - offset),
+                ) else {
+                    continue;
+                };
+                let padding = before.chars().count();
+                let width_of_span = marked.chars().count().max(1);
+                write!(
+                    f,
+                    "{:width$} | {:padding$}{}",
+                    "",
+                    "",
+                    mark.to_string().repeat(width_of_span)
+                )?;
+                match label {
+                    Some(label) if span.end() <= line_end => writeln!(f, " {label}")?,
+                    _ => writeln!(f)?,
+                }
+            }
+            offset = line_end + 1;
+        }
+        Ok(())
+    }
+}
+
+impl AnyError {
+    pub fn with_snippet(mut self, snippet: Snippet) -> Self {
+        self.context.details.snippet = Some(snippet);
+        self
+    }
+
+    /// The snippet of the outermost layer that has one.
+    pub fn snippet(&self) -> Option<&Snippet> {
+        let mut node = Some(self);
+        while let Some(error) = node {
+            if let Some(snippet) = error.context.details.snippet.as_ref() {
+                return Some(snippet);
+            }
+            node = error.inner();
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "[server]\nport = \"eighty\"\nhost = \"0.0.0.0\"\n";
+
+    #[test]
+    fn test_new() {
+        let snippet = Snippet::new("app.toml", SOURCE, 16..24);
+
+        assert_eq!(snippet.text, "port = \"eighty\"");
+        assert_eq!(snippet.line, 2);
+        assert_eq!(snippet.column(), 8);
+        assert_eq!(snippet.span, Span { start: 16, len: 8 });
+    }
+
+    #[test]
+    fn test_at_line_column() {
+        let snippet = Snippet::at_line_column("app.toml", SOURCE, 3, 8);
+
+        assert_eq!(snippet.text, "host = \"0.0.0.0\"");
+        assert_eq!(snippet.span, Span { start: 32, len: 1 });
+        assert_eq!(snippet.column(), 8);
+
+        let past_end = Snippet::at_line_column("app.toml", SOURCE, 9, 9);
+        assert_eq!(past_end.span.len, 0);
+    }
+
+    #[test]
+    fn test_render() {
+        let snippet = Snippet::new("app.toml", SOURCE, 16..24);
+
+        assert_eq!(
+            snippet.to_string(),
+            " --> app.toml:2:8\n  |\n2 | port = \"eighty\"\n  |        ^^^^^^^^\n"
+        );
+    }
+
+    #[test]
+    fn test_render_multiline_and_unicode() {
+        let source = "naïve = [\n  1,\n";
+        let snippet = Snippet::new("data.toml", source, 9..15);
+
+        assert_eq!(
+            snippet.to_string(),
+            " --> data.toml:1:9\n  |\n1 | naïve = [\n  |         ^\n2 |   1,\n  | ^^^^\n"
+        );
+    }
+
+    #[test]
+    fn test_labels() {
+        let source = "[limits]\nmax = 10\nmin = 20\n";
+        let snippet = Snippet::new("app.toml", source, 24..26)
+            .with_primary_label("must be at most `max`")
+            .with_label(source, 15..17, "`max` defined here");
+
+        assert_eq!(snippet.line, 2);
+        assert_eq!(snippet.text, "max = 10\nmin = 20");
+        assert_eq!(
+            snippet.to_string(),
+            " --> app.toml:3:7\n  |\n\
+             2 | max = 10\n  |       -- `max` defined here\n\
+             3 | min = 20\n  |       ^^ must be at most `max`\n"
+        );
+
+        let json = serde_json::to_value(&snippet).unwrap();
+        assert_eq!(json["labels"][0]["message"], "`max` defined here");
+        assert_eq!(json["label"], "must be at most `max`");
+    }
+
+    #[test]
+    fn test_from_chain() {
+        let snippet = Snippet::new("app.toml", SOURCE, 16..24);
+        let mut error = AnyError::msg("invalid port").with_snippet(snippet.clone());
+        error.wrap_with("loading config");
+
+        assert_eq!(error.snippet(), Some(&snippet));
+        assert_eq!(
+            serde_json::to_value(&error).unwrap()["context"]["innerError"]["context"]["snippet"]["span"]
+                ["start"],
+            16
+        );
+    }
+
+    #[test]
+    fn test_render_inconsistent() {
+        let snippet: Snippet = serde_json::from_value(serde_json::json!({
+            "sourceName": "remote.toml",
+            "span": { "start": 0, "len": 1 },
+            "text": "ab",
+            "textOffset": 5,
+            "line": 1,
+            "labels": [
+                { "span": { "start": 6, "len": usize::MAX }, "message": "huge" },
+                { "span": { "start": 99, "len": 1 }, "message": "outside" },
+            ],
+        }))
+        .unwrap();
+        assert_eq!(snippet.column(), 1);
+        assert_eq!(
+            snippet.to_string(),
+            " --> remote.toml:1:1\n  |\n1 | ab\n  |  -\n"
+        );
+
+        let snippet = Snippet {
+            span: Span { start: 3, len: 1 },
+            ..Snippet::new("data.toml", "naïve", 0..1)
+        };
+        assert_eq!(snippet.column(), 1);
+        assert_eq!(snippet.to_string(), " --> data.toml:1:1\n  |\n1 | naïve\n");
+
+        let error = AnyError::msg("invalid").with_snippet(snippet);
+        assert!(error.render_report().to_string().contains("naïve"));
+    }
+}
@@ -0,0 +1,152 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::AnyError;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, valuable::Valuable)]
+#[serde(rename_all = "camelCase")]
+pub struct SpanTraceFrame {
+    pub target: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub fields: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<u32>,
+}
+
+/// The `tracing` spans that were active when an error was captured, innermost first.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, valuable::Valuable)]
+#[serde(transparent)]
+pub struct SpanTrace {
+    spans: Vec<SpanTraceFrame>,
+}
+
+impl SpanTrace {
+    pub fn new(spans: Vec<SpanTraceFrame>) -> Self {
+        Self { spans }
+    }
+
+    pub fn spans(&self) -> &[SpanTraceFrame] {
+        &self.spans
+    }
+
+    /// Records the current span trace, or `None` when no span is active or the subscriber
+    /// has no `tracing_error::ErrorLayer`.
+    #[cfg(feature = "tracing-error")]
+    pub fn capture() -> Option<Self> {
+        use tracing_error::SpanTraceStatus;
+
+        let captured = tracing_error::SpanTrace::capture();
+        if captured.status() != SpanTraceStatus::CAPTURED {
+            return None;
+        }
+
+        let mut spans = Vec::new();
+        captured.with_spans(|metadata, fields| {
+            spans.push(SpanTraceFrame {
+                target: metadata.target().to_string(),
+                name: metadata.name().to_string(),
+                fields: fields.to_string(),
+                file: metadata.file().map(str::to_string),
+                line: metadata.line(),
+            });
+            true
+        });
+
+        Some(Self { spans })
+    }
+
+    #[cfg(not(feature = "tracing-error"))]
+    pub fn capture() -> Option<Self> {
+        None
+    }
+}
+
+impl AnyError {
+    /// The span trace of the outermost layer that has one.
+    pub fn span_trace(&self) -> Option<&SpanTrace> {
+        let mut node = Some(self);
+        while let Some(error) = node {
+            if let Some(span_trace) = error.context.details.span_trace.as_ref() {
+                return Some(span_trace);
+            }
+            node = error.inner();
+        }
+        None
+    }
+
+    /// Records the current span trace on this layer, for errors that weren't converted from
+    /// an `Error`, such as ones made with [`AnyError::msg`]. A recorded trace is kept.
+    pub fn in_current_span(mut self) -> Self {
+        if self.context.details.span_trace.is_none() {
+            self.context.details.span_trace = SpanTrace::capture();
+        }
+        self
+    }
+}
+
+impl Display for SpanTrace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, span) in self.spans.iter().enumerate() {
+            write!(f, "{index:>4}: {}::{}", span.target, span.name)?;
+            if !span.fields.is_empty() {
+                write!(f, " with {}", span.fields)?;
+            }
+            writeln!(f)?;
+            match (&span.file, span.line) {
+                (Some(file), Some(line)) => writeln!(f, "             at {file}:{line}")?,
+                (Some(file), None) => writeln!(f, "             at {file}")?,
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let span_trace = SpanTrace::new(vec![SpanTraceFrame {
+            target: "app::sync".to_string(),
+            name: "sync_tenant".to_string(),
+            fields: "tenant=42".to_string(),
+            file: Some("src/sync.rs".to_string()),
+            line: Some(10),
+        }]);
+
+        assert_eq!(
+            span_trace.to_string(),
+            "   0: app::sync::sync_tenant with tenant=42\n             at src/sync.rs:10\n"
+        );
+    }
+
+    #[cfg(feature = "tracing-error")]
+    #[test]
+    fn test_capture_inside_span() {
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let subscriber = tracing_subscriber::registry().with(tracing_error::ErrorLayer::default());
+        tracing::subscriber::with_default(subscriber, || {
+            assert!(SpanTrace::capture().is_none());
+
+            let span = tracing::info_span!("sync_tenant", tenant = 42);
+            let _entered = span.enter();
+
+            let span_trace = SpanTrace::capture().expect("span trace");
+            assert_eq!(span_trace.spans().len(), 1);
+            assert_eq!(span_trace.spans()[0].name, "sync_tenant");
+            assert_eq!(span_trace.spans()[0].fields, "tenant=42");
+
+            let mut error = AnyError::msg("tenant not found").in_current_span();
+            error.wrap_with("syncing");
+            assert_eq!(error.span_trace(), Some(&span_trace));
+        });
+    }
+}
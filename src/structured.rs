@@ -0,0 +1,157 @@
+//! An extension point for pulling structured fields out of error types the crate has no
+//! built-in adapter for.
+//!
+//! Implement [`CaptureStructured`] for the type, then either convert it with
+//! [`AnyError::from_structured`] or [`register`] it so the fields are also captured when the
+//! type shows up as the source of another error.
+
+use std::{
+    any::TypeId,
+    collections::BTreeMap,
+    error::Error,
+    sync::{LazyLock, RwLock},
+};
+
+use serde::Serialize;
+
+use crate::{AnyError, type_name::standardized_type_name_of};
+
+/// Collects the fields captured for one layer. They end up in the layer's extensions.
+pub struct ExtensionWriter<'a> {
+    extensions: &'a mut BTreeMap<String, serde_json::Value>,
+}
+
+impl ExtensionWriter<'_> {
+    /// Sets `key`, see [`AnyError::with_extension`].
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Serialize) -> &mut Self {
+        self.extensions
+            .insert(key.into(), serde_json::to_value(value).unwrap_or_default());
+        self
+    }
+}
+
+pub trait CaptureStructured {
+    fn capture(&self, ctx: &mut ExtensionWriter);
+}
+
+type Capturer = fn(&(dyn Error + 'static), &mut ExtensionWriter) -> bool;
+
+static CAPTURERS: LazyLock<RwLock<Vec<(TypeId, Capturer)>>> = LazyLock::new(Default::default);
+
+fn capture_as<E: Error + CaptureStructured + 'static>(
+    error: &(dyn Error + 'static),
+    writer: &mut ExtensionWriter,
+) -> bool {
+    match error.downcast_ref::<E>() {
+        Some(error) => {
+            error.capture(writer);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Captures fields from `E` whenever it is converted as a source error.
+pub fn register<E: Error + CaptureStructured + 'static>() {
+    let mut capturers = CAPTURERS.write().unwrap_or_else(|e| e.into_inner());
+    if !capturers.iter().any(|(id, _)| *id == TypeId::of::<E>()) {
+        capturers.push((TypeId::of::<E>(), capture_as::<E>));
+    }
+}
+
+/// Runs the registered capturer for `source`, if any, on the layer converted from it.
+pub(crate) fn apply(source: &(dyn Error + 'static), error: &mut AnyError) {
+    let capturers = CAPTURERS.read().unwrap_or_else(|e| e.into_inner());
+    let mut writer = ExtensionWriter {
+        extensions: &mut error.context.details.extensions,
+    };
+    for (_, capture) in capturers.iter() {
+        if capture(source, &mut writer) {
+            return;
+        }
+    }
+}
+
+impl AnyError {
+    /// Converts `value` like `AnyError::from`, adding the fields it captures.
+    #[track_caller]
+    pub fn from_structured<E: Error + CaptureStructured>(value: E) -> Self {
+        let mut error = AnyError::capture(standardized_type_name_of(&value), &value);
+        value.capture(&mut ExtensionWriter {
+            extensions: &mut error.context.details.extensions,
+        });
+        error.finish_conversion();
+        error
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt;
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct QuotaError {
+        limit: u32,
+        used: u32,
+    }
+
+    impl fmt::Display for QuotaError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "quota exceeded")
+        }
+    }
+
+    impl Error for QuotaError {}
+
+    impl CaptureStructured for QuotaError {
+        fn capture(&self, ctx: &mut ExtensionWriter) {
+            ctx.insert("limit", self.limit).insert("used", self.used);
+        }
+    }
+
+    #[derive(Debug)]
+    struct UploadError(QuotaError);
+
+    impl fmt::Display for UploadError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "upload failed")
+        }
+    }
+
+    impl Error for UploadError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    #[test]
+    fn test_from_structured() {
+        let line = line!() + 1;
+        let error = AnyError::from_structured(QuotaError {
+            limit: 10,
+            used: 12,
+        });
+
+        assert_eq!(error.message(), "quota exceeded");
+        assert_eq!(error.extension("limit"), Some(&serde_json::json!(10)));
+        assert_eq!(error.extension("used"), Some(&serde_json::json!(12)));
+        let location = error.location().unwrap();
+        assert_eq!(location.file, file!());
+        assert_eq!(location.line, line);
+    }
+
+    #[test]
+    fn test_registered_source() {
+        register::<QuotaError>();
+        register::<QuotaError>();
+        let error = AnyError::from(UploadError(QuotaError { limit: 5, used: 6 }));
+
+        assert!(error.context.extensions().is_empty());
+        assert_eq!(
+            error.inner().unwrap().context.extensions().get("used"),
+            Some(&serde_json::json!(6))
+        );
+    }
+}
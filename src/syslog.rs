@@ -0,0 +1,185 @@
+//! RFC 5424 syslog messages, for environments that ship errors through syslog rather than
+//! JSON logs. Each layer of the chain becomes a STRUCTURED-DATA element, `error.0` for the
+//! outermost error, `error.1` for its cause and so on.
+
+use std::{
+    fmt::Write,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{AnyError, severity::Severity};
+
+/// The private enterprise number RFC 5612 reserves for documentation. Replace it with your
+/// organization's number if it has one.
+pub const DOCUMENTATION_ENTERPRISE_ID: u32 = 32473;
+
+/// Header fields of the messages built by [`SyslogFormat::format`].
+#[derive(Debug, Clone)]
+pub struct SyslogFormat {
+    /// 0-23, `user` (1) by default.
+    pub facility: u8,
+    /// 0-7, `err` (3) by default. Used for errors without a [`Severity`].
+    pub severity: u8,
+    pub hostname: Option<String>,
+    pub app_name: Option<String>,
+    pub proc_id: Option<String>,
+    /// The number after the `@` in each SD-ID.
+    pub enterprise_id: u32,
+}
+
+impl Default for SyslogFormat {
+    fn default() -> Self {
+        Self {
+            facility: 1,
+            severity: 3,
+            hostname: None,
+            app_name: None,
+            proc_id: Some(std::process::id().to_string()),
+            enterprise_id: DOCUMENTATION_ENTERPRISE_ID,
+        }
+    }
+}
+
+impl SyslogFormat {
+    pub fn new(app_name: impl Into<String>) -> Self {
+        Self {
+            app_name: Some(app_name.into()),
+            ..Default::default()
+        }
+    }
+
+    /// A message timestamped now, with the outermost `$type` as MSGID and the chain's messages
+    /// as MSG.
+    pub fn format(&self, error: &AnyError) -> String {
+        self.format_at(error, SystemTime::now())
+    }
+
+    pub fn format_at(&self, error: &AnyError, timestamp: SystemTime) -> String {
+        let severity = error
+            .severity()
+            .map_or(self.severity, Severity::syslog_severity);
+        let priority = u16::from(self.facility.min(23)) * 8 + u16::from(severity.min(7));
+        let mut message = format!(
+            "<{priority}>1 {} {} {} {} {} ",
+            rfc3339(timestamp),
+            header_field(self.hostname.as_deref(), 255),
+            header_field(self.app_name.as_deref(), 48),
+            header_field(self.proc_id.as_deref(), 128),
+            header_field(Some(&error.r#type), 32),
+        );
+
+        let mut node = Some(error);
+        let mut level = 0;
+        while let Some(error) = node {
+            let _ = write!(
+                message,
+                "[error.{level}@{} type=\"{}\" message=\"{}\"]",
+                self.enterprise_id,
+                param_value(&error.r#type),
+                param_value(error.message()),
+            );
+            level += 1;
+            node = error.inner();
+        }
+
+        let _ = write!(message, " {}", error.chain_messages().join(": "));
+        message
+    }
+}
+
+/// Header fields are printable ASCII without spaces, `-` when absent.
+fn header_field(value: Option<&str>, max_len: usize) -> String {
+    let value = value
+        .unwrap_or_default()
+        .chars()
+        .map(|c| if c.is_ascii_graphic() { c } else { '_' })
+        .take(max_len)
+        .collect::<String>();
+    if value.is_empty() {
+        "-".to_string()
+    } else {
+        value
+    }
+}
+
+fn param_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '"' | '\\' | ']') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// `YYYY-MM-DDTHH:MM:SS.mmmZ`.
+fn rfc3339(timestamp: SystemTime) -> String {
+    let since_epoch = timestamp.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = since_epoch.as_secs();
+    let (days, time) = (seconds / 86_400, seconds % 86_400);
+
+    // Inverse of the day count in `retry::parse_http_date`, with years starting in March.
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let (year, month) = match month {
+        0..10 => (era * 400 + year_of_era, month + 3),
+        _ => (era * 400 + year_of_era + 1, month - 9),
+    };
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        time / 3_600,
+        time / 60 % 60,
+        time % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_rfc3339() {
+        assert_eq!(rfc3339(UNIX_EPOCH), "1970-01-01T00:00:00.000Z");
+        assert_eq!(
+            rfc3339(UNIX_EPOCH + Duration::from_millis(784_111_777_250)),
+            "1994-11-06T08:49:37.250Z"
+        );
+        assert_eq!(
+            rfc3339(UNIX_EPOCH + Duration::from_secs(1_709_208_000)),
+            "2024-02-29T12:00:00.000Z"
+        );
+    }
+
+    #[test]
+    fn test_format() {
+        let mut error = AnyError::msg("permission \"denied\"").with_type("io.Error");
+        error.wrap_with("loading config");
+        let format = SyslogFormat {
+            hostname: Some("web 1".to_string()),
+            proc_id: None,
+            ..SyslogFormat::new("billing")
+        };
+
+        assert_eq!(
+            format.format_at(&error, UNIX_EPOCH),
+            "<11>1 1970-01-01T00:00:00.000Z web_1 billing - Context \
+             [error.0@32473 type=\"Context\" message=\"loading config\"]\
+             [error.1@32473 type=\"io.Error\" message=\"permission \\\"denied\\\"\"] \
+             loading config: permission \"denied\""
+        );
+
+        let error = error.with_severity(Severity::Critical);
+        assert!(format.format_at(&error, UNIX_EPOCH).starts_with("<10>1 "));
+    }
+}
@@ -0,0 +1,175 @@
+use std::error::Error;
+
+use serde::{Deserialize, Serialize};
+
+use crate::AnyError;
+
+/// A stable `$type` tag for an error value, usually generated with
+/// `#[derive(LibError)] #[liberror(tag)]`.
+pub trait TypeTag {
+    fn type_tag(&self) -> String;
+
+    /// Every tag values of this type can produce, used to populate a
+    /// [`Catalog`](crate::catalog::Catalog).
+    fn known_tags() -> Vec<String>
+    where
+        Self: Sized,
+    {
+        Vec::new()
+    }
+}
+
+/// Casing applied to each segment of a generated tag, and to the identifiers of `$type`s
+/// derived from Rust types through [`Config::type_name_case`].
+///
+/// [`Config::type_name_case`]: crate::config::Config::type_name_case
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TagCase {
+    /// `my_app.user.not_found_error`
+    #[default]
+    Snake,
+    /// `MY_APP.USER.NOT_FOUND_ERROR`
+    ScreamingSnake,
+    /// `my-app.user.not-found-error`
+    Kebab,
+    /// `myApp.user.notFoundError`
+    Camel,
+    /// Segments are kept as written: `my_app.user.NotFoundError`
+    Preserve,
+}
+
+impl TagCase {
+    pub fn apply(self, segment: &str) -> String {
+        match self {
+            TagCase::Snake => words(segment).join("_"),
+            TagCase::ScreamingSnake => words(segment).join("_").to_uppercase(),
+            TagCase::Kebab => words(segment).join("-"),
+            TagCase::Camel => words(segment)
+                .iter()
+                .enumerate()
+                .map(|(index, word)| {
+                    if index == 0 {
+                        word.clone()
+                    } else {
+                        capitalize(word)
+                    }
+                })
+                .collect(),
+            TagCase::Preserve => segment.to_string(),
+        }
+    }
+}
+
+/// Joins a `::`-separated path (such as `module_path!()`) and an identifier into a dotted tag,
+/// casing every segment.
+pub fn format_tag(path: &str, ident: &str, case: TagCase) -> String {
+    path.split("::")
+        .chain(ident.split("::"))
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| case.apply(segment))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Splits an identifier into lowercase words at underscores and case boundaries, keeping
+/// acronyms together (`HTTPError` is `http`, `error`).
+fn words(ident: &str) -> Vec<String> {
+    let chars = ident.chars().collect::<Vec<_>>();
+    let mut words = Vec::new();
+    let mut current = String::new();
+
+    for (index, &c) in chars.iter().enumerate() {
+        if c == '_' || c == '-' {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+
+        if c.is_uppercase() && !current.is_empty() {
+            let previous = chars[index - 1];
+            let next_is_lower = chars.get(index + 1).is_some_and(|next| next.is_lowercase());
+            if previous.is_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_uppercase() && next_is_lower)
+            {
+                words.push(std::mem::take(&mut current));
+            }
+        }
+
+        current.extend(c.to_lowercase());
+    }
+
+    if !current.is_empty() {
+        words.push(current);
+    }
+
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+impl AnyError {
+    /// Converts `value` like `AnyError::from`, using its [`TypeTag`] (under the configured
+    /// namespace) as the `$type`.
+    #[track_caller]
+    pub fn from_tagged<E: Error + TypeTag>(value: E) -> Self {
+        let mut error = AnyError::capture(value.type_tag(), &value);
+        error.finish_conversion();
+        error
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_words() {
+        assert_eq!(words("NotFoundError"), vec!["not", "found", "error"]);
+        assert_eq!(words("HTTPError"), vec!["http", "error"]);
+        assert_eq!(words("my_app"), vec!["my", "app"]);
+        assert_eq!(words("Utf8Error"), vec!["utf8", "error"]);
+    }
+
+    #[test]
+    fn test_format_tag_cases() {
+        let path = "my_app::services::user";
+
+        assert_eq!(
+            format_tag(path, "DatabaseError", TagCase::Snake),
+            "my_app.services.user.database_error"
+        );
+        assert_eq!(
+            format_tag(path, "DatabaseError", TagCase::ScreamingSnake),
+            "MY_APP.SERVICES.USER.DATABASE_ERROR"
+        );
+        assert_eq!(
+            format_tag(path, "DatabaseError", TagCase::Kebab),
+            "my-app.services.user.database-error"
+        );
+        assert_eq!(
+            format_tag(path, "DatabaseError", TagCase::Camel),
+            "myApp.services.user.databaseError"
+        );
+        assert_eq!(
+            format_tag(path, "DatabaseError", TagCase::Preserve),
+            "my_app.services.user.DatabaseError"
+        );
+    }
+
+    #[test]
+    fn test_format_tag_nested_ident() {
+        assert_eq!(
+            format_tag("app", "UserError::NotFound", TagCase::Snake),
+            "app.user_error.not_found"
+        );
+    }
+}
@@ -0,0 +1,164 @@
+//! Text decoding and parsing errors, with the byte offsets and failure kind as structured
+//! fields. The std parse errors don't know what they were parsing into, so
+//! [`ParseExt::parse_as`] records the target type as well.
+
+use std::{
+    char::ParseCharError,
+    num::{ParseFloatError, ParseIntError},
+    str::{FromStr, ParseBoolError, Utf8Error},
+    string::FromUtf8Error,
+};
+
+use crate::{AnyError, IntoAnyError, category::Category, type_name::standardized_type_name};
+
+pub const VALID_UP_TO_EXTENSION: &str = "validUpTo";
+/// Length of the invalid sequence, absent when the input ended mid-character.
+pub const ERROR_LEN_EXTENSION: &str = "errorLen";
+pub const KIND_EXTENSION: &str = "kind";
+pub const TARGET_TYPE_EXTENSION: &str = "targetType";
+pub const INPUT_EXTENSION: &str = "input";
+
+/// Inputs longer than this are cut before being recorded.
+const MAX_INPUT_CHARS: usize = 64;
+
+fn utf8_error(error: AnyError, utf8: &Utf8Error) -> AnyError {
+    let error = error
+        .with_category(Category::Validation)
+        .with_extension(VALID_UP_TO_EXTENSION, utf8.valid_up_to());
+    match utf8.error_len() {
+        Some(len) => error.with_extension(ERROR_LEN_EXTENSION, len),
+        None => error,
+    }
+}
+
+impl IntoAnyError for Utf8Error {
+    fn into_any_error(self) -> AnyError {
+        utf8_error(AnyError::from(self), &self)
+    }
+}
+
+impl IntoAnyError for FromUtf8Error {
+    fn into_any_error(self) -> AnyError {
+        let utf8 = self.utf8_error();
+        utf8_error(AnyError::from(self), &utf8)
+    }
+}
+
+impl IntoAnyError for ParseIntError {
+    fn into_any_error(self) -> AnyError {
+        let kind = format!("{:?}", self.kind());
+        AnyError::from(self)
+            .with_category(Category::Validation)
+            .with_extension(KIND_EXTENSION, kind)
+    }
+}
+
+impl IntoAnyError for ParseFloatError {
+    fn into_any_error(self) -> AnyError {
+        AnyError::from(self).with_category(Category::Validation)
+    }
+}
+
+impl IntoAnyError for ParseBoolError {
+    fn into_any_error(self) -> AnyError {
+        AnyError::from(self).with_category(Category::Validation)
+    }
+}
+
+impl IntoAnyError for ParseCharError {
+    fn into_any_error(self) -> AnyError {
+        AnyError::from(self).with_category(Category::Validation)
+    }
+}
+
+pub trait ParseExt {
+    /// [`str::parse`], recording the target type and the input on failure.
+    fn parse_as<T>(&self) -> Result<T, AnyError>
+    where
+        T: FromStr + 'static,
+        T::Err: IntoAnyError;
+}
+
+impl ParseExt for str {
+    fn parse_as<T>(&self) -> Result<T, AnyError>
+    where
+        T: FromStr + 'static,
+        T::Err: IntoAnyError,
+    {
+        self.parse::<T>().map_err(|error| {
+            let input = match self.char_indices().nth(MAX_INPUT_CHARS) {
+                Some((end, _)) => format!("{}…", &self[..end]),
+                None => self.to_string(),
+            };
+            error
+                .into_any_error()
+                .with_extension(TARGET_TYPE_EXTENSION, standardized_type_name::<T>())
+                .with_extension(INPUT_EXTENSION, input)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_utf8_offsets() {
+        let bytes = b"caf\xc3\x28 au lait".to_vec();
+        let error = std::str::from_utf8(&bytes).unwrap_err().into_any_error();
+        assert_eq!(
+            error.extension(VALID_UP_TO_EXTENSION),
+            Some(&serde_json::json!(3))
+        );
+        assert_eq!(
+            error.extension(ERROR_LEN_EXTENSION),
+            Some(&serde_json::json!(1))
+        );
+        assert_eq!(error.category(), Some(Category::Validation));
+
+        let error = String::from_utf8(b"caf\xc3".to_vec())
+            .unwrap_err()
+            .into_any_error();
+        assert_eq!(
+            error.extension(VALID_UP_TO_EXTENSION),
+            Some(&serde_json::json!(3))
+        );
+        assert_eq!(error.extension(ERROR_LEN_EXTENSION), None);
+    }
+
+    #[test]
+    fn test_parse_as() {
+        let error = "300".parse_as::<u8>().unwrap_err();
+        assert_eq!(
+            error.extension(TARGET_TYPE_EXTENSION),
+            Some(&serde_json::json!("u8"))
+        );
+        assert_eq!(
+            error.extension(KIND_EXTENSION),
+            Some(&serde_json::json!("PosOverflow"))
+        );
+        assert_eq!(
+            error.extension(INPUT_EXTENSION),
+            Some(&serde_json::json!("300"))
+        );
+
+        let error = "yes".parse_as::<bool>().unwrap_err();
+        assert_eq!(
+            error.extension(TARGET_TYPE_EXTENSION),
+            Some(&serde_json::json!("bool"))
+        );
+
+        let long = "x".repeat(100);
+        let error = long.parse_as::<f64>().unwrap_err();
+        assert_eq!(
+            error.extension(INPUT_EXTENSION),
+            Some(&serde_json::json!(format!("{}…", "x".repeat(64))))
+        );
+
+        assert_eq!("42".parse_as::<i64>().unwrap(), 42);
+        assert_eq!(
+            "ab".parse_as::<char>().unwrap_err().category(),
+            Some(Category::Validation)
+        );
+    }
+}
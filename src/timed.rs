@@ -0,0 +1,101 @@
+//! Records how long an operation ran before it failed, a recurring triage question.
+
+use std::{panic::Location, time::Instant};
+
+use crate::{AnyError, location::located_at, timeout::ELAPSED_EXTENSION};
+
+/// The name given to [`timed`] or [`timed_async`].
+pub const OPERATION_EXTENSION: &str = "operation";
+
+impl AnyError {
+    fn with_timing(self, operation: &str, start: Instant) -> Self {
+        self.with_extension(OPERATION_EXTENSION, operation)
+            .with_extension(ELAPSED_EXTENSION, start.elapsed().as_millis() as u64)
+    }
+}
+
+/// Runs `f`, recording `operation` and the elapsed milliseconds on the error if it fails.
+///
+/// ```
+/// let result = liberror::timed("fetch_user", || "42".parse::<u32>());
+/// assert_eq!(result.unwrap(), 42);
+/// ```
+#[track_caller]
+pub fn timed<T, E: Into<AnyError>>(
+    operation: &str,
+    f: impl FnOnce() -> Result<T, E>,
+) -> Result<T, AnyError> {
+    let caller = Location::caller();
+    let start = Instant::now();
+    f().map_err(|error| located_at(caller, || error.into()).with_timing(operation, start))
+}
+
+/// [`timed`] for a future.
+#[track_caller]
+pub fn timed_async<T, E: Into<AnyError>>(
+    operation: &str,
+    future: impl IntoFuture<Output = Result<T, E>>,
+) -> impl Future<Output = Result<T, AnyError>> {
+    let caller = Location::caller();
+    async move {
+        let start = Instant::now();
+        future
+            .await
+            .map_err(|error| located_at(caller, || error.into()).with_timing(operation, start))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_timed() {
+        let error = timed("fetch_user", || {
+            std::thread::sleep(Duration::from_millis(20));
+            Err::<(), _>(AnyError::msg("user not found"))
+        })
+        .unwrap_err();
+
+        assert_eq!(
+            error.extension(OPERATION_EXTENSION),
+            Some(&serde_json::json!("fetch_user"))
+        );
+        assert!(
+            error
+                .extension(ELAPSED_EXTENSION)
+                .and_then(serde_json::Value::as_u64)
+                .unwrap()
+                >= 20
+        );
+    }
+
+    #[test]
+    fn test_timed_location() {
+        let line = line!() + 1;
+        let error = timed("parse", || "abc".parse::<u32>()).unwrap_err();
+        assert_eq!(error.location().unwrap().line, line);
+
+        let created = AnyError::msg("user not found");
+        let expected = created.location().cloned();
+        let error = timed("fetch_user", || Err::<(), _>(created)).unwrap_err();
+        assert_eq!(error.location().cloned(), expected);
+    }
+
+    #[tokio::test]
+    async fn test_timed_async() {
+        let line = line!() + 1;
+        let error = timed_async("parse", async { "abc".parse::<u32>() })
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.r#type, "ParseIntError");
+        assert_eq!(error.location().unwrap().line, line);
+        assert_eq!(
+            error.extension(OPERATION_EXTENSION),
+            Some(&serde_json::json!("parse"))
+        );
+    }
+}
@@ -0,0 +1,178 @@
+//! Timeout detection: converted errors whose source is a timed out `io::Error` or tokio
+//! `Elapsed` get the [`Category::Timeout`] category, and [`AnyError::with_timeout`] records
+//! how long the operation ran and what its deadline was.
+
+use std::{error::Error, io, time::Duration};
+
+use crate::{AnyError, IntoAnyError, category::Category, os_error};
+
+pub const ELAPSED_EXTENSION: &str = "elapsedMs";
+pub const DEADLINE_EXTENSION: &str = "deadlineMs";
+
+impl AnyError {
+    /// Marks the error as a timeout, recording the durations that are known in milliseconds.
+    pub fn with_timeout(mut self, elapsed: Option<Duration>, deadline: Option<Duration>) -> Self {
+        self = self.with_category(Category::Timeout);
+        if let Some(elapsed) = elapsed {
+            self = self.with_extension(ELAPSED_EXTENSION, elapsed.as_millis() as u64);
+        }
+        if let Some(deadline) = deadline {
+            self = self.with_extension(DEADLINE_EXTENSION, deadline.as_millis() as u64);
+        }
+        self
+    }
+
+    pub fn is_timeout(&self) -> bool {
+        self.category() == Some(Category::Timeout)
+    }
+}
+
+/// Whether `error` is a timeout this crate recognizes.
+pub fn is_timeout_error(error: &(dyn Error + 'static)) -> bool {
+    if let Some(error) = error.downcast_ref::<io::Error>() {
+        return error.kind() == io::ErrorKind::TimedOut;
+    }
+    #[cfg(feature = "tokio")]
+    if error.is::<tokio::time::error::Elapsed>() {
+        return true;
+    }
+    false
+}
+
+/// Categorizes a converted source error, which unlike the outermost error can be downcast.
+pub(crate) fn classify(source: &(dyn Error + 'static), error: &mut AnyError) {
+    if error.context.details.category.is_none() && is_timeout_error(source) {
+        error.context.details.category = Some(Category::Timeout);
+    }
+}
+
+impl IntoAnyError for io::Error {
+    fn into_any_error(self) -> AnyError {
+        let timed_out = self.kind() == io::ErrorKind::TimedOut;
+        let raw_os_error = self.raw_os_error();
+        let mut error = AnyError::from(self);
+        os_error::record(raw_os_error, &mut error);
+        if timed_out {
+            error.with_timeout(None, None)
+        } else {
+            error
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl IntoAnyError for tokio::time::error::Elapsed {
+    fn into_any_error(self) -> AnyError {
+        AnyError::from(self).with_timeout(None, None)
+    }
+}
+
+/// Runs `future` with a deadline, failing with a timeout error that records the elapsed time
+/// and the deadline.
+#[cfg(feature = "tokio")]
+#[track_caller]
+pub fn timeout<F: IntoFuture>(
+    deadline: Duration,
+    future: F,
+) -> impl Future<Output = Result<F::Output, AnyError>> {
+    let caller = crate::location::SourceLocation::caller();
+    async move {
+        let start = tokio::time::Instant::now();
+        tokio::time::timeout(deadline, future)
+            .await
+            .map_err(|elapsed| {
+                AnyError::from(elapsed)
+                    .with_location(caller)
+                    .with_timeout(Some(start.elapsed()), Some(deadline))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt;
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct FetchError(io::Error);
+
+    impl fmt::Display for FetchError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "fetch failed")
+        }
+    }
+
+    impl Error for FetchError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    #[test]
+    fn test_source_classified() {
+        let error = AnyError::from(FetchError(io::Error::from(io::ErrorKind::TimedOut)));
+        assert!(error.is_timeout());
+        assert_eq!(error.context.category(), None);
+
+        let error = AnyError::from(FetchError(io::Error::from(io::ErrorKind::NotFound)));
+        assert!(!error.is_timeout());
+    }
+
+    #[test]
+    fn test_io_into_any_error() {
+        let error = io::Error::new(io::ErrorKind::TimedOut, "read timed out").into_any_error();
+        assert!(error.is_timeout());
+        assert_eq!(error.extension(ELAPSED_EXTENSION), None);
+
+        let error = io::Error::from(io::ErrorKind::BrokenPipe).into_any_error();
+        assert_eq!(error.category(), None);
+    }
+
+    #[test]
+    fn test_with_timeout() {
+        let error = AnyError::msg("query cancelled").with_timeout(
+            Some(Duration::from_millis(5012)),
+            Some(Duration::from_secs(5)),
+        );
+
+        assert!(error.is_timeout());
+        assert_eq!(
+            error.extension(ELAPSED_EXTENSION),
+            Some(&serde_json::json!(5012))
+        );
+        assert_eq!(
+            error.extension(DEADLINE_EXTENSION),
+            Some(&serde_json::json!(5000))
+        );
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_tokio_timeout() {
+        let line = line!() + 1;
+        let error = timeout(Duration::from_millis(10), std::future::pending::<()>())
+            .await
+            .unwrap_err();
+
+        assert!(error.is_timeout());
+        assert_eq!(error.location().unwrap().file, file!());
+        assert_eq!(error.location().unwrap().line, line);
+        assert_eq!(
+            error.extension(DEADLINE_EXTENSION),
+            Some(&serde_json::json!(10))
+        );
+        assert!(
+            error
+                .extension(ELAPSED_EXTENSION)
+                .unwrap()
+                .as_u64()
+                .unwrap()
+                >= 10
+        );
+        assert_eq!(
+            timeout(Duration::from_secs(1), async { 7 }).await.unwrap(),
+            7
+        );
+    }
+}
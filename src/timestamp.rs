@@ -0,0 +1,52 @@
+use time::OffsetDateTime;
+
+use crate::AnyError;
+
+impl AnyError {
+    pub fn with_timestamp(mut self, timestamp: OffsetDateTime) -> Self {
+        self.context.details.timestamp = Some(timestamp);
+        self
+    }
+
+    /// When the outermost layer that has a timestamp was created.
+    pub fn timestamp(&self) -> Option<OffsetDateTime> {
+        let mut node = Some(self);
+        while let Some(error) = node {
+            if let Some(timestamp) = error.context.timestamp() {
+                return Some(timestamp);
+            }
+            node = error.inner();
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_captured() {
+        let before = OffsetDateTime::now_utc();
+        let mut error = AnyError::from("x".parse::<u8>().unwrap_err());
+        error.wrap_with("reading port");
+        let after = OffsetDateTime::now_utc();
+
+        let outer = error.context.timestamp().unwrap();
+        let inner = error.inner().unwrap().context.timestamp().unwrap();
+        assert!(before <= inner && inner <= outer && outer <= after);
+        assert_eq!(error.timestamp(), Some(outer));
+    }
+
+    #[test]
+    fn test_rfc3339_round_trip() {
+        let timestamp = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+        let error = AnyError::msg("boom").with_timestamp(timestamp);
+
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["context"]["timestamp"], "2023-11-14T22:13:20Z");
+
+        let decoded: AnyError = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded.timestamp(), Some(timestamp));
+    }
+}
@@ -0,0 +1,140 @@
+//! A fixed-capacity error chain for interrupt handlers and other places that can't allocate.
+//! Convert it into an [`AnyError`] once back in a context that can, e.g. before serializing.
+
+use heapless::{String, Vec};
+
+use crate::AnyError;
+
+/// Extension set on the outermost error converted from a [`TinyAnyError`] that had to drop
+/// layers, with how many.
+pub const DROPPED_LAYERS_EXTENSION: &str = "droppedLayers";
+
+/// An error chain of at most `DEPTH` layers, each with a type and message of at most `N`
+/// bytes. Longer strings are cut at a character boundary. Once full, wrapping drops the
+/// oldest context layer, keeping the root cause and the newest context.
+#[derive(Debug, Clone)]
+pub struct TinyAnyError<const N: usize, const DEPTH: usize = 4> {
+    /// Innermost first.
+    layers: Vec<Layer<N>, DEPTH>,
+    dropped: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Layer<const N: usize> {
+    r#type: String<N>,
+    message: String<N>,
+}
+
+impl<const N: usize> Layer<N> {
+    fn new(r#type: &str, message: &str) -> Self {
+        Self {
+            r#type: truncated(r#type),
+            message: truncated(message),
+        }
+    }
+}
+
+fn truncated<const N: usize>(value: &str) -> String<N> {
+    let mut string = String::new();
+    for c in value.chars() {
+        if string.push(c).is_err() {
+            break;
+        }
+    }
+    string
+}
+
+impl<const N: usize, const DEPTH: usize> TinyAnyError<N, DEPTH> {
+    pub fn new(r#type: &str, message: &str) -> Self {
+        let mut layers = Vec::new();
+        // `DEPTH` of 0 keeps nothing, counted as dropped.
+        let dropped = layers.push(Layer::new(r#type, message)).map_or(1, |_| 0);
+        Self { layers, dropped }
+    }
+
+    /// Adds an outer layer, as [`AnyError::wrap_with`] does.
+    pub fn wrap_with(&mut self, r#type: &str, message: &str) {
+        if self.layers.is_full() && self.layers.len() > 1 {
+            self.layers.remove(1);
+            self.dropped += 1;
+        }
+        if self.layers.push(Layer::new(r#type, message)).is_err() {
+            self.dropped += 1;
+        }
+    }
+
+    /// The number of layers, not counting dropped ones.
+    pub fn depth(&self) -> usize {
+        self.layers.len()
+    }
+
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    /// `(type, message)` of each layer, outermost first.
+    pub fn layers(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.layers
+            .iter()
+            .rev()
+            .map(|layer| (layer.r#type.as_str(), layer.message.as_str()))
+    }
+}
+
+impl<const N: usize, const DEPTH: usize> From<&TinyAnyError<N, DEPTH>> for AnyError {
+    fn from(value: &TinyAnyError<N, DEPTH>) -> Self {
+        let error = AnyError::from_chain(value.layers().collect::<std::vec::Vec<_>>())
+            .unwrap_or_else(|| AnyError::msg("empty TinyAnyError"));
+        match value.dropped {
+            0 => error,
+            dropped => error.with_extension(DROPPED_LAYERS_EXTENSION, dropped),
+        }
+    }
+}
+
+impl<const N: usize, const DEPTH: usize> From<TinyAnyError<N, DEPTH>> for AnyError {
+    fn from(value: TinyAnyError<N, DEPTH>) -> Self {
+        AnyError::from(&value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncates_at_char_boundary() {
+        let error = TinyAnyError::<8>::new("app.read", "naïve overflow");
+
+        assert_eq!(error.layers().next(), Some(("app.read", "naïve o")));
+    }
+
+    #[test]
+    fn test_into_any_error() {
+        let mut error = TinyAnyError::<32>::new("io.Error", "i2c nack");
+        error.wrap_with("Context", "reading sensor");
+
+        let error = AnyError::from(error);
+        assert_eq!(error.chain_types(), ["Context", "io.Error"]);
+        assert_eq!(error.chain_messages(), ["reading sensor", "i2c nack"]);
+        assert_eq!(error.extension(DROPPED_LAYERS_EXTENSION), None);
+    }
+
+    #[test]
+    fn test_keeps_root_and_newest_when_full() {
+        let mut error = TinyAnyError::<16, 3>::new("io.Error", "root");
+        for message in ["a", "b", "c", "d"] {
+            error.wrap_with("Context", message);
+        }
+
+        assert_eq!(error.depth(), 3);
+        assert_eq!(error.dropped(), 2);
+
+        let error = AnyError::from(&error);
+        assert_eq!(error.chain_messages(), ["d", "c", "root"]);
+        assert_eq!(
+            error.extension(DROPPED_LAYERS_EXTENSION),
+            Some(&serde_json::json!(2))
+        );
+    }
+}
@@ -0,0 +1,206 @@
+//! Opaque tokens carrying a whole chain, for services to hand to users and support staff to
+//! turn back into the original error.
+//!
+//! A token is URL-safe base64 of a flags byte, the deflated [`wire`](crate::wire) encoding and,
+//! when signed, an HMAC-SHA256 tag over both.
+//!
+//! Tokens are encoded, not encrypted: anyone holding one can decode the whole chain, with its
+//! messages, metadata, attachments, locations and backtraces. Signing only stops tampering.
+//! Before handing a token to a client, encode [`AnyError::to_public`] or, with the `redact`
+//! feature, a redacted copy instead of the error itself.
+
+use std::fmt;
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::{AnyError, wire::DecodeError};
+
+const SIGNED: u8 = 0b1;
+const TAG_LEN: usize = 32;
+const COMPRESSION_LEVEL: u8 = 9;
+/// Upper bound on the decompressed size, so a crafted token can't exhaust memory.
+const MAX_DECOMPRESSED_LEN: usize = 16 * 1024 * 1024;
+
+#[derive(Debug)]
+pub enum TokenError {
+    Base64(base64::DecodeError),
+    Empty,
+    Decompress,
+    /// [`AnyError::from_signed_token`] was given a token without a signature.
+    Unsigned,
+    /// The signature doesn't match the key.
+    Signature,
+    Decode(DecodeError),
+}
+
+impl fmt::Display for TokenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Base64(error) => write!(f, "invalid base64: {error}"),
+            Self::Empty => write!(f, "empty token"),
+            Self::Decompress => write!(f, "failed to decompress token"),
+            Self::Unsigned => write!(f, "token is not signed"),
+            Self::Signature => write!(f, "token signature does not match"),
+            Self::Decode(error) => write!(f, "invalid token payload: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for TokenError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Base64(error) => Some(error),
+            Self::Decode(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl AnyError {
+    /// Encodes the full chain as is. Readable by anyone with the token, see the
+    /// [module docs](self) before giving it to clients.
+    pub fn to_token(&self) -> String {
+        URL_SAFE_NO_PAD.encode(self.token_bytes(0))
+    }
+
+    /// Like [`AnyError::to_token`], with an HMAC-SHA256 tag so tokens echoed back by clients
+    /// can be trusted, see [`AnyError::from_signed_token`]. The tag doesn't hide the contents.
+    pub fn to_signed_token(&self, key: &[u8]) -> String {
+        let mut bytes = self.token_bytes(SIGNED);
+        let tag = mac(key, &bytes).finalize().into_bytes();
+        bytes.extend_from_slice(&tag);
+        URL_SAFE_NO_PAD.encode(bytes)
+    }
+
+    /// Decodes a signed or unsigned token without checking the signature.
+    pub fn from_token(token: &str) -> Result<Self, TokenError> {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(token.trim())
+            .map_err(TokenError::Base64)?;
+        let (flags, _) = bytes.split_first().ok_or(TokenError::Empty)?;
+        let payload = match flags & SIGNED {
+            0 => &bytes[1..],
+            _ => &bytes[1..bytes.len().saturating_sub(TAG_LEN).max(1)],
+        };
+        decode_payload(payload)
+    }
+
+    /// Decodes a token made by [`AnyError::to_signed_token`] with the same key.
+    pub fn from_signed_token(token: &str, key: &[u8]) -> Result<Self, TokenError> {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(token.trim())
+            .map_err(TokenError::Base64)?;
+        let (flags, _) = bytes.split_first().ok_or(TokenError::Empty)?;
+        if flags & SIGNED == 0 {
+            return Err(TokenError::Unsigned);
+        }
+        if bytes.len() < 1 + TAG_LEN {
+            return Err(TokenError::Signature);
+        }
+        let (signed, tag) = bytes.split_at(bytes.len() - TAG_LEN);
+        mac(key, signed)
+            .verify_slice(tag)
+            .map_err(|_| TokenError::Signature)?;
+        decode_payload(&signed[1..])
+    }
+
+    fn token_bytes(&self, flags: u8) -> Vec<u8> {
+        let mut bytes = vec![flags];
+        bytes.extend(miniz_oxide::deflate::compress_to_vec(
+            &self.to_bytes(),
+            COMPRESSION_LEVEL,
+        ));
+        bytes
+    }
+}
+
+fn mac(key: &[u8], bytes: &[u8]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(bytes);
+    mac
+}
+
+fn decode_payload(payload: &[u8]) -> Result<AnyError, TokenError> {
+    let bytes = miniz_oxide::inflate::decompress_to_vec_with_limit(payload, MAX_DECOMPRESSED_LEN)
+        .map_err(|_| TokenError::Decompress)?;
+    AnyError::from_bytes(&bytes).map_err(TokenError::Decode)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error() -> AnyError {
+        let mut error = AnyError::msg("connection refused").with_extension("port", 5432);
+        error.wrap_with("loading user 42");
+        error
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let error = error();
+        let token = error.to_token();
+
+        assert!(
+            token
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        );
+        assert_eq!(
+            serde_json::to_value(AnyError::from_token(&token).unwrap()).unwrap(),
+            serde_json::to_value(&error).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_round_trip_causes() {
+        let error = AnyError::msg("2 failed").with_causes([AnyError::msg("a"), error()]);
+
+        let decoded = AnyError::from_token(&error.to_token()).unwrap();
+        assert_eq!(decoded.causes().count(), 2);
+        assert_eq!(
+            serde_json::to_value(decoded).unwrap(),
+            serde_json::to_value(&error).unwrap()
+        );
+
+        let decoded = AnyError::from_signed_token(&error.to_signed_token(b"secret"), b"secret");
+        assert_eq!(decoded.unwrap().to_string(), error.to_string());
+    }
+
+    #[test]
+    fn test_signed() {
+        let error = error();
+        let token = error.to_signed_token(b"secret");
+
+        let decoded = AnyError::from_signed_token(&token, b"secret").unwrap();
+        assert_eq!(decoded.chain_messages(), error.chain_messages());
+        assert_eq!(
+            AnyError::from_token(&token).unwrap().chain_messages(),
+            error.chain_messages()
+        );
+
+        assert!(matches!(
+            AnyError::from_signed_token(&token, b"other"),
+            Err(TokenError::Signature)
+        ));
+        assert!(matches!(
+            AnyError::from_signed_token(&error.to_token(), b"secret"),
+            Err(TokenError::Unsigned)
+        ));
+    }
+
+    #[test]
+    fn test_rejects_garbage() {
+        assert!(matches!(
+            AnyError::from_token("not base64!"),
+            Err(TokenError::Base64(_))
+        ));
+        assert!(matches!(AnyError::from_token(""), Err(TokenError::Empty)));
+        assert!(matches!(
+            AnyError::from_token("AAAA"),
+            Err(TokenError::Decompress)
+        ));
+    }
+}
@@ -0,0 +1,94 @@
+//! Cutting long messages, such as third-party errors embedding a whole SQL statement or
+//! request body, before they bloat every log line.
+
+use std::sync::Arc;
+
+use crate::AnyError;
+
+/// Set to `true` on a layer whose message was cut. Kept apart from
+/// [`TRUNCATED_EXTENSION`](crate::TRUNCATED_EXTENSION), which records cut source chains.
+pub const MESSAGE_TRUNCATED_EXTENSION: &str = "messageTruncated";
+
+/// Cuts `message` to `max_chars` characters followed by `…`, returning whether it was longer.
+pub(crate) fn truncate(message: &mut String, max_chars: usize) -> bool {
+    match message.char_indices().nth(max_chars) {
+        Some((end, _)) => {
+            message.truncate(end);
+            message.push('…');
+            true
+        }
+        None => false,
+    }
+}
+
+/// Cuts the message of a single layer, marking it if it was cut.
+pub(crate) fn apply(error: &mut AnyError, max_chars: usize) {
+    if truncate(&mut error.context.message, max_chars) {
+        error
+            .context
+            .details
+            .extensions
+            .insert(MESSAGE_TRUNCATED_EXTENSION.to_string(), true.into());
+    }
+}
+
+impl AnyError {
+    /// Cuts every message of the chain and its causes longer than `max_chars` characters,
+    /// ending it in `…` and setting [`MESSAGE_TRUNCATED_EXTENSION`]. Set
+    /// [`Config::max_message_chars`](crate::config::Config::max_message_chars) to cut every
+    /// converted error instead.
+    pub fn truncate_messages(mut self, max_chars: usize) -> Self {
+        let mut stack = vec![&mut self];
+        while let Some(error) = stack.pop() {
+            apply(error, max_chars);
+            let context = error.make_context_mut();
+            stack.extend(
+                context
+                    .inner_error
+                    .iter_mut()
+                    .chain(&mut context.details.inner_errors)
+                    .map(Arc::make_mut),
+            );
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_messages() {
+        let statement = format!(
+            "syntax error in INSERT INTO events VALUES {}",
+            "(1), ".repeat(500)
+        );
+        let error = AnyError::msg(statement)
+            .with_type("db.QueryError")
+            .wrap_in("app.SaveFailed", "saving events")
+            .truncate_messages(24);
+
+        let root = error.root_cause();
+        assert_eq!(root.message(), "syntax error in INSERT I…");
+        assert_eq!(
+            root.extension(MESSAGE_TRUNCATED_EXTENSION),
+            Some(&true.into())
+        );
+        assert_eq!(error.message(), "saving events");
+        assert!(
+            !error
+                .context
+                .extensions()
+                .contains_key(MESSAGE_TRUNCATED_EXTENSION)
+        );
+    }
+
+    #[test]
+    fn test_char_boundaries() {
+        let mut message = "héllo wörld".to_string();
+        assert!(truncate(&mut message, 8));
+        assert_eq!(message, "héllo wö…");
+        assert!(!truncate(&mut message, 100));
+    }
+}
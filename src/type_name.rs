@@ -1,3 +1,104 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{config, tag::TagCase};
+
+/// The crate and module a type is declared in, read from its full path before
+/// [`standardized_type_name`] shortens it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, valuable::Valuable)]
+#[serde(rename_all = "camelCase")]
+pub struct Origin {
+    #[serde(rename = "crate")]
+    pub crate_name: String,
+    /// The full module path, e.g. `my_app::services::user`.
+    pub module: String,
+}
+
+/// The origin of `T`, or `None` for types without a path (primitives, tuples, trait objects).
+pub fn origin<T: ?Sized>() -> Option<Origin> {
+    parse_origin(std::any::type_name::<T>())
+}
+
+fn parse_origin(type_name: &str) -> Option<Origin> {
+    let path = type_name.trim_start_matches('&').trim_start_matches("mut ");
+    if path.starts_with("dyn ") || path.starts_with('(') || path.starts_with('[') {
+        return None;
+    }
+    let path = path.split('<').next()?;
+    let (module, _) = path.rsplit_once("::")?;
+    let crate_name = module.split("::").next()?;
+
+    Some(Origin {
+        crate_name: crate_name.to_string(),
+        module: module.to_string(),
+    })
+}
+
+/// Shortening rules for paths outside std, set through [`Config::type_aliases`].
+///
+/// A rule matches a full type path, or every type under a module when its path ends in `::`:
+/// `tokio::sync::oneshot::error::` → `oneshot::` renders `RecvError` as `oneshot.RecvError`.
+///
+/// [`Config::type_aliases`]: crate::config::Config::type_aliases
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TypeAliases {
+    rules: BTreeMap<String, String>,
+}
+
+impl TypeAliases {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rules for common crates whose error types live in private modules.
+    pub fn popular() -> Self {
+        Self::new()
+            .with("serde_json::error::Error", "serde_json::Error")
+            .with("serde_yaml::error::Error", "serde_yaml::Error")
+            .with("reqwest::error::Error", "reqwest::Error")
+            .with("hyper::error::Error", "hyper::Error")
+            .with("url::parser::ParseError", "url::ParseError")
+            .with("uuid::error::Error", "uuid::Error")
+            .with("tokio::sync::oneshot::error::", "oneshot::")
+            .with("tokio::sync::mpsc::error::", "mpsc::")
+            .with("tokio::sync::broadcast::error::", "broadcast::")
+            .with("tokio::time::error::", "tokio::time::")
+            .with("tokio::task::join_set::", "tokio::task::")
+            .with("tokio::runtime::task::error::", "tokio::task::")
+    }
+
+    /// Adds a rule, returning the alias it replaced.
+    pub fn insert(&mut self, path: impl Into<String>, alias: impl Into<String>) -> Option<String> {
+        self.rules.insert(path.into(), alias.into())
+    }
+
+    pub fn with(mut self, path: impl Into<String>, alias: impl Into<String>) -> Self {
+        self.insert(path, alias);
+        self
+    }
+
+    /// The alias of `path`, from an exact rule or else the longest matching module rule.
+    pub fn resolve(&self, path: &str) -> Option<String> {
+        if let Some(alias) = self.rules.get(path) {
+            return Some(alias.clone());
+        }
+        self.rules
+            .iter()
+            .filter(|(prefix, _)| prefix.ends_with("::") && path.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(prefix, alias)| format!("{alias}{}", &path[prefix.len()..]))
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+}
+
 pub fn standardized_type_name<T: 'static>() -> String {
     process_type_name(std::any::type_name::<T>())
 }
@@ -7,28 +108,77 @@ pub fn standardized_type_name_of<T: ?Sized>(_: &T) -> String {
 }
 
 fn process_type_name(type_name: &str) -> String {
+    let config = config::current();
+    let type_name = normalize(type_name, &config.type_aliases);
+    match config.type_name_case {
+        Some(case) => recase(&type_name, case),
+        None => type_name,
+    }
+}
+
+/// Recases every identifier in `type_name`, leaving separators and generics in place.
+fn recase(type_name: &str, case: TagCase) -> String {
+    if case == TagCase::Preserve {
+        return type_name.to_string();
+    }
+
+    let mut output = String::with_capacity(type_name.len() + 8);
+    let mut identifier = String::new();
+    for c in type_name.chars().chain(std::iter::once('\0')) {
+        if c.is_alphanumeric() || c == '_' {
+            identifier.push(c);
+            continue;
+        }
+        if !identifier.is_empty() {
+            output.push_str(&case.apply(&identifier));
+            identifier.clear();
+        }
+        if c != '\0' {
+            output.push(c);
+        }
+    }
+    output
+}
+
+fn normalize(type_name: &str, aliases: &TypeAliases) -> String {
     if type_name.starts_with('[') && type_name.contains(';') {
         if let (Some(semicolon_pos), Some(bracket_pos)) =
             (type_name.find(';'), type_name.rfind(']'))
         {
             let element_type = &type_name[1..semicolon_pos].trim();
             let size = &type_name[semicolon_pos..bracket_pos];
-            return format!("[{}{}]", process_type_name(element_type), size);
+            return format!("[{}{}]", normalize(element_type, aliases), size);
         }
         return type_name.to_string();
     }
 
-    if type_name.starts_with('&') {
-        return format!("&{}", process_type_name(&type_name[1..]));
+    if let Some(element_type) = type_name
+        .strip_prefix('[')
+        .and_then(|type_name| type_name.strip_suffix(']'))
+    {
+        return format!("[{}]", normalize(element_type, aliases));
+    }
+
+    if let Some(referenced_type) = type_name.strip_prefix("&mut ") {
+        return format!("&mut {}", normalize(referenced_type, aliases));
+    }
+
+    if let Some(referenced_type) = type_name.strip_prefix('&') {
+        return format!("&{}", normalize(referenced_type, aliases));
     }
 
-    if type_name.starts_with("*const ") || type_name.starts_with("*mut ") {
-        let (pointer_type, pointed_type) = type_name.split_at(6);
-        return format!("{} {}", pointer_type, process_type_name(pointed_type));
+    for pointer in ["*const ", "*mut "] {
+        if let Some(pointed_type) = type_name.strip_prefix(pointer) {
+            return format!("{pointer}{}", normalize(pointed_type, aliases));
+        }
     }
 
-    if type_name.starts_with("dyn ") {
-        return format!("dyn {}", process_base_type(&type_name[4..]));
+    if let Some(bounds) = type_name.strip_prefix("dyn ") {
+        let bounds = bounds
+            .split(" + ")
+            .map(|bound| normalize(bound, aliases))
+            .collect::<Vec<_>>();
+        return format!("dyn {}", bounds.join(" + "));
     }
 
     if let (Some(generic_start), true) = (type_name.find('<'), type_name.ends_with('>')) {
@@ -37,20 +187,20 @@ fn process_type_name(type_name: &str) -> String {
 
         return format!(
             "{}<{}>",
-            process_base_type(base_type),
-            parse_generics(generic_part)
+            process_base_type(base_type, aliases),
+            parse_generics(generic_part, aliases)
         );
     }
 
-    process_base_type(type_name)
+    process_base_type(type_name, aliases)
 }
 
-fn parse_generics(generic_str: &str) -> String {
+fn parse_generics(generic_str: &str, aliases: &TypeAliases) -> String {
     let mut params = Vec::new();
     let mut bracket_depth = 0;
     let mut current_param_start = 0;
 
-    for (i, c) in generic_str.chars().enumerate() {
+    for (i, c) in generic_str.char_indices() {
         match c {
             '<' => bracket_depth += 1,
             '>' => bracket_depth -= 1,
@@ -69,36 +219,47 @@ fn parse_generics(generic_str: &str) -> String {
 
     params
         .iter()
-        .map(|param| process_type_name(param))
+        .map(|param| normalize(param, aliases))
         .collect::<Vec<_>>()
         .join(", ")
 }
 
-fn process_base_type(base_type: &str) -> String {
-    if base_type == "std::error::Error" {
-        return "Error".to_string();
+/// Types that render under a fixed name wherever they are declared, so `$type` doesn't change
+/// with the implementation crate of a collection or with internal std module moves.
+const CANONICAL_NAMES: &[(&str, &str)] = &[
+    ("std::error::Error", "Error"),
+    ("core::error::Error", "Error"),
+    ("core::fmt::Debug", "Debug"),
+    ("core::fmt::Display", "Display"),
+    ("core::any::Any", "Any"),
+    ("hashbrown::map::HashMap", "HashMap"),
+    ("hashbrown::set::HashSet", "HashSet"),
+    ("hashbrown::table::HashTable", "HashTable"),
+    ("hashbrown::HashMap", "HashMap"),
+    ("hashbrown::HashSet", "HashSet"),
+];
+
+fn process_base_type(base_type: &str, aliases: &TypeAliases) -> String {
+    if let Some((_, canonical)) = CANONICAL_NAMES.iter().find(|(path, _)| *path == base_type) {
+        return canonical.to_string();
     }
 
-    if base_type.contains("dyn ") {
-        if let Some(trait_part) = base_type.split("dyn ").nth(1) {
-            return format!("dyn {}", process_base_type(trait_part));
-        }
+    if let Some(alias) = aliases.resolve(base_type) {
+        return alias.replace("::", ".");
     }
 
-    match base_type {
-        "core::fmt::Debug" => return "Debug".to_string(),
-        "core::fmt::Display" => return "Display".to_string(),
-        "core::any::Any" => return "Any".to_string(),
-        _ => {}
+    if base_type.contains("dyn ")
+        && let Some(trait_part) = base_type.split("dyn ").nth(1)
+    {
+        return format!("dyn {}", process_base_type(trait_part, aliases));
     }
 
-    if base_type.starts_with("std::")
+    if (base_type.starts_with("std::")
         || base_type.starts_with("core::")
-        || base_type.starts_with("alloc::")
+        || base_type.starts_with("alloc::"))
+        && let Some(last_part) = base_type.split("::").last()
     {
-        if let Some(last_part) = base_type.split("::").last() {
-            return last_part.to_string();
-        }
+        return last_part.to_string();
     }
 
     base_type.replace("::", ".").to_string()
@@ -130,6 +291,126 @@ mod tests {
         assert_eq!(standardized_type_name::<String>(), "String");
     }
 
+    #[test]
+    fn test_reference_types() {
+        assert_eq!(standardized_type_name::<&mut String>(), "&mut String");
+        assert_eq!(standardized_type_name::<&&String>(), "&&String");
+        assert_eq!(standardized_type_name::<&mut &String>(), "&mut &String");
+        assert_eq!(
+            standardized_type_name::<&&mut Vec<String>>(),
+            "&&mut Vec<String>"
+        );
+        assert_eq!(
+            standardized_type_name::<Option<&mut [String]>>(),
+            "Option<&mut [String]>"
+        );
+        assert_eq!(standardized_type_name::<&mut dyn Debug>(), "&mut dyn Debug");
+    }
+
+    #[test]
+    fn test_raw_pointer_types() {
+        assert_eq!(standardized_type_name::<*const String>(), "*const String");
+        assert_eq!(standardized_type_name::<*mut String>(), "*mut String");
+        assert_eq!(standardized_type_name::<*const *mut u8>(), "*const *mut u8");
+        assert_eq!(
+            standardized_type_name::<*mut *const Vec<String>>(),
+            "*mut *const Vec<String>"
+        );
+        assert_eq!(
+            standardized_type_name::<Option<*mut my_module::MyStruct<i32>>>(),
+            format!(
+                "Option<*mut {}>",
+                standardized_type_name::<my_module::MyStruct<i32>>()
+            )
+        );
+        assert_eq!(standardized_type_name::<&*mut String>(), "&*mut String");
+    }
+
+    #[test]
+    fn test_canonical_names() {
+        assert_eq!(
+            process_type_name(
+                "hashbrown::map::HashMap<alloc::string::String, i32, foldhash::fast::RandomState>"
+            ),
+            "HashMap<String, i32, foldhash.fast.RandomState>"
+        );
+        assert_eq!(
+            process_type_name("hashbrown::set::HashSet<u8>"),
+            "HashSet<u8>"
+        );
+        assert_eq!(
+            process_type_name("std::collections::hash::map::HashMap<i32, i32>"),
+            "HashMap<i32, i32>"
+        );
+        assert_eq!(process_type_name("dyn core::error::Error"), "dyn Error");
+        assert_eq!(
+            standardized_type_name::<Box<dyn std::error::Error + Send + Sync>>(),
+            "Box<dyn Error + Send + Sync>"
+        );
+        assert_eq!(
+            process_type_name("alloc::boxed::Box<dyn core::error::Error + core::marker::Send>"),
+            "Box<dyn Error + Send>"
+        );
+    }
+
+    #[test]
+    fn test_type_aliases() {
+        let aliases =
+            TypeAliases::popular().with("my_app::errors::db::QueryError", "db::QueryError");
+
+        assert_eq!(
+            normalize("tokio::sync::oneshot::error::RecvError", &aliases),
+            "oneshot.RecvError"
+        );
+        assert_eq!(
+            normalize(
+                "tokio::sync::mpsc::error::SendError<serde_json::error::Error>",
+                &aliases
+            ),
+            "mpsc.SendError<serde_json.Error>"
+        );
+        assert_eq!(
+            normalize("my_app::errors::db::QueryError", &aliases),
+            "db.QueryError"
+        );
+        assert_eq!(
+            normalize("my_app::errors::db::OtherError", &aliases),
+            "my_app.errors.db.OtherError"
+        );
+        assert_eq!(normalize("alloc::vec::Vec<i32>", &aliases), "Vec<i32>");
+        assert_eq!(
+            normalize(
+                "tokio::sync::oneshot::error::RecvError",
+                &TypeAliases::new()
+            ),
+            "tokio.sync.oneshot.error.RecvError"
+        );
+    }
+
+    #[test]
+    fn test_type_name_case() {
+        let name = "my_app.user.NotFoundError<HTTPStatus, Vec<u8>>";
+
+        assert_eq!(recase(name, TagCase::Preserve), name);
+        assert_eq!(
+            recase(name, TagCase::Snake),
+            "my_app.user.not_found_error<http_status, vec<u8>>"
+        );
+        assert_eq!(
+            recase("my_app.user.NotFoundError", TagCase::ScreamingSnake),
+            "MY_APP.USER.NOT_FOUND_ERROR"
+        );
+        assert_eq!(
+            recase("my_app.user.NotFoundError", TagCase::Kebab),
+            "my-app.user.not-found-error"
+        );
+        assert_eq!(
+            recase("&[Utf8Error; 2]", TagCase::Snake),
+            "&[utf8_error; 2]"
+        );
+        assert_eq!(recase("IOError2Way", TagCase::Snake), "io_error2_way");
+    }
+
     #[test]
     fn test_std_collection_types() {
         assert_eq!(standardized_type_name::<Vec<i32>>(), "Vec<i32>");
@@ -227,6 +508,30 @@ mod tests {
         assert_eq!(standardized_type_name::<[String; 3]>(), "[String; 3]");
     }
 
+    #[test]
+    fn test_origin() {
+        assert_eq!(
+            origin::<std::io::Error>(),
+            Some(Origin {
+                crate_name: "std".to_string(),
+                module: "std::io::error".to_string(),
+            })
+        );
+        assert_eq!(
+            origin::<Vec<String>>().map(|origin| origin.module),
+            Some("alloc::vec".to_string())
+        );
+        assert_eq!(
+            parse_origin("my_app::services::user::UserError"),
+            Some(Origin {
+                crate_name: "my_app".to_string(),
+                module: "my_app::services::user".to_string(),
+            })
+        );
+        assert_eq!(origin::<i32>(), None);
+        assert_eq!(origin::<dyn Debug>(), None);
+    }
+
     #[test]
     fn test_type_format_of_values() {
         let value = 42i32;
@@ -0,0 +1,159 @@
+//! Two serialized views of the same error: the full [`Internal`] one for logs and services
+//! you own, and a sanitized [`Public`] one for API clients. Both keep the `AnyError` shape, so
+//! clients deserialize either with `AnyError`.
+
+use std::{mem, sync::Arc};
+
+use serde::{Serialize, Serializer};
+
+use crate::{
+    AnyError, CODE_EXTENSION, ContextDetails, config,
+    mapping::{CodeMapping, GlobalCodes},
+    mask::MaskPolicy,
+};
+
+/// Serializes every detail, the same as the error itself.
+#[derive(Debug, Clone, Copy)]
+pub struct Internal<'a>(pub &'a AnyError);
+
+/// Serializes [`AnyError::to_public`].
+#[derive(Debug, Clone, Copy)]
+pub struct Public<'a>(pub &'a AnyError);
+
+impl Serialize for Internal<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl Serialize for Public<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.to_public().serialize(serializer)
+    }
+}
+
+impl AnyError {
+    pub fn internal(&self) -> Internal<'_> {
+        Internal(self)
+    }
+
+    pub fn public(&self) -> Public<'_> {
+        Public(self)
+    }
+
+    /// A copy for clients, see [`AnyError::to_public_with`]. Masked by the configured
+    /// [`public_mask_policy`](crate::config::Config::public_mask_policy), or if there is none
+    /// by exposing only the `$type`s registered in the [code mapping](crate::mapping).
+    pub fn to_public(&self) -> AnyError {
+        match &config::current().public_mask_policy {
+            Some(policy) => self.to_public_with(policy),
+            None => self.to_public_with(&MaskPolicy::custom(|r#type| {
+                GlobalCodes.codes_for(r#type).is_some()
+            })),
+        }
+    }
+
+    /// A copy for clients, masked by `policy` and, with the `redact` feature, scrubbed by the
+    /// configured redactor or `Redactor::common`. Only the `$type`, message, help, docs URL,
+    /// category, retry hints and [`CODE_EXTENSION`] of each layer are kept; locations,
+    /// traces, origins, snippets, metadata and the other extensions are dropped.
+    pub fn to_public_with(&self, policy: &MaskPolicy) -> AnyError {
+        let mut public = self.masked(policy);
+        #[cfg(feature = "redact")]
+        match config::current().redactor.as_deref() {
+            Some(redactor) => redactor.redact(&mut public),
+            None => crate::redact::COMMON.redact(&mut public),
+        }
+
+        let mut stack = vec![&mut public];
+        while let Some(error) = stack.pop() {
+            let context = error.make_context_mut();
+            let mut details = mem::take(&mut *context.details);
+            let code = details.extensions.remove(CODE_EXTENSION);
+            *context.details = ContextDetails {
+                help: details.help,
+                docs_url: details.docs_url,
+                category: details.category,
+                retryable: details.retryable,
+                retry_after: details.retry_after,
+                extensions: code
+                    .map(|code| [(CODE_EXTENSION.to_string(), code)].into())
+                    .unwrap_or_default(),
+                inner_errors: details.inner_errors,
+                ..ContextDetails::default()
+            };
+            stack.extend(
+                context
+                    .inner_error
+                    .iter_mut()
+                    .chain(&mut context.details.inner_errors)
+                    .map(Arc::make_mut),
+            );
+        }
+        public
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mask::{MASKED_MESSAGE, MASKED_TYPE};
+
+    #[test]
+    fn test_views() {
+        let error = AnyError::builder()
+            .type_name("billing.CardDeclined")
+            .message("card declined")
+            .code("PAY042")
+            .data("customerId", "cus_9f2A")
+            .source(AnyError::msg("issuer returned 05"))
+            .build()
+            .with_help("use another card")
+            .with_extension("gateway", "adyen");
+
+        let internal = serde_json::to_value(error.internal()).unwrap();
+        assert_eq!(internal, serde_json::to_value(&error).unwrap());
+        assert!(internal["context"].get("location").is_some());
+
+        let public = serde_json::to_value(error.public()).unwrap();
+        assert_eq!(public["$type"], MASKED_TYPE);
+        assert_eq!(public["context"]["message"], MASKED_MESSAGE);
+        assert!(public["context"]["innerError"].is_null());
+        assert!(public["context"].get("help").is_none());
+
+        let public =
+            serde_json::to_value(error.to_public_with(&MaskPolicy::custom(|_| true))).unwrap();
+        let context = &public["context"];
+        assert_eq!(public["$type"], "billing.CardDeclined");
+        assert_eq!(context["help"], "use another card");
+        assert_eq!(
+            context["extensions"],
+            serde_json::json!({ "code": "PAY042" })
+        );
+        assert!(context.get("location").is_none());
+        assert!(context.get("data").is_none());
+        assert_eq!(
+            context["innerError"]["context"]["message"],
+            "issuer returned 05"
+        );
+        assert!(context["innerError"]["context"].get("location").is_none());
+
+        let decoded: AnyError = serde_json::from_value(public).unwrap();
+        assert_eq!(decoded, error);
+
+        let public = error.to_public_with(&MaskPolicy::allow(["billing."]));
+        assert_eq!(public.chain_types(), ["billing.CardDeclined", MASKED_TYPE]);
+    }
+
+    #[cfg(feature = "redact")]
+    #[test]
+    fn test_redacts_without_a_redactor() {
+        let error = AnyError::msg("no account for jane@example.com");
+        let public = error.to_public_with(&MaskPolicy::custom(|_| true));
+
+        assert_eq!(
+            public.message(),
+            format!("no account for {}", crate::redact::REDACTED)
+        );
+    }
+}
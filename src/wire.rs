@@ -0,0 +1,286 @@
+//! A compact binary encoding for transports where JSON is too verbose.
+//!
+//! Layout, with every length and index a LEB128 varint:
+//!
+//! ```text
+//! "LE" version
+//! string_count (len utf8)*          -- each distinct type and message once
+//! node_count (type message causes details)*
+//! ```
+//!
+//! Nodes are listed outermost first with causes depth first, as in
+//! [`AnyError::frames`](crate::AnyError::frames); `causes` is how many of the following
+//! subtrees are direct causes of the node. `details` is len + JSON, 0 if empty.
+
+use std::{collections::HashMap, fmt};
+
+use crate::{AnyError, AnyErrorContext, ContextDetails};
+
+const MAGIC: &[u8; 2] = b"LE";
+const VERSION: u8 = 2;
+
+#[derive(Debug)]
+pub enum DecodeError {
+    UnexpectedEof,
+    BadMagic,
+    UnsupportedVersion(u8),
+    VarintOverflow,
+    InvalidUtf8,
+    StringIndex(u64),
+    NoErrors,
+    /// The cause counts don't describe a single tree.
+    Causes,
+    TrailingBytes(usize),
+    Details(serde_json::Error),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedEof => write!(f, "unexpected end of input"),
+            Self::BadMagic => write!(f, "not a liberror binary encoding"),
+            Self::UnsupportedVersion(version) => write!(f, "unsupported version {version}"),
+            Self::VarintOverflow => write!(f, "varint does not fit in 64 bits"),
+            Self::InvalidUtf8 => write!(f, "string is not valid UTF-8"),
+            Self::StringIndex(index) => write!(f, "string index {index} out of range"),
+            Self::NoErrors => write!(f, "encoding contains no errors"),
+            Self::Causes => write!(f, "cause counts do not form a single tree"),
+            Self::TrailingBytes(count) => write!(f, "{count} trailing bytes"),
+            Self::Details(error) => write!(f, "invalid details: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Details(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl AnyError {
+    /// Encodes the chain in the [`wire`](crate::wire) format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut strings = Vec::<&str>::new();
+        let mut indices = HashMap::<&str, usize>::new();
+        let mut intern = |value| {
+            *indices.entry(value).or_insert_with(|| {
+                strings.push(value);
+                strings.len() - 1
+            })
+        };
+
+        let layers = self.split_layers().collect::<Vec<_>>();
+        let mut nodes = Vec::new();
+        for (layer, causes) in &layers {
+            let details = serde_json::to_vec(&layer.context.details).unwrap_or_default();
+            let details = if details == b"{}" {
+                Vec::new()
+            } else {
+                details
+            };
+            nodes.push((
+                intern(&layer.r#type),
+                intern(layer.message()),
+                *causes,
+                details,
+            ));
+        }
+
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(VERSION);
+        write_varint(&mut bytes, strings.len() as u64);
+        for string in strings {
+            write_varint(&mut bytes, string.len() as u64);
+            bytes.extend_from_slice(string.as_bytes());
+        }
+        write_varint(&mut bytes, nodes.len() as u64);
+        for (r#type, message, causes, details) in nodes {
+            write_varint(&mut bytes, r#type as u64);
+            write_varint(&mut bytes, message as u64);
+            write_varint(&mut bytes, causes as u64);
+            write_varint(&mut bytes, details.len() as u64);
+            bytes.extend_from_slice(&details);
+        }
+        bytes
+    }
+
+    /// Decodes an error written by [`AnyError::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let mut reader = Reader { bytes };
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(DecodeError::BadMagic);
+        }
+        match reader.take(1)?[0] {
+            VERSION => {}
+            version => return Err(DecodeError::UnsupportedVersion(version)),
+        }
+
+        let mut strings = Vec::new();
+        for _ in 0..reader.varint()? {
+            let len = reader.len()?;
+            let string =
+                std::str::from_utf8(reader.take(len)?).map_err(|_| DecodeError::InvalidUtf8)?;
+            strings.push(string);
+        }
+        let string = |index: u64| {
+            usize::try_from(index)
+                .ok()
+                .and_then(|index| strings.get(index))
+                .map(|string| string.to_string())
+                .ok_or(DecodeError::StringIndex(index))
+        };
+
+        let mut nodes = Vec::new();
+        for _ in 0..reader.varint()? {
+            let r#type = string(reader.varint()?)?;
+            let message = string(reader.varint()?)?;
+            let causes = reader.len()?;
+            let len = reader.len()?;
+            let details = match reader.take(len)? {
+                [] => ContextDetails::default(),
+                json => serde_json::from_slice(json).map_err(DecodeError::Details)?,
+            };
+            let layer = AnyError {
+                r#type,
+                context: AnyErrorContext {
+                    message,
+                    inner_error: None,
+                    details: Box::new(details),
+                },
+            };
+            nodes.push((layer, causes));
+        }
+        if !reader.bytes.is_empty() {
+            return Err(DecodeError::TrailingBytes(reader.bytes.len()));
+        }
+        if nodes.is_empty() {
+            return Err(DecodeError::NoErrors);
+        }
+
+        AnyError::join_layers(nodes).ok_or(DecodeError::Causes)
+    }
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        if self.bytes.len() < len {
+            return Err(DecodeError::UnexpectedEof);
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn varint(&mut self) -> Result<u64, DecodeError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1)?[0];
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(DecodeError::VarintOverflow)
+    }
+
+    fn len(&mut self) -> Result<usize, DecodeError> {
+        usize::try_from(self.varint()?).map_err(|_| DecodeError::UnexpectedEof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::category::Category;
+
+    #[test]
+    fn test_round_trip() {
+        let mut error = AnyError::msg("connection refused")
+            .with_type("io.Error")
+            .with_retryable(true);
+        error.wrap_with("loading user");
+        let error = error
+            .with_category(Category::Unavailable)
+            .with_extension("userId", 42);
+
+        let decoded = AnyError::from_bytes(&error.to_bytes()).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&decoded).unwrap(),
+            serde_json::to_value(&error).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_round_trip_causes() {
+        let mut nested = AnyError::msg("b").with_causes([AnyError::msg("b1"), AnyError::msg("b2")]);
+        nested.wrap_with("loading b");
+        let error = AnyError::msg("2 failed").with_causes([AnyError::msg("a"), nested]);
+
+        let decoded = AnyError::from_bytes(&error.to_bytes()).unwrap();
+
+        assert_eq!(decoded.to_string(), error.to_string());
+        assert_eq!(decoded.causes().count(), 2);
+        assert_eq!(
+            serde_json::to_value(&decoded).unwrap(),
+            serde_json::to_value(&error).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_repeated_strings_stored_once() {
+        let mut error = AnyError::from_parts("app.timeout", "timed out", None);
+        for _ in 0..10 {
+            error = AnyError::from_parts("app.timeout", "timed out", Some(error));
+        }
+
+        let bytes = error.to_bytes();
+        assert_eq!(
+            bytes
+                .windows("app.timeout".len())
+                .filter(|window| *window == b"app.timeout")
+                .count(),
+            1
+        );
+        assert!(bytes.len() < serde_json::to_vec(&error).unwrap().len() / 10);
+    }
+
+    #[test]
+    fn test_rejects_malformed() {
+        let bytes = AnyError::msg("boom").to_bytes();
+
+        assert!(matches!(
+            AnyError::from_bytes(b"{}"),
+            Err(DecodeError::BadMagic)
+        ));
+        assert!(matches!(
+            AnyError::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(DecodeError::UnexpectedEof)
+        ));
+        assert!(matches!(
+            AnyError::from_bytes(&[bytes.as_slice(), &[0]].concat()),
+            Err(DecodeError::TrailingBytes(1))
+        ));
+
+        // One node claiming a cause that isn't there.
+        assert!(matches!(
+            AnyError::from_bytes(&[b'L', b'E', VERSION, 1, 1, b'x', 1, 0, 0, 1, 0]),
+            Err(DecodeError::Causes)
+        ));
+    }
+}